@@ -0,0 +1,32 @@
+use {
+    crate::{commands::Command, output::OutputFormat},
+    clap::Parser,
+};
+
+/// Scilla — an interactive Solana CLI. Run without a subcommand to start the
+/// interactive prompt; any required argument left out of a subcommand is
+/// prompted for.
+#[derive(Debug, Parser)]
+#[command(name = "scilla", version)]
+pub struct Cli {
+    /// Output format for command results
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Table)]
+    pub output: OutputFormat,
+
+    /// Send transactions without confirming the simulation preview
+    #[arg(long, short = 'y', global = true)]
+    pub yes: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, clap::CommandFactory};
+
+    #[test]
+    fn test_cli() {
+        Cli::command().debug_assert();
+    }
+}
@@ -1,42 +1,257 @@
 use {
     crate::{
         commands::CommandFlow,
+        constants::{DEFAULT_BATCH_CONCURRENCY, MAX_MULTIPLE_ACCOUNTS, MAX_SIGNATURE_STATUSES},
         context::ScillaContext,
-        misc::helpers::{bincode_deserialize, lamports_to_sol},
-        prompt::prompt_input_data,
-        ui::{print_error, show_spinner},
+        error::ScillaResult,
+        misc::{
+            batch::{Batch, pack_instructions, pin_batch_tx_args},
+            compute_budget::{ComputeBudgetArgs, ComputeUnitPrice, resolve_compute_unit_price},
+            helpers::{
+                SolAmount, TxArgs, TxOutcome, build_and_send_tx, build_tx, check_minimum_balance,
+                decode_nonce_data, lamports_to_sol, memo_instruction, read_keypair_from_path,
+            },
+            simulation::fee_for_message,
+        },
+        output::CommandOutput,
+        prompt::{prompt_input_data, prompt_keypair_path, prompt_select_data},
+        ui::{print_status, report_error, show_spinner, suspend_spinner},
     },
-    anyhow::bail,
-    comfy_table::{Cell, Table, presets::UTF8_FULL},
+    anyhow::{Context, anyhow, bail},
+    clap::{Args, Subcommand, ValueEnum},
+    comfy_table::{Attribute, Cell, Table, presets::UTF8_FULL},
     console::style,
-    inquire::Select,
-    solana_nonce::versions::Versions,
+    futures::{StreamExt, TryStreamExt, stream},
+    inquire::Confirm,
+    serde::{Deserialize, Serialize},
+    solana_account::Account,
+    solana_instruction::Instruction,
+    solana_keypair::Signer,
+    solana_message::{Hash, Message, VersionedMessage},
+    solana_nonce::state::{Data as NonceData, State as NonceState},
     solana_pubkey::Pubkey,
     solana_rpc_client_api::config::{RpcLargestAccountsConfig, RpcLargestAccountsFilter},
-    std::fmt,
+    solana_signature::Signature,
+    solana_system_interface::instruction::{
+        advance_nonce_account, authorize_nonce_account, create_nonce_account, transfer,
+        withdraw_nonce_account,
+    },
+    solana_transaction::versioned::VersionedTransaction,
+    std::{
+        collections::{HashMap, HashSet},
+        fmt, fs,
+        num::NonZeroUsize,
+        path::{Path, PathBuf},
+        str::FromStr,
+        sync::Mutex,
+    },
 };
 
 /// Commands related to wallet or account management
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Subcommand)]
 pub enum AccountCommand {
-    FetchAccount,
-    Balance,
-    Transfer,
+    /// Fetch an account
+    FetchAccount(FetchAccountArgs),
+    /// Check SOL balance
+    Balance(BalanceArgs),
+    /// Send SOL to another wallet
+    Transfer(TransferArgs),
+    /// Send SOL to many recipients listed in a CSV file
+    BatchTransfer(BatchTransferArgs),
+    /// Request devnet/testnet SOL
     Airdrop,
-    LargestAccounts,
-    NonceAccount,
+    /// See the biggest accounts on the cluster
+    LargestAccounts(LargestAccountsArgs),
+    /// Inspect a durable nonce account
+    NonceAccount(NonceAccountArgs),
+    /// Create and initialize a durable nonce account
+    CreateNonceAccount(CreateNonceAccountArgs),
+    /// Advance the stored nonce of a nonce account
+    AdvanceNonceAccount(AdvanceNonceAccountArgs),
+    /// Change the authority of a nonce account
+    AuthorizeNonceAccount(AuthorizeNonceAccountArgs),
+    /// Withdraw SOL from a nonce account
+    WithdrawFromNonceAccount(WithdrawFromNonceAccountArgs),
+    #[command(skip)]
     GoBack,
 }
 
+#[derive(Debug, Clone, Default, Args)]
+pub struct FetchAccountArgs {
+    /// Account address
+    #[arg(long)]
+    pub pubkey: Option<Pubkey>,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct BalanceArgs {
+    /// Account address
+    #[arg(long)]
+    pub pubkey: Option<Pubkey>,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct TransferArgs {
+    /// Recipient address
+    #[arg(long)]
+    pub recipient: Option<Pubkey>,
+    /// Amount to send in SOL, or ALL to send the whole balance minus the fee
+    #[arg(long)]
+    pub amount: Option<TransferAmount>,
+    /// Memo to attach to the transfer
+    #[arg(long)]
+    pub memo: Option<String>,
+    /// Keypair path of a separate account paying the transaction fee
+    #[arg(long, conflicts_with = "fee_payer")]
+    pub fee_payer_keypair: Option<PathBuf>,
+    /// Send even if the recipient account does not exist yet
+    #[arg(long)]
+    pub allow_unfunded_recipient: bool,
+    #[command(flatten)]
+    pub tx: TxArgs,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct BatchTransferArgs {
+    /// CSV file with `recipient,amount[,memo]` rows, amounts in SOL
+    #[arg(long)]
+    pub file: Option<PathBuf>,
+    /// CSV file recording the signature of every transfer, used to resume an
+    /// interrupted run (defaults to `<file>.results.csv`)
+    #[arg(long)]
+    pub results: Option<PathBuf>,
+    /// Number of transactions in flight at once
+    #[arg(long)]
+    pub concurrency: Option<NonZeroUsize>,
+    #[command(flatten, next_help_heading = "Transaction Options")]
+    pub compute_budget: ComputeBudgetArgs,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct LargestAccountsArgs {
+    /// Filter accounts by circulation status
+    #[arg(long, value_enum)]
+    pub filter: Option<LargestAccountsFilter>,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct NonceAccountArgs {
+    /// Nonce account address
+    #[arg(long)]
+    pub pubkey: Option<Pubkey>,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct CreateNonceAccountArgs {
+    /// New nonce account keypair path
+    #[arg(long)]
+    pub nonce_account: Option<PathBuf>,
+    /// Nonce authority address (defaults to the fee payer)
+    #[arg(long)]
+    pub authority: Option<Pubkey>,
+    /// Amount to fund the nonce account with in SOL
+    #[arg(long)]
+    pub amount: Option<SolAmount>,
+    #[command(flatten)]
+    pub tx: TxArgs,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct AdvanceNonceAccountArgs {
+    /// Nonce account address
+    #[arg(long)]
+    pub nonce_account: Option<Pubkey>,
+    /// Nonce authority keypair path
+    #[arg(long)]
+    pub authority: Option<PathBuf>,
+    #[command(flatten)]
+    pub tx: TxArgs,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct AuthorizeNonceAccountArgs {
+    /// Nonce account address
+    #[arg(long)]
+    pub nonce_account: Option<Pubkey>,
+    /// Current nonce authority keypair path
+    #[arg(long)]
+    pub authority: Option<PathBuf>,
+    /// New nonce authority address
+    #[arg(long)]
+    pub new_authority: Option<Pubkey>,
+    #[command(flatten)]
+    pub tx: TxArgs,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct WithdrawFromNonceAccountArgs {
+    /// Nonce account address
+    #[arg(long)]
+    pub nonce_account: Option<Pubkey>,
+    /// Nonce authority keypair path
+    #[arg(long)]
+    pub authority: Option<PathBuf>,
+    /// Recipient address
+    #[arg(long)]
+    pub recipient: Option<Pubkey>,
+    /// Amount to withdraw in SOL
+    #[arg(long)]
+    pub amount: Option<SolAmount>,
+    #[command(flatten)]
+    pub tx: TxArgs,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum LargestAccountsFilter {
+    All,
+    Circulating,
+    NonCirculating,
+}
+
+impl fmt::Display for LargestAccountsFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let filter = match self {
+            LargestAccountsFilter::All => "All",
+            LargestAccountsFilter::Circulating => "Circulating",
+            LargestAccountsFilter::NonCirculating => "Non-Circulating",
+        };
+        write!(f, "{filter}")
+    }
+}
+
+/// Amount of a SOL transfer
+#[derive(Debug, Clone, Copy)]
+pub enum TransferAmount {
+    /// The whole balance, minus the fee if the sender pays it
+    All,
+    Sol(SolAmount),
+}
+
+impl FromStr for TransferAmount {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().eq_ignore_ascii_case("all") {
+            return Ok(TransferAmount::All);
+        }
+        s.parse().map(TransferAmount::Sol)
+    }
+}
+
 impl AccountCommand {
     pub fn spinner_msg(&self) -> &'static str {
         match self {
-            AccountCommand::FetchAccount => "Fetching account…",
-            AccountCommand::Balance => "Checking SOL balance…",
-            AccountCommand::Transfer => "Sending SOL…",
+            AccountCommand::FetchAccount(_) => "Fetching account…",
+            AccountCommand::Balance(_) => "Checking SOL balance…",
+            AccountCommand::Transfer(_) => "Sending SOL…",
+            AccountCommand::BatchTransfer(_) => "Sending batch transfers…",
             AccountCommand::Airdrop => "Requesting SOL on devnet/testnet…",
-            AccountCommand::LargestAccounts => "Fetching largest accounts on the cluster…",
-            AccountCommand::NonceAccount => "Inspecting or managing durable nonces…",
+            AccountCommand::LargestAccounts(_) => "Fetching largest accounts on the cluster…",
+            AccountCommand::NonceAccount(_) => "Inspecting or managing durable nonces…",
+            AccountCommand::CreateNonceAccount(_) => "Creating nonce account…",
+            AccountCommand::AdvanceNonceAccount(_) => "Advancing nonce…",
+            AccountCommand::AuthorizeNonceAccount(_) => "Changing nonce authority…",
+            AccountCommand::WithdrawFromNonceAccount(_) => "Withdrawing SOL from nonce account…",
             AccountCommand::GoBack => "Going back…",
         }
     }
@@ -45,12 +260,17 @@ impl AccountCommand {
 impl fmt::Display for AccountCommand {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let command = match self {
-            AccountCommand::FetchAccount => "Fetch account",
-            AccountCommand::Balance => "Check balance",
-            AccountCommand::Transfer => "Transfer SOL",
+            AccountCommand::FetchAccount(_) => "Fetch account",
+            AccountCommand::Balance(_) => "Check balance",
+            AccountCommand::Transfer(_) => "Transfer SOL",
+            AccountCommand::BatchTransfer(_) => "Batch transfer SOL",
             AccountCommand::Airdrop => "Request airdrop",
-            AccountCommand::LargestAccounts => "View largest accounts",
-            AccountCommand::NonceAccount => "View nonce account",
+            AccountCommand::LargestAccounts(_) => "View largest accounts",
+            AccountCommand::NonceAccount(_) => "View nonce account",
+            AccountCommand::CreateNonceAccount(_) => "Create nonce account",
+            AccountCommand::AdvanceNonceAccount(_) => "Advance nonce account",
+            AccountCommand::AuthorizeNonceAccount(_) => "Authorize nonce account",
+            AccountCommand::WithdrawFromNonceAccount(_) => "Withdraw from nonce account",
             AccountCommand::GoBack => "Go back",
         };
         write!(f, "{command}")
@@ -58,116 +278,404 @@ impl fmt::Display for AccountCommand {
 }
 
 impl AccountCommand {
-    pub async fn process_command(&self, ctx: &ScillaContext) -> CommandFlow<()> {
+    pub async fn process_command(&self, ctx: &ScillaContext) -> ScillaResult<()> {
         match self {
-            AccountCommand::FetchAccount => {
-                let pubkey: Pubkey = prompt_input_data("Enter Pubkey:");
-                show_spinner(self.spinner_msg(), fetch_acc_data(ctx, &pubkey)).await;
+            AccountCommand::FetchAccount(args) => {
+                let pubkey: Pubkey = args
+                    .pubkey
+                    .unwrap_or_else(|| prompt_input_data("Enter Pubkey:"));
+                let account =
+                    show_spinner(self.spinner_msg(), fetch_acc_data(ctx, &pubkey)).await?;
+                ctx.output_format().render(&account)?;
             }
-            AccountCommand::Balance => {
-                let pubkey: Pubkey = prompt_input_data("Enter Pubkey :");
-                show_spinner(self.spinner_msg(), fetch_account_balance(ctx, &pubkey)).await;
+            AccountCommand::Balance(args) => {
+                let pubkey: Pubkey = args
+                    .pubkey
+                    .unwrap_or_else(|| prompt_input_data("Enter Pubkey :"));
+                let balance =
+                    show_spinner(self.spinner_msg(), fetch_account_balance(ctx, &pubkey)).await?;
+                ctx.output_format().render(&balance)?;
             }
-            AccountCommand::Transfer => {
-                // show_spinner(self.spinner_msg(), todo!()).await?;
+            AccountCommand::Transfer(args) => {
+                let interactive = ctx.is_interactive();
+                let recipient: Pubkey = args
+                    .recipient
+                    .unwrap_or_else(|| prompt_input_data("Enter Recipient Address:"));
+                let amount: TransferAmount = args.amount.unwrap_or_else(|| {
+                    prompt_input_data("Enter Amount to Transfer (SOL, or ALL):")
+                });
+                let memo = match &args.memo {
+                    Some(memo) => Some(memo.clone()),
+                    None if interactive => {
+                        let memo: String = prompt_input_data("Enter Memo (leave empty for none):");
+                        Some(memo.trim().to_string()).filter(|memo| !memo.is_empty())
+                    }
+                    None => None,
+                };
+                // Interactive users are asked instead of having to pass the flag
+                let allow_unfunded_recipient = (args.allow_unfunded_recipient || !interactive)
+                    .then_some(args.allow_unfunded_recipient);
+
+                let transfer = show_spinner(
+                    self.spinner_msg(),
+                    process_transfer(
+                        ctx,
+                        &recipient,
+                        amount,
+                        memo.as_deref(),
+                        args.fee_payer_keypair.as_ref(),
+                        allow_unfunded_recipient,
+                        &args.tx,
+                    ),
+                )
+                .await?;
+                ctx.output_format().render(&transfer)?;
+            }
+            AccountCommand::BatchTransfer(args) => {
+                let file: PathBuf = args
+                    .file
+                    .clone()
+                    .unwrap_or_else(|| prompt_input_data("Enter CSV File Path:"));
+                let results_path = args
+                    .results
+                    .clone()
+                    .unwrap_or_else(|| default_results_path(&file));
+                let concurrency = args
+                    .concurrency
+                    .map_or(DEFAULT_BATCH_CONCURRENCY, NonZeroUsize::get);
+
+                let rows = read_distribution_file(&file).map_err(report_error)?;
+                let plan = show_spinner(
+                    "Preparing batch transfers…",
+                    prepare_batch_transfer(
+                        ctx,
+                        rows,
+                        &results_path,
+                        &args.compute_budget,
+                        concurrency,
+                    ),
+                )
+                .await?;
+                plan.print(ctx);
+
+                if plan.batches.is_empty() {
+                    print_status(
+                        ctx,
+                        style("Every transfer has already been confirmed").green(),
+                    );
+                    if ctx.output_format().is_json() {
+                        let output = BatchTransferOutput::new(&plan.results, Vec::new());
+                        ctx.output_format().render(&output)?;
+                    }
+                    return Ok(CommandFlow::Process(()));
+                }
+                if !ctx.skip_confirmation()
+                    && !Confirm::new(&format!("Send {} transactions?", plan.batches.len()))
+                        .with_default(false)
+                        .prompt()
+                        .unwrap_or(false)
+                {
+                    return Err(report_error(anyhow!("Batch transfer aborted")));
+                }
+
+                let summary = show_spinner(
+                    self.spinner_msg(),
+                    process_batch_transfer(ctx, plan, concurrency),
+                )
+                .await?;
+                ctx.output_format().render(&summary)?;
+
+                if summary.unconfirmed > 0 {
+                    let err = anyhow!(
+                        "{} transfers were not confirmed. Re-run the command to resume",
+                        summary.unconfirmed
+                    );
+                    return Err(report_error(err));
+                }
             }
             AccountCommand::Airdrop => {
-                show_spinner(self.spinner_msg(), request_sol_airdrop(ctx)).await;
+                let airdrop = show_spinner(self.spinner_msg(), request_sol_airdrop(ctx)).await?;
+                ctx.output_format().render(&airdrop)?;
+            }
+            AccountCommand::LargestAccounts(args) => {
+                let filter = args.filter.unwrap_or_else(|| {
+                    prompt_select_data(
+                        "Filter accounts by:",
+                        vec![
+                            LargestAccountsFilter::All,
+                            LargestAccountsFilter::Circulating,
+                            LargestAccountsFilter::NonCirculating,
+                        ],
+                    )
+                });
+                let largest_accounts =
+                    show_spinner(self.spinner_msg(), fetch_largest_accounts(ctx, filter)).await?;
+                ctx.output_format().render(&largest_accounts)?;
             }
-            AccountCommand::LargestAccounts => {
-                show_spinner(self.spinner_msg(), fetch_largest_accounts(ctx)).await;
+            AccountCommand::NonceAccount(args) => {
+                let pubkey: Pubkey = args
+                    .pubkey
+                    .unwrap_or_else(|| prompt_input_data("Enter nonce account pubkey:"));
+                let nonce_account =
+                    show_spinner(self.spinner_msg(), fetch_nonce_account(ctx, &pubkey)).await?;
+                ctx.output_format().render(&nonce_account)?;
             }
-            AccountCommand::NonceAccount => {
-                let pubkey: Pubkey = prompt_input_data("Enter nonce account pubkey:");
-                show_spinner(self.spinner_msg(), fetch_nonce_account(ctx, &pubkey)).await;
+            AccountCommand::CreateNonceAccount(args) => {
+                let nonce_keypair_path = args.nonce_account.clone().unwrap_or_else(|| {
+                    prompt_keypair_path("Enter Nonce Account Keypair Path:", ctx)
+                });
+                let amount: SolAmount = args
+                    .amount
+                    .unwrap_or_else(|| prompt_input_data("Enter amount to fund (in SOL):"));
+                let authority = args.authority.unwrap_or(*ctx.pubkey());
+
+                let created = show_spinner(
+                    self.spinner_msg(),
+                    process_create_nonce_account(
+                        ctx,
+                        &nonce_keypair_path,
+                        &authority,
+                        amount.to_lamports(),
+                        &args.tx,
+                    ),
+                )
+                .await?;
+                ctx.output_format().render(&created)?;
+            }
+            AccountCommand::AdvanceNonceAccount(args) => {
+                let nonce_pubkey: Pubkey = args
+                    .nonce_account
+                    .unwrap_or_else(|| prompt_input_data("Enter nonce account pubkey:"));
+                let authority_keypair_path = args.authority.clone().unwrap_or_else(|| {
+                    prompt_keypair_path("Enter Nonce Authority Keypair Path:", ctx)
+                });
+
+                let advanced = show_spinner(
+                    self.spinner_msg(),
+                    process_advance_nonce_account(
+                        ctx,
+                        &nonce_pubkey,
+                        &authority_keypair_path,
+                        &args.tx,
+                    ),
+                )
+                .await?;
+                ctx.output_format().render(&advanced)?;
+            }
+            AccountCommand::AuthorizeNonceAccount(args) => {
+                let nonce_pubkey: Pubkey = args
+                    .nonce_account
+                    .unwrap_or_else(|| prompt_input_data("Enter nonce account pubkey:"));
+                let authority_keypair_path = args.authority.clone().unwrap_or_else(|| {
+                    prompt_keypair_path("Enter Current Nonce Authority Keypair Path:", ctx)
+                });
+                let new_authority: Pubkey = args
+                    .new_authority
+                    .unwrap_or_else(|| prompt_input_data("Enter New Nonce Authority Pubkey:"));
+
+                let authorized = show_spinner(
+                    self.spinner_msg(),
+                    process_authorize_nonce_account(
+                        ctx,
+                        &nonce_pubkey,
+                        &authority_keypair_path,
+                        &new_authority,
+                        &args.tx,
+                    ),
+                )
+                .await?;
+                ctx.output_format().render(&authorized)?;
+            }
+            AccountCommand::WithdrawFromNonceAccount(args) => {
+                let nonce_pubkey: Pubkey = args
+                    .nonce_account
+                    .unwrap_or_else(|| prompt_input_data("Enter nonce account pubkey:"));
+                let authority_keypair_path = args.authority.clone().unwrap_or_else(|| {
+                    prompt_keypair_path("Enter Nonce Authority Keypair Path:", ctx)
+                });
+                let recipient: Pubkey = args
+                    .recipient
+                    .unwrap_or_else(|| prompt_input_data("Enter Recipient Address:"));
+                let amount: SolAmount = args
+                    .amount
+                    .unwrap_or_else(|| prompt_input_data("Enter Amount to Withdraw (SOL):"));
+
+                let withdrawn = show_spinner(
+                    self.spinner_msg(),
+                    process_withdraw_from_nonce_account(
+                        ctx,
+                        &nonce_pubkey,
+                        &authority_keypair_path,
+                        &recipient,
+                        amount.to_lamports(),
+                        &args.tx,
+                    ),
+                )
+                .await?;
+                ctx.output_format().render(&withdrawn)?;
             }
             AccountCommand::GoBack => {
-                return CommandFlow::GoBack;
+                return Ok(CommandFlow::GoBack);
             }
         }
 
-        CommandFlow::Process(())
+        Ok(CommandFlow::Process(()))
     }
 }
 
-async fn request_sol_airdrop(ctx: &ScillaContext) -> anyhow::Result<()> {
-    let sig = ctx.rpc().request_airdrop(ctx.pubkey(), 1).await;
-    match sig {
-        Ok(signature) => {
-            println!(
-                "{} {}",
-                style("Airdrop requested successfully!").green().bold(),
-                style(format!("Signature: {signature}")).cyan()
-            );
-        }
-        Err(err) => {
-            print_error(format!("Airdrop failed: {err}"));
-        }
+#[derive(Debug, Serialize)]
+pub struct AirdropOutput {
+    pub signature: String,
+}
+
+impl CommandOutput for AirdropOutput {
+    fn print_table(&self) {
+        println!(
+            "{} {}",
+            style("Airdrop requested successfully!").green().bold(),
+            style(format!("Signature: {}", self.signature)).cyan()
+        );
     }
+}
 
-    Ok(())
+async fn request_sol_airdrop(ctx: &ScillaContext) -> anyhow::Result<AirdropOutput> {
+    let signature = ctx
+        .rpc()
+        .request_airdrop(ctx.pubkey(), 1)
+        .await
+        .map_err(|err| anyhow!("Airdrop failed: {err}"))?;
+
+    Ok(AirdropOutput {
+        signature: signature.to_string(),
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountOutput {
+    pub address: String,
+    pub lamports: u64,
+    pub data_length: usize,
+    pub owner: String,
+    pub executable: bool,
+    pub rent_epoch: u64,
+}
+
+impl CommandOutput for AccountOutput {
+    fn print_table(&self) {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_header(vec![
+                Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
+            ])
+            .add_row(vec![
+                Cell::new("Lamports"),
+                Cell::new(format!("{}", self.lamports)),
+            ])
+            .add_row(vec![
+                Cell::new("Data Length"),
+                Cell::new(format!("{}", self.data_length)),
+            ])
+            .add_row(vec![Cell::new("Owner"), Cell::new(&self.owner)])
+            .add_row(vec![
+                Cell::new("Executable"),
+                Cell::new(format!("{}", self.executable)),
+            ])
+            .add_row(vec![
+                Cell::new("Rent Epoch"),
+                Cell::new(format!("{}", self.rent_epoch)),
+            ]);
+
+        println!("{}\n{}", style("ACCOUNT INFO").green().bold(), table);
+    }
 }
 
-async fn fetch_acc_data(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Result<()> {
+async fn fetch_acc_data(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Result<AccountOutput> {
     let acc = ctx.rpc().get_account(pubkey).await?;
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
-        ])
-        .add_row(vec![
-            Cell::new("Lamports"),
-            Cell::new(format!("{}", acc.lamports)),
-        ])
-        .add_row(vec![
-            Cell::new("Data Length"),
-            Cell::new(format!("{}", acc.data.len())),
-        ])
-        .add_row(vec![
-            Cell::new("Owner"),
-            Cell::new(format!("{}", acc.owner)),
-        ])
-        .add_row(vec![
-            Cell::new("Executable"),
-            Cell::new(format!("{}", acc.executable)),
-        ])
-        .add_row(vec![
-            Cell::new("Rent Epoch"),
-            Cell::new(format!("{}", acc.rent_epoch)),
-        ]);
+    Ok(AccountOutput {
+        address: pubkey.to_string(),
+        lamports: acc.lamports,
+        data_length: acc.data.len(),
+        owner: acc.owner.to_string(),
+        executable: acc.executable,
+        rent_epoch: acc.rent_epoch,
+    })
+}
 
-    println!("{}\n{}", style("ACCOUNT INFO").green().bold(), table);
+#[derive(Debug, Serialize)]
+pub struct BalanceOutput {
+    pub address: String,
+    pub lamports: u64,
+    pub sol: f64,
+}
 
-    Ok(())
+impl CommandOutput for BalanceOutput {
+    fn print_table(&self) {
+        println!(
+            "{} {}",
+            style("Account balance in SOL:").green().bold(),
+            style(format!("{:#?}", self.sol)).cyan()
+        );
+    }
 }
 
-async fn fetch_account_balance(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Result<()> {
+async fn fetch_account_balance(
+    ctx: &ScillaContext,
+    pubkey: &Pubkey,
+) -> anyhow::Result<BalanceOutput> {
     let acc = ctx.rpc().get_account(pubkey).await?;
-    let acc_balance = lamports_to_sol(acc.lamports);
 
-    println!(
-        "{} {}",
-        style("Account balance in SOL:").green().bold(),
-        style(format!("{acc_balance:#?}")).cyan()
-    );
+    Ok(BalanceOutput {
+        address: pubkey.to_string(),
+        lamports: acc.lamports,
+        sol: lamports_to_sol(acc.lamports),
+    })
+}
 
-    Ok(())
+#[derive(Debug, Serialize)]
+pub struct LargestAccountOutput {
+    pub address: String,
+    pub lamports: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LargestAccountsOutput {
+    pub accounts: Vec<LargestAccountOutput>,
 }
 
-async fn fetch_largest_accounts(ctx: &ScillaContext) -> anyhow::Result<()> {
-    let filter_choice = Select::new(
-        "Filter accounts by:",
-        vec!["All", "Circulating", "Non-Circulating"],
-    )
-    .prompt()?;
+impl CommandOutput for LargestAccountsOutput {
+    fn print_table(&self) {
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL).set_header(vec![
+            Cell::new("#").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Address").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Balance (SOL)").add_attribute(comfy_table::Attribute::Bold),
+        ]);
+
+        for (idx, account) in self.accounts.iter().enumerate() {
+            let balance_sol = lamports_to_sol(account.lamports);
+            table.add_row(vec![
+                Cell::new(format!("{}", idx + 1)),
+                Cell::new(&account.address),
+                Cell::new(format!("{balance_sol:.2}")),
+            ]);
+        }
+
+        println!("\n{}", style("LARGEST ACCOUNTS").green().bold());
+        println!("{table}");
+    }
+}
 
-    let filter = match filter_choice {
-        "Circulating" => Some(RpcLargestAccountsFilter::Circulating),
-        "Non-Circulating" => Some(RpcLargestAccountsFilter::NonCirculating),
-        _ => None,
+async fn fetch_largest_accounts(
+    ctx: &ScillaContext,
+    filter: LargestAccountsFilter,
+) -> anyhow::Result<LargestAccountsOutput> {
+    let filter = match filter {
+        LargestAccountsFilter::Circulating => Some(RpcLargestAccountsFilter::Circulating),
+        LargestAccountsFilter::NonCirculating => Some(RpcLargestAccountsFilter::NonCirculating),
+        LargestAccountsFilter::All => None,
     };
 
     let config = RpcLargestAccountsConfig {
@@ -177,72 +685,1374 @@ async fn fetch_largest_accounts(ctx: &ScillaContext) -> anyhow::Result<()> {
     };
 
     let response = ctx.rpc().get_largest_accounts_with_config(config).await?;
-    let largest_accounts = response.value;
-
-    let mut table = Table::new();
-    table.load_preset(UTF8_FULL).set_header(vec![
-        Cell::new("#").add_attribute(comfy_table::Attribute::Bold),
-        Cell::new("Address").add_attribute(comfy_table::Attribute::Bold),
-        Cell::new("Balance (SOL)").add_attribute(comfy_table::Attribute::Bold),
-    ]);
-
-    for (idx, account) in largest_accounts.iter().enumerate() {
-        let balance_sol = lamports_to_sol(account.lamports);
-        table.add_row(vec![
-            Cell::new(format!("{}", idx + 1)),
-            Cell::new(&account.address),
-            Cell::new(format!("{balance_sol:.2}")),
-        ]);
+
+    Ok(LargestAccountsOutput {
+        accounts: response
+            .value
+            .into_iter()
+            .map(|account| LargestAccountOutput {
+                address: account.address,
+                lamports: account.lamports,
+            })
+            .collect(),
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct NonceAccountOutput {
+    pub address: String,
+    pub lamports: u64,
+    pub owner: String,
+    pub executable: bool,
+    pub rent_epoch: u64,
+    pub blockhash: String,
+    pub authority: String,
+}
+
+impl CommandOutput for NonceAccountOutput {
+    fn print_table(&self) {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_header(vec![
+                Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
+            ])
+            .add_row(vec![Cell::new("Address"), Cell::new(&self.address)])
+            .add_row(vec![
+                Cell::new("Lamports"),
+                Cell::new(format!("{}", self.lamports)),
+            ])
+            .add_row(vec![
+                Cell::new("Balance (SOL)"),
+                Cell::new(format!("{:.6}", lamports_to_sol(self.lamports))),
+            ])
+            .add_row(vec![Cell::new("Owner"), Cell::new(&self.owner)])
+            .add_row(vec![
+                Cell::new("Executable"),
+                Cell::new(format!("{}", self.executable)),
+            ])
+            .add_row(vec![
+                Cell::new("Rent Epoch"),
+                Cell::new(format!("{}", self.rent_epoch)),
+            ])
+            .add_row(vec![
+                Cell::new("Nonce blockhash"),
+                Cell::new(&self.blockhash),
+            ])
+            .add_row(vec![Cell::new("Authority"), Cell::new(&self.authority)]);
+
+        println!("\n{}", style("NONCE ACCOUNT INFO").green().bold());
+        println!("{table}");
     }
+}
 
-    println!("\n{}", style("LARGEST ACCOUNTS").green().bold());
-    println!("{table}");
+async fn fetch_nonce_account(
+    ctx: &ScillaContext,
+    pubkey: &Pubkey,
+) -> anyhow::Result<NonceAccountOutput> {
+    let (account, data) = fetch_nonce_data(ctx, pubkey).await?;
 
-    Ok(())
+    Ok(NonceAccountOutput {
+        address: pubkey.to_string(),
+        lamports: account.lamports,
+        owner: account.owner.to_string(),
+        executable: account.executable,
+        rent_epoch: account.rent_epoch,
+        blockhash: data.blockhash().to_string(),
+        authority: data.authority.to_string(),
+    })
 }
 
-async fn fetch_nonce_account(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Result<()> {
+async fn fetch_nonce_data(
+    ctx: &ScillaContext,
+    pubkey: &Pubkey,
+) -> anyhow::Result<(Account, NonceData)> {
     let account = ctx.rpc().get_account(pubkey).await?;
+    let data = decode_nonce_data(&account)?;
+    Ok((account, data))
+}
+
+/// Fetches the nonce account and checks that `authority` is its current
+/// authority.
+async fn fetch_nonce_data_with_authority(
+    ctx: &ScillaContext,
+    nonce_pubkey: &Pubkey,
+    authority: &Pubkey,
+) -> anyhow::Result<(Account, NonceData)> {
+    let (account, data) = fetch_nonce_data(ctx, nonce_pubkey).await?;
+
+    if &data.authority != authority {
+        bail!(
+            "Provided keypair is not the nonce authority\nExpected: {}\nProvided: {}",
+            data.authority,
+            authority
+        );
+    }
+
+    Ok((account, data))
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateNonceAccountOutput {
+    pub signature: String,
+    pub nonce_account: NonceAccountOutput,
+}
+
+impl CommandOutput for CreateNonceAccountOutput {
+    fn print_table(&self) {
+        println!(
+            "{}\n{}",
+            style("Nonce account created successfully!").green().bold(),
+            style(format!("Signature: {}", self.signature)).cyan()
+        );
+        self.nonce_account.print_table();
+    }
+}
+
+async fn process_create_nonce_account(
+    ctx: &ScillaContext,
+    nonce_keypair_path: &PathBuf,
+    authority: &Pubkey,
+    lamports: u64,
+    tx_args: &TxArgs,
+) -> anyhow::Result<TxOutcome<CreateNonceAccountOutput>> {
+    let nonce_keypair = read_keypair_from_path(nonce_keypair_path)?;
+    let nonce_pubkey = nonce_keypair.pubkey();
+
+    if &nonce_pubkey == ctx.pubkey() {
+        bail!("Nonce account {nonce_pubkey} cannot be the same as the fee payer");
+    }
+
+    let rent_exempt_minimum = ctx
+        .rpc()
+        .get_minimum_balance_for_rent_exemption(NonceState::size())
+        .await?;
+    if lamports < rent_exempt_minimum {
+        bail!(
+            "Nonce account must hold at least {} SOL to be rent exempt, got {} SOL",
+            lamports_to_sol(rent_exempt_minimum),
+            lamports_to_sol(lamports)
+        );
+    }
+    check_minimum_balance(ctx, ctx.pubkey(), lamports).await?;
+
+    let ixs = create_nonce_account(ctx.pubkey(), &nonce_pubkey, authority, lamports);
+    let signature =
+        match build_and_send_tx(ctx, &ixs, &[ctx.keypair(), &nonce_keypair], tx_args).await? {
+            TxOutcome::Sent(signature) => signature,
+            TxOutcome::SignedOnly(signed) => return Ok(TxOutcome::SignedOnly(signed)),
+        };
+
+    let nonce_account = fetch_nonce_account(ctx, &nonce_pubkey).await?;
+
+    Ok(TxOutcome::Sent(CreateNonceAccountOutput {
+        signature: signature.to_string(),
+        nonce_account,
+    }))
+}
+
+#[derive(Debug, Serialize)]
+pub struct AdvanceNonceAccountOutput {
+    pub signature: String,
+    pub nonce_account: String,
+    pub previous_blockhash: String,
+    pub blockhash: String,
+}
+
+impl CommandOutput for AdvanceNonceAccountOutput {
+    fn print_table(&self) {
+        println!(
+            "{}\n{}\n{}\n{}",
+            style("Nonce advanced successfully!").green().bold(),
+            style(format!("Nonce Account: {}", self.nonce_account)).yellow(),
+            style(format!("New Nonce: {}", self.blockhash)).yellow(),
+            style(format!("Signature: {}", self.signature)).cyan()
+        );
+    }
+}
+
+async fn process_advance_nonce_account(
+    ctx: &ScillaContext,
+    nonce_pubkey: &Pubkey,
+    authority_keypair_path: &PathBuf,
+    tx_args: &TxArgs,
+) -> anyhow::Result<TxOutcome<AdvanceNonceAccountOutput>> {
+    let authority_keypair = read_keypair_from_path(authority_keypair_path)?;
+    let (_, data) =
+        fetch_nonce_data_with_authority(ctx, nonce_pubkey, &authority_keypair.pubkey()).await?;
+
+    let ix = advance_nonce_account(nonce_pubkey, &authority_keypair.pubkey());
+    let signature =
+        match build_and_send_tx(ctx, &[ix], &[ctx.keypair(), &authority_keypair], tx_args).await? {
+            TxOutcome::Sent(signature) => signature,
+            TxOutcome::SignedOnly(signed) => return Ok(TxOutcome::SignedOnly(signed)),
+        };
+
+    let (_, advanced) = fetch_nonce_data(ctx, nonce_pubkey).await?;
+
+    Ok(TxOutcome::Sent(AdvanceNonceAccountOutput {
+        signature: signature.to_string(),
+        nonce_account: nonce_pubkey.to_string(),
+        previous_blockhash: data.blockhash().to_string(),
+        blockhash: advanced.blockhash().to_string(),
+    }))
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuthorizeNonceAccountOutput {
+    pub signature: String,
+    pub nonce_account: String,
+    pub previous_authority: String,
+    pub new_authority: String,
+}
+
+impl CommandOutput for AuthorizeNonceAccountOutput {
+    fn print_table(&self) {
+        println!(
+            "{}\n{}\n{}\n{}",
+            style("Nonce authority updated successfully!")
+                .green()
+                .bold(),
+            style(format!("Nonce Account: {}", self.nonce_account)).yellow(),
+            style(format!("New Authority: {}", self.new_authority)).yellow(),
+            style(format!("Signature: {}", self.signature)).cyan()
+        );
+    }
+}
+
+async fn process_authorize_nonce_account(
+    ctx: &ScillaContext,
+    nonce_pubkey: &Pubkey,
+    authority_keypair_path: &PathBuf,
+    new_authority: &Pubkey,
+    tx_args: &TxArgs,
+) -> anyhow::Result<TxOutcome<AuthorizeNonceAccountOutput>> {
+    let authority_keypair = read_keypair_from_path(authority_keypair_path)?;
+    let authority_pubkey = authority_keypair.pubkey();
+    fetch_nonce_data_with_authority(ctx, nonce_pubkey, &authority_pubkey).await?;
+
+    if new_authority == &authority_pubkey {
+        bail!("{new_authority} is already the nonce authority");
+    }
 
-    let versions = bincode_deserialize::<Versions>(&account.data, "nonce account data")?;
+    let ix = authorize_nonce_account(nonce_pubkey, &authority_pubkey, new_authority);
+    let signature =
+        match build_and_send_tx(ctx, &[ix], &[ctx.keypair(), &authority_keypair], tx_args).await? {
+            TxOutcome::Sent(signature) => signature,
+            TxOutcome::SignedOnly(signed) => return Ok(TxOutcome::SignedOnly(signed)),
+        };
+
+    Ok(TxOutcome::Sent(AuthorizeNonceAccountOutput {
+        signature: signature.to_string(),
+        nonce_account: nonce_pubkey.to_string(),
+        previous_authority: authority_pubkey.to_string(),
+        new_authority: new_authority.to_string(),
+    }))
+}
+
+#[derive(Debug, Serialize)]
+pub struct WithdrawFromNonceAccountOutput {
+    pub signature: String,
+    pub nonce_account: String,
+    pub recipient: String,
+    pub lamports: u64,
+}
+
+impl CommandOutput for WithdrawFromNonceAccountOutput {
+    fn print_table(&self) {
+        println!(
+            "{}\n{}\n{}\n{}\n{}",
+            style("Withdrawn from nonce account successfully!")
+                .green()
+                .bold(),
+            style(format!("From Nonce Account: {}", self.nonce_account)).yellow(),
+            style(format!("To Recipient: {}", self.recipient)).yellow(),
+            style(format!("Amount: {} SOL", lamports_to_sol(self.lamports))).cyan(),
+            style(format!("Signature: {}", self.signature)).cyan()
+        );
+    }
+}
+
+async fn process_withdraw_from_nonce_account(
+    ctx: &ScillaContext,
+    nonce_pubkey: &Pubkey,
+    authority_keypair_path: &PathBuf,
+    recipient: &Pubkey,
+    lamports: u64,
+    tx_args: &TxArgs,
+) -> anyhow::Result<TxOutcome<WithdrawFromNonceAccountOutput>> {
+    let authority_keypair = read_keypair_from_path(authority_keypair_path)?;
+    let (account, _) =
+        fetch_nonce_data_with_authority(ctx, nonce_pubkey, &authority_keypair.pubkey()).await?;
+
+    if lamports > account.lamports {
+        bail!(
+            "Insufficient balance. Have {} SOL, trying to withdraw {} SOL",
+            lamports_to_sol(account.lamports),
+            lamports_to_sol(lamports)
+        );
+    }
+
+    // Withdrawing everything closes the account; otherwise it has to stay
+    // rent exempt
+    let remaining = account.lamports - lamports;
+    if remaining > 0 {
+        let rent_exempt_minimum = ctx
+            .rpc()
+            .get_minimum_balance_for_rent_exemption(account.data.len())
+            .await?;
+        if remaining < rent_exempt_minimum {
+            bail!(
+                "Withdrawal would leave {} SOL, below the rent-exempt minimum of {} SOL. Withdraw \
+                 at most {} SOL or the full balance of {} SOL",
+                lamports_to_sol(remaining),
+                lamports_to_sol(rent_exempt_minimum),
+                lamports_to_sol(account.lamports.saturating_sub(rent_exempt_minimum)),
+                lamports_to_sol(account.lamports)
+            );
+        }
+    }
+
+    let ix = withdraw_nonce_account(
+        nonce_pubkey,
+        &authority_keypair.pubkey(),
+        recipient,
+        lamports,
+    );
+    let signature =
+        match build_and_send_tx(ctx, &[ix], &[ctx.keypair(), &authority_keypair], tx_args).await? {
+            TxOutcome::Sent(signature) => signature,
+            TxOutcome::SignedOnly(signed) => return Ok(TxOutcome::SignedOnly(signed)),
+        };
+
+    Ok(TxOutcome::Sent(WithdrawFromNonceAccountOutput {
+        signature: signature.to_string(),
+        nonce_account: nonce_pubkey.to_string(),
+        recipient: recipient.to_string(),
+        lamports,
+    }))
+}
+
+#[derive(Debug, Serialize)]
+pub struct TransferOutput {
+    pub signature: String,
+    pub from: String,
+    pub to: String,
+    pub lamports: u64,
+    pub fee_payer: String,
+    pub memo: Option<String>,
+}
 
-    let solana_nonce::state::State::Initialized(data) = versions.state() else {
-        bail!("This account is not an initialized nonce account");
+impl CommandOutput for TransferOutput {
+    fn print_table(&self) {
+        println!(
+            "{}\n{}\n{}\n{}",
+            style("SOL transferred successfully!").green().bold(),
+            style(format!("From: {}", self.from)).yellow(),
+            style(format!("To: {}", self.to)).yellow(),
+            style(format!("Amount: {} SOL", lamports_to_sol(self.lamports))).cyan(),
+        );
+        if self.fee_payer != self.from {
+            println!(
+                "{}",
+                style(format!("Fee Payer: {}", self.fee_payer)).yellow()
+            );
+        }
+        if let Some(memo) = &self.memo {
+            println!("{}", style(format!("Memo: {memo}")).yellow());
+        }
+        println!("{}", style(format!("Signature: {}", self.signature)).cyan());
+    }
+}
+
+/// Sends SOL from the configured keypair to `recipient`.
+///
+/// `allow_unfunded_recipient` of `None` asks before sending to an account that
+/// does not exist yet.
+async fn process_transfer(
+    ctx: &ScillaContext,
+    recipient: &Pubkey,
+    amount: TransferAmount,
+    memo: Option<&str>,
+    fee_payer_keypair_path: Option<&PathBuf>,
+    allow_unfunded_recipient: Option<bool>,
+    tx_args: &TxArgs,
+) -> anyhow::Result<TxOutcome<TransferOutput>> {
+    let sender = *ctx.pubkey();
+    if recipient == &sender {
+        bail!("Recipient cannot be the sending account {sender}");
+    }
+
+    let fee_payer_keypair = fee_payer_keypair_path
+        .map(read_keypair_from_path)
+        .transpose()?;
+    let mut tx_args = tx_args.clone();
+    if let Some(keypair) = &fee_payer_keypair {
+        tx_args.fee_payer = Some(keypair.pubkey());
+    }
+    let fee_payer = tx_args.fee_payer.unwrap_or(sender);
+
+    let mut signers: Vec<&dyn Signer> = vec![ctx.keypair()];
+    if let Some(keypair) = &fee_payer_keypair {
+        signers.push(keypair);
+    }
+
+    let instructions = |lamports| {
+        let mut ixs = vec![transfer(&sender, recipient, lamports)];
+        if let Some(memo) = memo {
+            ixs.push(memo_instruction(memo, &sender));
+        }
+        ixs
+    };
+
+    let lamports = match amount {
+        TransferAmount::Sol(amount) => amount.to_lamports(),
+        TransferAmount::All if tx_args.sign_only => {
+            bail!("ALL needs the current balance. Pass an explicit amount with --sign-only")
+        }
+        TransferAmount::All => 0,
     };
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
-        ])
-        .add_row(vec![Cell::new("Address"), Cell::new(pubkey)])
-        .add_row(vec![
-            Cell::new("Lamports"),
-            Cell::new(format!("{}", account.lamports)),
-        ])
-        .add_row(vec![
-            Cell::new("Balance (SOL)"),
-            Cell::new(format!("{:.6}", lamports_to_sol(account.lamports))),
-        ])
-        .add_row(vec![Cell::new("Owner"), Cell::new(account.owner)])
-        .add_row(vec![
-            Cell::new("Executable"),
-            Cell::new(format!("{}", account.executable)),
-        ])
-        .add_row(vec![
-            Cell::new("Rent Epoch"),
-            Cell::new(format!("{}", account.rent_epoch)),
-        ])
-        .add_row(vec![
-            Cell::new("Nonce blockhash"),
-            Cell::new(data.blockhash()),
-        ])
-        .add_row(vec![Cell::new("Authority"), Cell::new(data.authority)]);
-
-    println!("\n{}", style("NONCE ACCOUNT INFO").green().bold());
-    println!("{table}");
+    // The balances can only be checked online, so skip it when signing offline
+    let lamports = if tx_args.sign_only {
+        lamports
+    } else {
+        let accounts = ctx
+            .rpc()
+            .get_multiple_accounts(&[sender, *recipient])
+            .await?;
+        let sender_balance = accounts[0].as_ref().map_or(0, |account| account.lamports);
+        let recipient_account = accounts[1].as_ref();
+
+        // Pin the priority fee so the fee estimated here is the one the sent
+        // transaction pays
+        let micro_lamports = resolve_compute_unit_price(
+            ctx,
+            &tx_args.compute_budget,
+            &fee_payer,
+            &instructions(sender_balance),
+        )
+        .await?;
+        tx_args.compute_budget.compute_unit_price = Some(ComputeUnitPrice::Fixed(micro_lamports));
+        let estimate = build_tx(ctx, &instructions(sender_balance), &signers, &tx_args).await?;
+        let fee = fee_for_message(ctx, &estimate.message).await?;
+        let sender_fee = if fee_payer == sender { fee } else { 0 };
+
+        let lamports = match amount {
+            TransferAmount::Sol(_) => lamports,
+            TransferAmount::All => transfer_all_lamports(sender_balance, sender_fee)?,
+        };
+
+        check_minimum_balance(ctx, &sender, lamports + sender_fee).await?;
+        if fee_payer != sender {
+            check_minimum_balance(ctx, &fee_payer, fee).await?;
+        }
 
+        // An account left with less than the rent-exempt minimum is rejected
+        let remaining = remaining_balance(sender_balance, lamports, sender_fee)?;
+        if remaining > 0 {
+            let data_len = accounts[0].as_ref().map_or(0, |account| account.data.len());
+            let rent_exempt_minimum = ctx
+                .rpc()
+                .get_minimum_balance_for_rent_exemption(data_len)
+                .await?;
+            check_sender_remainder(&sender, remaining, lamports, rent_exempt_minimum)?;
+        }
+
+        if recipient_account.is_none() {
+            let allowed = match allow_unfunded_recipient {
+                Some(allowed) => allowed,
+                None => suspend_spinner(|| {
+                    Confirm::new(&format!(
+                        "Recipient {recipient} does not exist yet. Send to it anyway?"
+                    ))
+                    .with_default(false)
+                    .prompt()
+                    .unwrap_or(false)
+                }),
+            };
+            if !allowed {
+                bail!(
+                    "Recipient {recipient} is not funded. Check the address, or pass \
+                     --allow-unfunded-recipient to send anyway"
+                );
+            }
+        }
+
+        let (recipient_balance, data_len) =
+            recipient_account.map_or((0, 0), |account| (account.lamports, account.data.len()));
+        let rent_exempt_minimum = ctx
+            .rpc()
+            .get_minimum_balance_for_rent_exemption(data_len)
+            .await?;
+        check_recipient_balance(recipient_balance, lamports, rent_exempt_minimum)?;
+
+        lamports
+    };
+
+    let signature =
+        match build_and_send_tx(ctx, &instructions(lamports), &signers, &tx_args).await? {
+            TxOutcome::Sent(signature) => signature,
+            TxOutcome::SignedOnly(signed) => return Ok(TxOutcome::SignedOnly(signed)),
+        };
+
+    Ok(TxOutcome::Sent(TransferOutput {
+        signature: signature.to_string(),
+        from: sender.to_string(),
+        to: recipient.to_string(),
+        lamports,
+        fee_payer: fee_payer.to_string(),
+        memo: memo.map(ToString::to_string),
+    }))
+}
+
+/// Lamports sent by an ALL transfer: the whole balance minus the fee the
+/// sender pays.
+fn transfer_all_lamports(balance: u64, sender_fee: u64) -> anyhow::Result<u64> {
+    balance
+        .checked_sub(sender_fee)
+        .filter(|lamports| *lamports > 0)
+        .ok_or_else(|| {
+            anyhow!(
+                "Balance of {} SOL does not cover the fee of {} SOL",
+                lamports_to_sol(balance),
+                lamports_to_sol(sender_fee)
+            )
+        })
+}
+
+/// Balance the sender keeps after the transfer and the fee it pays
+fn remaining_balance(balance: u64, lamports: u64, sender_fee: u64) -> anyhow::Result<u64> {
+    lamports
+        .checked_add(sender_fee)
+        .and_then(|spent| balance.checked_sub(spent))
+        .ok_or_else(|| {
+            anyhow!(
+                "Insufficient balance: {} SOL does not cover {} SOL plus the fee of {} SOL",
+                lamports_to_sol(balance),
+                lamports_to_sol(lamports),
+                lamports_to_sol(sender_fee)
+            )
+        })
+}
+
+/// Rejects a transfer that would leave the sender funded but below the
+/// rent-exempt minimum.
+fn check_sender_remainder(
+    sender: &Pubkey,
+    remaining: u64,
+    lamports: u64,
+    rent_exempt_minimum: u64,
+) -> anyhow::Result<()> {
+    if remaining > 0 && remaining < rent_exempt_minimum {
+        bail!(
+            "Transfer would leave {} SOL in {sender}, below the rent-exempt minimum of {} SOL. \
+             Send at most {} SOL or ALL",
+            lamports_to_sol(remaining),
+            lamports_to_sol(rent_exempt_minimum),
+            lamports_to_sol((remaining + lamports).saturating_sub(rent_exempt_minimum))
+        );
+    }
     Ok(())
 }
+
+/// Rejects a transfer that would leave the recipient below the rent-exempt
+/// minimum.
+fn check_recipient_balance(
+    recipient_balance: u64,
+    lamports: u64,
+    rent_exempt_minimum: u64,
+) -> anyhow::Result<()> {
+    let balance = recipient_balance.saturating_add(lamports);
+    if balance < rent_exempt_minimum {
+        bail!(
+            "Recipient would hold {} SOL, below the rent-exempt minimum of {} SOL. Send at least \
+             {} SOL",
+            lamports_to_sol(balance),
+            lamports_to_sol(rent_exempt_minimum),
+            lamports_to_sol(rent_exempt_minimum - recipient_balance)
+        );
+    }
+    Ok(())
+}
+
+/// A validated row of a distribution file
+#[derive(Debug, Clone)]
+struct DistributionRow {
+    line: u64,
+    recipient: Pubkey,
+    lamports: u64,
+    memo: Option<String>,
+}
+
+fn default_results_path(file: &Path) -> PathBuf {
+    file.with_extension("results.csv")
+}
+
+/// Reads and validates every `recipient,amount[,memo]` row, reporting all
+/// invalid rows at once. A header row is skipped, and a recipient listed
+/// twice is rejected as a likely double payment.
+fn read_distribution_file(path: &Path) -> anyhow::Result<Vec<DistributionRow>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_path(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+    let mut first_lines = HashMap::new();
+    for (i, record) in reader.records().enumerate() {
+        let record = record.with_context(|| format!("Failed to read {}", path.display()))?;
+        let line = record
+            .position()
+            .map_or(i as u64 + 1, |position| position.line());
+        if i == 0
+            && record
+                .get(0)
+                .is_some_and(|field| field.eq_ignore_ascii_case("recipient"))
+        {
+            continue;
+        }
+
+        match parse_distribution_row(&record) {
+            Ok((recipient, _, _)) if first_lines.contains_key(&recipient) => errors.push(format!(
+                "  line {line}: Duplicate recipient {recipient}, first listed on line {}",
+                first_lines[&recipient]
+            )),
+            Ok((recipient, amount, memo)) => {
+                first_lines.insert(recipient, line);
+                rows.push(DistributionRow {
+                    line,
+                    recipient,
+                    lamports: amount.to_lamports(),
+                    memo,
+                });
+            }
+            Err(err) => errors.push(format!("  line {line}: {err}")),
+        }
+    }
+
+    if !errors.is_empty() {
+        bail!("Invalid rows in {}:\n{}", path.display(), errors.join("\n"));
+    }
+    if rows.is_empty() {
+        bail!("{} does not contain any transfers", path.display());
+    }
+
+    Ok(rows)
+}
+
+fn parse_distribution_row(
+    record: &csv::StringRecord,
+) -> anyhow::Result<(Pubkey, SolAmount, Option<String>)> {
+    if !(2..=3).contains(&record.len()) {
+        bail!(
+            "Expected recipient,amount[,memo], got {} fields",
+            record.len()
+        );
+    }
+
+    let recipient = Pubkey::from_str(&record[0])
+        .map_err(|_| anyhow!("Invalid recipient address: {}", &record[0]))?;
+    let amount = record[1].parse()?;
+    let memo = record
+        .get(2)
+        .filter(|memo| !memo.is_empty())
+        .map(ToString::to_string);
+
+    Ok((recipient, amount, memo))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferStatus {
+    /// Not sent yet
+    Pending,
+    /// Submitted, but not known to be confirmed
+    Sent,
+    Confirmed,
+    /// Did not land; retried on the next run
+    Failed,
+}
+
+impl fmt::Display for TransferStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self {
+            TransferStatus::Pending => "pending",
+            TransferStatus::Sent => "sent",
+            TransferStatus::Confirmed => "confirmed",
+            TransferStatus::Failed => "failed",
+        };
+        write!(f, "{status}")
+    }
+}
+
+/// A row of the results file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TransferRecord {
+    line: u64,
+    recipient: String,
+    lamports: u64,
+    memo: Option<String>,
+    status: TransferStatus,
+    signature: Option<String>,
+    blockhash: Option<String>,
+    error: Option<String>,
+}
+
+/// The results file, rewritten after every state change so an interrupted
+/// run can be resumed.
+struct TransferResults {
+    path: PathBuf,
+    records: Vec<TransferRecord>,
+}
+
+impl TransferResults {
+    /// Loads the results of a previous run over the same rows, or starts with
+    /// every transfer pending.
+    fn load_or_create(path: &Path, rows: &[DistributionRow]) -> anyhow::Result<Self> {
+        let mut previous = HashMap::new();
+        if path.exists() {
+            let mut reader = csv::Reader::from_path(path)
+                .with_context(|| format!("Failed to open {}", path.display()))?;
+            for record in reader.deserialize::<TransferRecord>() {
+                let record =
+                    record.with_context(|| format!("Failed to read {}", path.display()))?;
+                previous.insert(record.line, record);
+            }
+        }
+
+        let records = rows
+            .iter()
+            .map(|row| match previous.remove(&row.line) {
+                Some(record)
+                    if record.recipient == row.recipient.to_string()
+                        && record.lamports == row.lamports =>
+                {
+                    Ok(record)
+                }
+                Some(_) => bail!(
+                    "{} does not match line {} of the CSV file. Remove it or pass another \
+                     --results path",
+                    path.display(),
+                    row.line
+                ),
+                None => Ok(TransferRecord {
+                    line: row.line,
+                    recipient: row.recipient.to_string(),
+                    lamports: row.lamports,
+                    memo: row.memo.clone(),
+                    status: TransferStatus::Pending,
+                    signature: None,
+                    blockhash: None,
+                    error: None,
+                }),
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(TransferResults {
+            path: path.to_path_buf(),
+            records,
+        })
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        // Write to a temporary file first so an interruption never leaves a
+        // truncated results file behind
+        let tmp_path = self.path.with_extension("csv.tmp");
+        let mut writer = csv::Writer::from_path(&tmp_path)
+            .with_context(|| format!("Failed to write {}", tmp_path.display()))?;
+        for record in &self.records {
+            writer.serialize(record)?;
+        }
+        writer.flush()?;
+        fs::rename(&tmp_path, &self.path)
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+        Ok(())
+    }
+
+    fn update(
+        &mut self,
+        indices: &[usize],
+        status: TransferStatus,
+        tx: Option<&VersionedTransaction>,
+        error: Option<String>,
+    ) -> anyhow::Result<()> {
+        for &i in indices {
+            let record = &mut self.records[i];
+            record.status = status;
+            if let Some(tx) = tx {
+                record.signature = Some(tx.signatures[0].to_string());
+                record.blockhash = Some(tx.message.recent_blockhash().to_string());
+            }
+            record.error = error.clone();
+        }
+        self.save()
+    }
+
+    fn count(&self, status: TransferStatus) -> usize {
+        self.records
+            .iter()
+            .filter(|record| record.status == status)
+            .count()
+    }
+}
+
+/// Settles transfers a previous run sent without seeing them confirmed.
+/// Bails while any of them could still land, so nothing is paid twice.
+async fn resolve_sent_transfers(
+    ctx: &ScillaContext,
+    results: &mut TransferResults,
+) -> anyhow::Result<()> {
+    let sent: Vec<(usize, Signature, Hash)> = results
+        .records
+        .iter()
+        .enumerate()
+        .filter(|(_, record)| record.status == TransferStatus::Sent)
+        .map(|(i, record)| {
+            let signature = record.signature.as_deref().unwrap_or_default().parse()?;
+            let blockhash = record.blockhash.as_deref().unwrap_or_default().parse()?;
+            Ok((i, signature, blockhash))
+        })
+        .collect::<anyhow::Result<_>>()
+        .with_context(|| format!("Invalid sent transfer in {}", results.path.display()))?;
+    if sent.is_empty() {
+        return Ok(());
+    }
+
+    let mut signatures: Vec<Signature> = sent.iter().map(|(_, signature, _)| *signature).collect();
+    signatures.sort_unstable();
+    signatures.dedup();
+
+    let mut statuses = HashMap::new();
+    for chunk in signatures.chunks(MAX_SIGNATURE_STATUSES) {
+        let chunk_statuses = ctx
+            .rpc()
+            .get_signature_statuses_with_history(chunk)
+            .await?
+            .value;
+        statuses.extend(chunk.iter().copied().zip(chunk_statuses));
+    }
+
+    let commitment = ctx.rpc().commitment();
+    for (i, signature, blockhash) in sent {
+        let record = &mut results.records[i];
+        match statuses.get(&signature).and_then(Option::as_ref) {
+            Some(status) if status.err.is_some() => {
+                record.status = TransferStatus::Failed;
+                record.error = status.err.as_ref().map(ToString::to_string);
+            }
+            Some(status) if status.satisfies_commitment(commitment) => {
+                record.status = TransferStatus::Confirmed;
+                record.error = None;
+            }
+            _ if ctx.rpc().is_blockhash_valid(&blockhash, commitment).await? => {
+                bail!(
+                    "Transaction {signature} from a previous run may still land. Wait a minute \
+                     and run the command again"
+                );
+            }
+            Some(_) | None => {
+                record.status = TransferStatus::Failed;
+                record.error = Some("Transaction expired before it was confirmed".to_string());
+            }
+        }
+    }
+
+    results.save()
+}
+
+/// Transfers still to be sent, packed into transactions.
+struct BatchTransferPlan {
+    results: TransferResults,
+    batches: Vec<Batch<usize>>,
+    tx_args: TxArgs,
+    lamports: u64,
+    fee_lamports: u64,
+}
+
+impl BatchTransferPlan {
+    /// Prints the plan ahead of the command result.
+    fn print(&self, ctx: &ScillaContext) {
+        let transfers: usize = self.batches.iter().map(|batch| batch.items.len()).sum();
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_header(vec![
+                Cell::new("Batch Transfer").add_attribute(Attribute::Bold),
+                Cell::new("Value").add_attribute(Attribute::Bold),
+            ])
+            .add_row(vec![
+                Cell::new("Transfers in File"),
+                Cell::new(self.results.records.len()),
+            ])
+            .add_row(vec![
+                Cell::new("Already Confirmed"),
+                Cell::new(self.results.count(TransferStatus::Confirmed)),
+            ])
+            .add_row(vec![Cell::new("To Send"), Cell::new(transfers)])
+            .add_row(vec![
+                Cell::new("Transactions"),
+                Cell::new(self.batches.len()),
+            ])
+            .add_row(vec![
+                Cell::new("Amount (SOL)"),
+                Cell::new(lamports_to_sol(self.lamports)),
+            ])
+            .add_row(vec![
+                Cell::new("Estimated Fees (SOL)"),
+                Cell::new(lamports_to_sol(self.fee_lamports)),
+            ])
+            .add_row(vec![
+                Cell::new("Results File"),
+                Cell::new(self.results.path.display()),
+            ]);
+
+        let rendered = format!("\n{}\n{table}", style("BATCH TRANSFER PLAN").green().bold());
+        print_status(ctx, rendered);
+    }
+}
+
+async fn prepare_batch_transfer(
+    ctx: &ScillaContext,
+    rows: Vec<DistributionRow>,
+    results_path: &Path,
+    compute_budget: &ComputeBudgetArgs,
+    concurrency: usize,
+) -> anyhow::Result<BatchTransferPlan> {
+    let mut results = TransferResults::load_or_create(results_path, &rows)?;
+    resolve_sent_transfers(ctx, &mut results).await?;
+    results.save()?;
+
+    let sender = *ctx.pubkey();
+    let remaining: Vec<&DistributionRow> = rows
+        .iter()
+        .zip(&results.records)
+        .filter(|(_, record)| record.status != TransferStatus::Confirmed)
+        .map(|(row, _)| row)
+        .collect();
+    let instructions = |row: &DistributionRow| {
+        let mut ixs = vec![transfer(&sender, &row.recipient, row.lamports)];
+        if let Some(memo) = &row.memo {
+            ixs.push(memo_instruction(memo, &sender));
+        }
+        ixs
+    };
+
+    // New accounts have to receive at least the rent-exempt minimum, and one
+    // short transfer would fail every other transfer in its transaction
+    let recipients: Vec<Pubkey> = remaining.iter().map(|row| row.recipient).collect();
+    let mut existing = HashSet::new();
+    for chunk in recipients.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        let accounts = ctx.rpc().get_multiple_accounts(chunk).await?;
+        existing.extend(
+            chunk
+                .iter()
+                .zip(accounts)
+                .filter(|(_, account)| account.is_some())
+                .map(|(pubkey, _)| *pubkey),
+        );
+    }
+    let rent_exempt_minimum = ctx.rpc().get_minimum_balance_for_rent_exemption(0).await?;
+    let below_rent: Vec<String> = remaining
+        .iter()
+        .filter(|row| !existing.contains(&row.recipient) && row.lamports < rent_exempt_minimum)
+        .map(|row| format!("  line {}: {}", row.line, row.recipient))
+        .collect();
+    if !below_rent.is_empty() {
+        bail!(
+            "These recipients do not exist yet and need at least {} SOL to be rent exempt:\n{}",
+            lamports_to_sol(rent_exempt_minimum),
+            below_rent.join("\n")
+        );
+    }
+
+    let index: HashMap<u64, usize> = rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| (row.line, i))
+        .collect();
+    let items: Vec<(usize, Vec<Instruction>)> = remaining
+        .iter()
+        .map(|row| (index[&row.line], instructions(row)))
+        .collect();
+
+    // Pin the priority fee so every transaction pays the estimated fee
+    let (tx_args, prefix) = pin_batch_tx_args(ctx, compute_budget, &items).await?;
+    let batches = pack_instructions(items, &sender, &prefix)?;
+
+    let blockhash = ctx.rpc().get_latest_blockhash().await?;
+    let fees: Vec<u64> = stream::iter(&batches)
+        .map(|batch| {
+            let ixs: Vec<Instruction> = prefix.iter().chain(&batch.instructions).cloned().collect();
+            let message = Message::new_with_blockhash(&ixs, Some(&sender), &blockhash);
+            async move { fee_for_message(ctx, &VersionedMessage::Legacy(message)).await }
+        })
+        .buffered(concurrency)
+        .try_collect()
+        .await?;
+    let fee_lamports = fees.iter().sum();
+    let lamports = remaining.iter().map(|row| row.lamports).sum();
+
+    check_minimum_balance(ctx, &sender, lamports + fee_lamports).await?;
+
+    Ok(BatchTransferPlan {
+        results,
+        batches,
+        tx_args,
+        lamports,
+        fee_lamports,
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchTransactionOutput {
+    pub signature: Option<String>,
+    pub transfers: usize,
+    pub lamports: u64,
+    pub status: TransferStatus,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchTransferOutput {
+    pub results_file: String,
+    pub confirmed: usize,
+    pub unconfirmed: usize,
+    pub transactions: Vec<BatchTransactionOutput>,
+}
+
+impl BatchTransferOutput {
+    fn new(results: &TransferResults, transactions: Vec<BatchTransactionOutput>) -> Self {
+        let confirmed = results.count(TransferStatus::Confirmed);
+        BatchTransferOutput {
+            results_file: results.path.display().to_string(),
+            confirmed,
+            unconfirmed: results.records.len() - confirmed,
+            transactions,
+        }
+    }
+}
+
+impl CommandOutput for BatchTransferOutput {
+    fn print_table(&self) {
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL).set_header(vec![
+            Cell::new("Signature").add_attribute(Attribute::Bold),
+            Cell::new("Transfers").add_attribute(Attribute::Bold),
+            Cell::new("Amount (SOL)").add_attribute(Attribute::Bold),
+            Cell::new("Status").add_attribute(Attribute::Bold),
+        ]);
+        for tx in &self.transactions {
+            let status = match tx.status {
+                TransferStatus::Confirmed => style(tx.status).green(),
+                TransferStatus::Sent => style(tx.status).yellow(),
+                TransferStatus::Pending | TransferStatus::Failed => style(tx.status).red(),
+            };
+            table.add_row(vec![
+                Cell::new(tx.signature.as_deref().unwrap_or("-")),
+                Cell::new(tx.transfers),
+                Cell::new(lamports_to_sol(tx.lamports)),
+                Cell::new(status.to_string()),
+            ]);
+        }
+
+        println!("\n{}", style("BATCH TRANSFER").green().bold());
+        println!("{table}");
+        for tx in &self.transactions {
+            if let Some(error) = &tx.error {
+                let signature = tx.signature.as_deref().unwrap_or("Transaction");
+                println!("{}", style(format!("{signature}: {error}")).red());
+            }
+        }
+        println!(
+            "{}",
+            style(format!(
+                "Confirmed: {} of {} transfers",
+                self.confirmed,
+                self.confirmed + self.unconfirmed
+            ))
+            .cyan()
+        );
+        println!(
+            "{}",
+            style(format!("Results saved to: {}", self.results_file)).cyan()
+        );
+    }
+}
+
+async fn process_batch_transfer(
+    ctx: &ScillaContext,
+    plan: BatchTransferPlan,
+    concurrency: usize,
+) -> anyhow::Result<BatchTransferOutput> {
+    let BatchTransferPlan {
+        results,
+        batches,
+        tx_args,
+        ..
+    } = plan;
+    let results = Mutex::new(results);
+
+    let transactions: Vec<BatchTransactionOutput> = stream::iter(batches)
+        .map(|batch| send_transfer_batch(ctx, &results, batch, &tx_args))
+        .buffer_unordered(concurrency)
+        .try_collect()
+        .await?;
+
+    let results = results
+        .into_inner()
+        .map_err(|_| anyhow!("Batch transfer results are poisoned"))?;
+
+    Ok(BatchTransferOutput::new(&results, transactions))
+}
+
+/// Sends one transaction of the batch, recording it before it is submitted
+/// so a crash can never lead to paying twice.
+async fn send_transfer_batch(
+    ctx: &ScillaContext,
+    results: &Mutex<TransferResults>,
+    batch: Batch<usize>,
+    tx_args: &TxArgs,
+) -> anyhow::Result<BatchTransactionOutput> {
+    let update = |status, tx: Option<&VersionedTransaction>, error: Option<String>| {
+        results
+            .lock()
+            .map_err(|_| anyhow!("Batch transfer results are poisoned"))?
+            .update(&batch.items, status, tx, error)
+    };
+    let lamports = {
+        let results = results
+            .lock()
+            .map_err(|_| anyhow!("Batch transfer results are poisoned"))?;
+        batch
+            .items
+            .iter()
+            .map(|&i| results.records[i].lamports)
+            .sum()
+    };
+    let output = |status, tx: Option<&VersionedTransaction>, error| BatchTransactionOutput {
+        signature: tx.map(|tx| tx.signatures[0].to_string()),
+        transfers: batch.items.len(),
+        lamports,
+        status,
+        error,
+    };
+
+    let tx = match build_tx(ctx, &batch.instructions, &[ctx.keypair()], tx_args).await {
+        Ok(tx) => tx,
+        Err(err) => {
+            update(TransferStatus::Failed, None, Some(err.to_string()))?;
+            return Ok(output(TransferStatus::Failed, None, Some(err.to_string())));
+        }
+    };
+
+    update(TransferStatus::Sent, Some(&tx), None)?;
+    match ctx.rpc().send_and_confirm_transaction(&tx).await {
+        Ok(_) => {
+            update(TransferStatus::Confirmed, Some(&tx), None)?;
+            Ok(output(TransferStatus::Confirmed, Some(&tx), None))
+        }
+        // The transaction may still land, so it stays sent until the next run
+        // settles it
+        Err(err) => {
+            update(TransferStatus::Sent, Some(&tx), Some(err.to_string()))?;
+            Ok(output(
+                TransferStatus::Sent,
+                Some(&tx),
+                Some(err.to_string()),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, tempfile::TempDir};
+
+    fn write_file(dir: &TempDir, name: &str, contents: &str) -> PathBuf {
+        let path = dir.path().join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_parse_transfer_amount() {
+        assert!(matches!("all".parse(), Ok(TransferAmount::All)));
+        assert!(matches!(" ALL ".parse(), Ok(TransferAmount::All)));
+
+        let Ok(TransferAmount::Sol(amount)) = "1.5".parse::<TransferAmount>() else {
+            panic!("1.5 should parse as a SOL amount");
+        };
+        assert_eq!(amount.to_lamports(), 1_500_000_000);
+        let Ok(TransferAmount::Sol(amount)) = "0.000000001".parse::<TransferAmount>() else {
+            panic!("0.000000001 should parse as a SOL amount");
+        };
+        assert_eq!(amount.to_lamports(), 1);
+
+        for input in ["", "abc", "0", "-1", "inf", "NaN", "1e30"] {
+            assert!(
+                input.parse::<TransferAmount>().is_err(),
+                "{input:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_transfer_all_lamports() {
+        assert_eq!(transfer_all_lamports(1_000_000, 5_000).unwrap(), 995_000);
+        assert!(transfer_all_lamports(5_000, 5_000).is_err());
+        assert!(transfer_all_lamports(4_000, 5_000).is_err());
+    }
+
+    #[test]
+    fn test_remaining_balance() {
+        assert_eq!(remaining_balance(1_000_000, 995_000, 5_000).unwrap(), 0);
+        assert_eq!(
+            remaining_balance(1_000_000, 500_000, 5_000).unwrap(),
+            495_000
+        );
+        assert!(remaining_balance(1_000_000, 996_000, 5_000).is_err());
+        assert!(remaining_balance(1_000_000, u64::MAX, 5_000).is_err());
+    }
+
+    #[test]
+    fn test_rent_exempt_checks() {
+        let sender = Pubkey::new_unique();
+        check_sender_remainder(&sender, 0, 1_000, 890_880).unwrap();
+        check_sender_remainder(&sender, 890_880, 1_000, 890_880).unwrap();
+        assert!(check_sender_remainder(&sender, 1, 1_000, 890_880).is_err());
+
+        check_recipient_balance(0, 890_880, 890_880).unwrap();
+        check_recipient_balance(890_000, 880, 890_880).unwrap();
+        assert!(check_recipient_balance(0, 1_000, 890_880).is_err());
+    }
+
+    #[test]
+    fn test_parse_distribution_row() {
+        let recipient = Pubkey::new_unique();
+
+        let record = csv::StringRecord::from(vec![recipient.to_string(), "0.5".into()]);
+        let (parsed, amount, memo) = parse_distribution_row(&record).unwrap();
+        assert_eq!(parsed, recipient);
+        assert_eq!(amount.to_lamports(), 500_000_000);
+        assert_eq!(memo, None);
+
+        let record =
+            csv::StringRecord::from(vec![recipient.to_string(), "1".into(), "payout".into()]);
+        let (_, _, memo) = parse_distribution_row(&record).unwrap();
+        assert_eq!(memo.as_deref(), Some("payout"));
+
+        for fields in [
+            vec![recipient.to_string()],
+            vec![
+                recipient.to_string(),
+                "1".into(),
+                "memo".into(),
+                "extra".into(),
+            ],
+            vec!["not-a-pubkey".into(), "1".into()],
+            vec![recipient.to_string(), "-1".into()],
+            vec![recipient.to_string(), "ten".into()],
+        ] {
+            let record = csv::StringRecord::from(fields.clone());
+            assert!(
+                parse_distribution_row(&record).is_err(),
+                "{fields:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_read_distribution_file() {
+        let dir = TempDir::new().expect("Failed to create temp dir");
+        let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        let path = write_file(
+            &dir,
+            "payouts.csv",
+            &format!("recipient,amount,memo\n{alice}, 1.5\n{bob},0.25,bonus\n"),
+        );
+        let rows = read_distribution_file(&path).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            (rows[0].line, rows[0].recipient, rows[0].lamports),
+            (2, alice, 1_500_000_000)
+        );
+        assert_eq!(rows[0].memo, None);
+        assert_eq!(
+            (rows[1].line, rows[1].recipient, rows[1].lamports),
+            (3, bob, 250_000_000)
+        );
+        assert_eq!(rows[1].memo.as_deref(), Some("bonus"));
+
+        // Every invalid row is reported at once
+        let path = write_file(
+            &dir,
+            "invalid.csv",
+            &format!("{alice},1\n{bob}\n{bob},abc\n{alice},2\n"),
+        );
+        let err = read_distribution_file(&path).unwrap_err().to_string();
+        assert!(
+            err.contains("line 2: Expected recipient,amount[,memo]"),
+            "{err}"
+        );
+        assert!(err.contains("line 3: "), "{err}");
+        assert!(
+            err.contains(&format!(
+                "line 4: Duplicate recipient {alice}, first listed on line 1"
+            )),
+            "{err}"
+        );
+        assert!(!err.contains("line 1:"), "{err}");
+
+        let path = write_file(&dir, "empty.csv", "recipient,amount\n");
+        assert!(read_distribution_file(&path).is_err());
+    }
+
+    #[test]
+    fn test_transfer_results_resume() {
+        let dir = TempDir::new().expect("Failed to create temp dir");
+        let rows: Vec<DistributionRow> = (1..=3)
+            .map(|line| DistributionRow {
+                line,
+                recipient: Pubkey::new_unique(),
+                lamports: line * 1_000_000,
+                memo: None,
+            })
+            .collect();
+        let path = dir.path().join("payouts.results.csv");
+
+        let mut results = TransferResults::load_or_create(&path, &rows).unwrap();
+        assert_eq!(results.count(TransferStatus::Pending), 3);
+        results
+            .update(&[0], TransferStatus::Confirmed, None, None)
+            .unwrap();
+        results
+            .update(&[1], TransferStatus::Failed, None, Some("expired".into()))
+            .unwrap();
+
+        // A rerun picks up where the previous one stopped
+        let resumed = TransferResults::load_or_create(&path, &rows).unwrap();
+        let statuses: Vec<TransferStatus> =
+            resumed.records.iter().map(|record| record.status).collect();
+        assert_eq!(
+            statuses,
+            [
+                TransferStatus::Confirmed,
+                TransferStatus::Failed,
+                TransferStatus::Pending
+            ]
+        );
+        assert_eq!(resumed.records[1].error.as_deref(), Some("expired"));
+
+        // Results of a different distribution file are refused
+        let mut changed = rows.clone();
+        changed[0].lamports += 1;
+        assert!(TransferResults::load_or_create(&path, &changed).is_err());
+    }
+}
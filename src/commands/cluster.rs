@@ -1,24 +1,35 @@
 use {
     crate::{
         commands::CommandFlow, constants::LAMPORTS_PER_SOL, context::ScillaContext,
-        ui::show_spinner,
+        error::ScillaResult, output::CommandOutput, ui::show_spinner,
     },
+    clap::Subcommand,
     comfy_table::{Cell, Table, presets::UTF8_FULL},
     console::style,
+    serde::Serialize,
     std::{fmt, ops::Div},
 };
 
 /// Commands related to cluster operations
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Subcommand)]
 pub enum ClusterCommand {
+    /// Current epoch and progress
     EpochInfo,
+    /// Latest confirmed slot
     CurrentSlot,
+    /// Current block height
     BlockHeight,
+    /// Timestamp of the latest block
     BlockTime,
+    /// List active validators
     Validators,
+    /// Total and circulating supply
     SupplyInfo,
+    /// Current inflation parameters
     Inflation,
+    /// Solana version running on the cluster
     ClusterVersion,
+    #[command(skip)]
     GoBack,
 }
 
@@ -56,42 +67,105 @@ impl fmt::Display for ClusterCommand {
 }
 
 impl ClusterCommand {
-    pub async fn process_command(&self, ctx: &ScillaContext) -> CommandFlow<()> {
+    pub async fn process_command(&self, ctx: &ScillaContext) -> ScillaResult<()> {
         match self {
             ClusterCommand::EpochInfo => {
-                show_spinner(self.spinner_msg(), fetch_epoch_info(ctx)).await;
+                let epoch_info = show_spinner(self.spinner_msg(), fetch_epoch_info(ctx)).await?;
+                ctx.output_format().render(&epoch_info)?;
             }
             ClusterCommand::CurrentSlot => {
-                show_spinner(self.spinner_msg(), fetch_current_slot(ctx)).await;
+                let slot = show_spinner(self.spinner_msg(), fetch_current_slot(ctx)).await?;
+                ctx.output_format().render(&slot)?;
             }
             ClusterCommand::BlockHeight => {
-                show_spinner(self.spinner_msg(), fetch_block_height(ctx)).await;
+                let block_height =
+                    show_spinner(self.spinner_msg(), fetch_block_height(ctx)).await?;
+                ctx.output_format().render(&block_height)?;
             }
             ClusterCommand::BlockTime => {
-                show_spinner(self.spinner_msg(), fetch_block_time(ctx)).await;
+                let block_time = show_spinner(self.spinner_msg(), fetch_block_time(ctx)).await?;
+                ctx.output_format().render(&block_time)?;
             }
             ClusterCommand::Validators => {
-                show_spinner(self.spinner_msg(), fetch_validators(ctx)).await;
+                let validators = show_spinner(self.spinner_msg(), fetch_validators(ctx)).await?;
+                ctx.output_format().render(&validators)?;
             }
             ClusterCommand::SupplyInfo => {
-                show_spinner(self.spinner_msg(), fetch_supply_info(ctx)).await;
+                let supply = show_spinner(self.spinner_msg(), fetch_supply_info(ctx)).await?;
+                ctx.output_format().render(&supply)?;
             }
             ClusterCommand::Inflation => {
-                show_spinner(self.spinner_msg(), fetch_inflation_info(ctx)).await;
+                let inflation = show_spinner(self.spinner_msg(), fetch_inflation_info(ctx)).await?;
+                ctx.output_format().render(&inflation)?;
             }
             ClusterCommand::ClusterVersion => {
-                show_spinner(self.spinner_msg(), fetch_cluster_version(ctx)).await;
+                let version = show_spinner(self.spinner_msg(), fetch_cluster_version(ctx)).await?;
+                ctx.output_format().render(&version)?;
             }
             ClusterCommand::GoBack => {
-                return CommandFlow::GoBack;
+                return Ok(CommandFlow::GoBack);
             }
         }
 
-        CommandFlow::Process(())
+        Ok(CommandFlow::Process(()))
     }
 }
 
-async fn fetch_epoch_info(ctx: &ScillaContext) -> anyhow::Result<()> {
+#[derive(Debug, Serialize)]
+pub struct EpochInfoOutput {
+    pub epoch: u64,
+    pub epoch_progress: f64,
+    pub slot_index: u64,
+    pub slots_in_epoch: u64,
+    pub absolute_slot: u64,
+    pub block_height: u64,
+    pub transaction_count: Option<u64>,
+}
+
+impl CommandOutput for EpochInfoOutput {
+    fn print_table(&self) {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_header(vec![
+                Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
+            ])
+            .add_row(vec![
+                Cell::new("Epoch"),
+                Cell::new(format!("{}", self.epoch)),
+            ])
+            .add_row(vec![
+                Cell::new("Epoch Progress"),
+                Cell::new(format!("{:.2}%", self.epoch_progress)),
+            ])
+            .add_row(vec![
+                Cell::new("Slot Index"),
+                Cell::new(format!("{}", self.slot_index)),
+            ])
+            .add_row(vec![
+                Cell::new("Slots in Epoch"),
+                Cell::new(format!("{}", self.slots_in_epoch)),
+            ])
+            .add_row(vec![
+                Cell::new("Absolute Slot"),
+                Cell::new(format!("{}", self.absolute_slot)),
+            ])
+            .add_row(vec![
+                Cell::new("Block Height"),
+                Cell::new(format!("{}", self.block_height)),
+            ])
+            .add_row(vec![
+                Cell::new("Transaction Count"),
+                Cell::new(format!("{}", self.transaction_count.unwrap_or(0))),
+            ]);
+
+        println!("\n{}", style("EPOCH INFORMATION").green().bold());
+        println!("{table}");
+    }
+}
+
+async fn fetch_epoch_info(ctx: &ScillaContext) -> anyhow::Result<EpochInfoOutput> {
     let epoch_info = ctx.rpc().get_epoch_info().await?;
 
     let epoch_progress = if epoch_info.slots_in_epoch > 0 {
@@ -100,262 +174,342 @@ async fn fetch_epoch_info(ctx: &ScillaContext) -> anyhow::Result<()> {
         0.0
     };
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
-        ])
-        .add_row(vec![
-            Cell::new("Epoch"),
-            Cell::new(format!("{}", epoch_info.epoch)),
-        ])
-        .add_row(vec![
-            Cell::new("Epoch Progress"),
-            Cell::new(format!("{:.2}%", epoch_progress)),
-        ])
-        .add_row(vec![
-            Cell::new("Slot Index"),
-            Cell::new(format!("{}", epoch_info.slot_index)),
-        ])
-        .add_row(vec![
-            Cell::new("Slots in Epoch"),
-            Cell::new(format!("{}", epoch_info.slots_in_epoch)),
-        ])
-        .add_row(vec![
-            Cell::new("Absolute Slot"),
-            Cell::new(format!("{}", epoch_info.absolute_slot)),
-        ])
-        .add_row(vec![
-            Cell::new("Block Height"),
-            Cell::new(format!("{}", epoch_info.block_height)),
-        ])
-        .add_row(vec![
-            Cell::new("Transaction Count"),
-            Cell::new(format!("{}", epoch_info.transaction_count.unwrap_or(0))),
-        ]);
-
-    println!("\n{}", style("EPOCH INFORMATION").green().bold());
-    println!("{table}");
-
-    Ok(())
+    Ok(EpochInfoOutput {
+        epoch: epoch_info.epoch,
+        epoch_progress,
+        slot_index: epoch_info.slot_index,
+        slots_in_epoch: epoch_info.slots_in_epoch,
+        absolute_slot: epoch_info.absolute_slot,
+        block_height: epoch_info.block_height,
+        transaction_count: epoch_info.transaction_count,
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct SlotOutput {
+    pub slot: u64,
 }
 
-async fn fetch_current_slot(ctx: &ScillaContext) -> anyhow::Result<()> {
+impl CommandOutput for SlotOutput {
+    fn print_table(&self) {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_header(vec![
+                Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
+            ])
+            .add_row(vec![
+                Cell::new("Current Slot"),
+                Cell::new(format!("{}", self.slot)),
+            ]);
+
+        println!("\n{}", style("CURRENT SLOT").green().bold());
+        println!("{table}");
+    }
+}
+
+async fn fetch_current_slot(ctx: &ScillaContext) -> anyhow::Result<SlotOutput> {
     let slot = ctx.rpc().get_slot().await?;
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
-        ])
-        .add_row(vec![
-            Cell::new("Current Slot"),
-            Cell::new(format!("{slot}")),
-        ]);
-
-    println!("\n{}", style("CURRENT SLOT").green().bold());
-    println!("{table}");
-
-    Ok(())
+    Ok(SlotOutput { slot })
+}
+
+#[derive(Debug, Serialize)]
+pub struct BlockHeightOutput {
+    pub block_height: u64,
 }
 
-async fn fetch_block_height(ctx: &ScillaContext) -> anyhow::Result<()> {
+impl CommandOutput for BlockHeightOutput {
+    fn print_table(&self) {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_header(vec![
+                Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
+            ])
+            .add_row(vec![
+                Cell::new("Block Height"),
+                Cell::new(format!("{}", self.block_height)),
+            ]);
+
+        println!("\n{}", style("BLOCK HEIGHT").green().bold());
+        println!("{table}");
+    }
+}
+
+async fn fetch_block_height(ctx: &ScillaContext) -> anyhow::Result<BlockHeightOutput> {
     let block_height = ctx.rpc().get_block_height().await?;
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
-        ])
-        .add_row(vec![
-            Cell::new("Block Height"),
-            Cell::new(format!("{block_height}")),
-        ]);
-
-    println!("\n{}", style("BLOCK HEIGHT").green().bold());
-    println!("{table}");
-
-    Ok(())
+    Ok(BlockHeightOutput { block_height })
+}
+
+#[derive(Debug, Serialize)]
+pub struct BlockTimeOutput {
+    pub slot: u64,
+    pub unix_timestamp: i64,
+    pub date_time: Option<String>,
+}
+
+impl CommandOutput for BlockTimeOutput {
+    fn print_table(&self) {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_header(vec![
+                Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
+            ])
+            .add_row(vec![Cell::new("Slot"), Cell::new(format!("{}", self.slot))])
+            .add_row(vec![
+                Cell::new("Unix Timestamp"),
+                Cell::new(format!("{}", self.unix_timestamp)),
+            ])
+            .add_row(vec![
+                Cell::new("Date/Time"),
+                Cell::new(self.date_time.as_deref().unwrap_or("Invalid timestamp")),
+            ]);
+
+        println!("\n{}", style("BLOCK TIME").green().bold());
+        println!("{table}");
+    }
 }
 
-async fn fetch_block_time(ctx: &ScillaContext) -> anyhow::Result<()> {
+async fn fetch_block_time(ctx: &ScillaContext) -> anyhow::Result<BlockTimeOutput> {
     let slot = ctx.rpc().get_slot().await?;
     let block_time = ctx.rpc().get_block_time(slot).await?;
 
-    let datetime = chrono::DateTime::<chrono::Utc>::from_timestamp_secs(block_time)
-        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
-        .unwrap_or_else(|| "Invalid timestamp".to_string());
-
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
-        ])
-        .add_row(vec![Cell::new("Slot"), Cell::new(format!("{slot}"))])
-        .add_row(vec![
-            Cell::new("Unix Timestamp"),
-            Cell::new(format!("{block_time}")),
-        ])
-        .add_row(vec![Cell::new("Date/Time"), Cell::new(datetime)]);
-
-    println!("\n{}", style("BLOCK TIME").green().bold());
-    println!("{table}");
-
-    Ok(())
+    let date_time = chrono::DateTime::<chrono::Utc>::from_timestamp_secs(block_time)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string());
+
+    Ok(BlockTimeOutput {
+        slot,
+        unix_timestamp: block_time,
+        date_time,
+    })
 }
 
-async fn fetch_validators(ctx: &ScillaContext) -> anyhow::Result<()> {
-    let validators = ctx.rpc().get_vote_accounts().await?;
+#[derive(Debug, Serialize)]
+pub struct ValidatorOutput {
+    pub node_pubkey: String,
+    pub vote_pubkey: String,
+    pub activated_stake: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ValidatorsOutput {
+    pub current_count: usize,
+    pub delinquent_count: usize,
+    pub current: Vec<ValidatorOutput>,
+}
 
-    // Summary table
-    let mut summary_table = Table::new();
-    summary_table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
-        ])
-        .add_row(vec![
-            Cell::new("Current Validators"),
-            Cell::new(format!("{}", validators.current.len())),
-        ])
-        .add_row(vec![
-            Cell::new("Delinquent Validators"),
-            Cell::new(format!("{}", validators.delinquent.len())),
-        ]);
-
-    println!("\n{}", style("VALIDATORS SUMMARY").green().bold());
-    println!("{summary_table}");
-
-    // Validators detail table
-    if !validators.current.is_empty() {
-        let mut validators_table = Table::new();
-        validators_table.load_preset(UTF8_FULL).set_header(vec![
-            Cell::new("#").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Node Pubkey").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Vote Account").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Activated Stake (SOL)").add_attribute(comfy_table::Attribute::Bold),
-        ]);
-
-        for (idx, validator) in validators.current.iter().enumerate() {
-            let stake_sol = (validator.activated_stake as f64).div(LAMPORTS_PER_SOL as f64);
-            validators_table.add_row(vec![
-                Cell::new(format!("{}", idx + 1)),
-                Cell::new(&validator.node_pubkey),
-                Cell::new(&validator.vote_pubkey),
-                Cell::new(format!("{stake_sol:.2}")),
+impl CommandOutput for ValidatorsOutput {
+    fn print_table(&self) {
+        // Summary table
+        let mut summary_table = Table::new();
+        summary_table
+            .load_preset(UTF8_FULL)
+            .set_header(vec![
+                Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
+            ])
+            .add_row(vec![
+                Cell::new("Current Validators"),
+                Cell::new(format!("{}", self.current_count)),
+            ])
+            .add_row(vec![
+                Cell::new("Delinquent Validators"),
+                Cell::new(format!("{}", self.delinquent_count)),
             ]);
-        }
 
-        println!("\n{}", style("TOP VALIDATORS").green().bold());
-        println!("{validators_table}");
+        println!("\n{}", style("VALIDATORS SUMMARY").green().bold());
+        println!("{summary_table}");
+
+        // Validators detail table
+        if !self.current.is_empty() {
+            let mut validators_table = Table::new();
+            validators_table.load_preset(UTF8_FULL).set_header(vec![
+                Cell::new("#").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Node Pubkey").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Vote Account").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Activated Stake (SOL)").add_attribute(comfy_table::Attribute::Bold),
+            ]);
+
+            for (idx, validator) in self.current.iter().enumerate() {
+                let stake_sol = (validator.activated_stake as f64).div(LAMPORTS_PER_SOL as f64);
+                validators_table.add_row(vec![
+                    Cell::new(format!("{}", idx + 1)),
+                    Cell::new(&validator.node_pubkey),
+                    Cell::new(&validator.vote_pubkey),
+                    Cell::new(format!("{stake_sol:.2}")),
+                ]);
+            }
+
+            println!("\n{}", style("TOP VALIDATORS").green().bold());
+            println!("{validators_table}");
+        }
     }
+}
+
+async fn fetch_validators(ctx: &ScillaContext) -> anyhow::Result<ValidatorsOutput> {
+    let validators = ctx.rpc().get_vote_accounts().await?;
+
+    Ok(ValidatorsOutput {
+        current_count: validators.current.len(),
+        delinquent_count: validators.delinquent.len(),
+        current: validators
+            .current
+            .into_iter()
+            .map(|validator| ValidatorOutput {
+                node_pubkey: validator.node_pubkey,
+                vote_pubkey: validator.vote_pubkey,
+                activated_stake: validator.activated_stake,
+            })
+            .collect(),
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct SupplyOutput {
+    pub total: u64,
+    pub circulating: u64,
+    pub non_circulating: u64,
+    pub circulating_pct: f64,
+}
+
+impl CommandOutput for SupplyOutput {
+    fn print_table(&self) {
+        let total_sol = (self.total as f64).div(LAMPORTS_PER_SOL as f64);
+        let circulating_sol = (self.circulating as f64).div(LAMPORTS_PER_SOL as f64);
+        let non_circulating_sol = (self.non_circulating as f64).div(LAMPORTS_PER_SOL as f64);
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_header(vec![
+                Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Value (SOL)").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Percentage").add_attribute(comfy_table::Attribute::Bold),
+            ])
+            .add_row(vec![
+                Cell::new("Total Supply"),
+                Cell::new(format!("{total_sol:.2}")),
+                Cell::new("100.00%"),
+            ])
+            .add_row(vec![
+                Cell::new("Circulating"),
+                Cell::new(format!("{circulating_sol:.2}")),
+                Cell::new(format!("{:.2}%", self.circulating_pct)),
+            ])
+            .add_row(vec![
+                Cell::new("Non-Circulating"),
+                Cell::new(format!("{non_circulating_sol:.2}")),
+                Cell::new(format!("{:.2}%", 100.0 - self.circulating_pct)),
+            ]);
 
-    Ok(())
+        println!("\n{}", style("SUPPLY INFORMATION").green().bold());
+        println!("{table}");
+    }
 }
 
-async fn fetch_supply_info(ctx: &ScillaContext) -> anyhow::Result<()> {
+async fn fetch_supply_info(ctx: &ScillaContext) -> anyhow::Result<SupplyOutput> {
     let supply = ctx.rpc().supply().await?;
 
-    let total_sol = (supply.value.total as f64).div(LAMPORTS_PER_SOL as f64);
-    let circulating_sol = (supply.value.circulating as f64).div(LAMPORTS_PER_SOL as f64);
-    let non_circulating_sol = (supply.value.non_circulating as f64).div(LAMPORTS_PER_SOL as f64);
-    let circulating_pct = (circulating_sol / total_sol) * 100.0;
-
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Value (SOL)").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Percentage").add_attribute(comfy_table::Attribute::Bold),
-        ])
-        .add_row(vec![
-            Cell::new("Total Supply"),
-            Cell::new(format!("{total_sol:.2}")),
-            Cell::new("100.00%"),
-        ])
-        .add_row(vec![
-            Cell::new("Circulating"),
-            Cell::new(format!("{circulating_sol:.2}")),
-            Cell::new(format!("{circulating_pct:.2}%")),
-        ])
-        .add_row(vec![
-            Cell::new("Non-Circulating"),
-            Cell::new(format!("{non_circulating_sol:.2}")),
-            Cell::new(format!("{:.2}%", 100.0 - circulating_pct)),
-        ]);
-
-    println!("\n{}", style("SUPPLY INFORMATION").green().bold());
-    println!("{table}");
-
-    Ok(())
+    let circulating_pct = (supply.value.circulating as f64 / supply.value.total as f64) * 100.0;
+
+    Ok(SupplyOutput {
+        total: supply.value.total,
+        circulating: supply.value.circulating,
+        non_circulating: supply.value.non_circulating,
+        circulating_pct,
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct InflationOutput {
+    pub epoch: u64,
+    pub total: f64,
+    pub validator: f64,
+    pub foundation: f64,
+}
+
+impl CommandOutput for InflationOutput {
+    fn print_table(&self) {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_header(vec![
+                Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
+            ])
+            .add_row(vec![
+                Cell::new("Epoch"),
+                Cell::new(format!("{}", self.epoch)),
+            ])
+            .add_row(vec![
+                Cell::new("Total Inflation Rate"),
+                Cell::new(format!("{:.4}%", self.total * 100.0)),
+            ])
+            .add_row(vec![
+                Cell::new("Validator Inflation"),
+                Cell::new(format!("{:.4}%", self.validator * 100.0)),
+            ])
+            .add_row(vec![
+                Cell::new("Foundation Inflation"),
+                Cell::new(format!("{:.4}%", self.foundation * 100.0)),
+            ]);
+
+        println!("\n{}", style("INFLATION INFORMATION").green().bold());
+        println!("{table}");
+    }
 }
 
-async fn fetch_inflation_info(ctx: &ScillaContext) -> anyhow::Result<()> {
+async fn fetch_inflation_info(ctx: &ScillaContext) -> anyhow::Result<InflationOutput> {
     let inflation = ctx.rpc().get_inflation_rate().await?;
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
-        ])
-        .add_row(vec![
-            Cell::new("Epoch"),
-            Cell::new(format!("{}", inflation.epoch)),
-        ])
-        .add_row(vec![
-            Cell::new("Total Inflation Rate"),
-            Cell::new(format!("{:.4}%", inflation.total * 100.0)),
-        ])
-        .add_row(vec![
-            Cell::new("Validator Inflation"),
-            Cell::new(format!("{:.4}%", inflation.validator * 100.0)),
-        ])
-        .add_row(vec![
-            Cell::new("Foundation Inflation"),
-            Cell::new(format!("{:.4}%", inflation.foundation * 100.0)),
-        ]);
-
-    println!("\n{}", style("INFLATION INFORMATION").green().bold());
-    println!("{table}");
-
-    Ok(())
+
+    Ok(InflationOutput {
+        epoch: inflation.epoch,
+        total: inflation.total,
+        validator: inflation.validator,
+        foundation: inflation.foundation,
+    })
 }
 
-async fn fetch_cluster_version(ctx: &ScillaContext) -> anyhow::Result<()> {
-    let version = ctx.rpc().get_version().await?;
+#[derive(Debug, Serialize)]
+pub struct ClusterVersionOutput {
+    pub solana_core: String,
+    pub feature_set: Option<u32>,
+}
+
+impl CommandOutput for ClusterVersionOutput {
+    fn print_table(&self) {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_header(vec![
+                Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
+            ])
+            .add_row(vec![Cell::new("Solana Core"), Cell::new(&self.solana_core)]);
+
+        if let Some(feature_set) = self.feature_set {
+            table.add_row(vec![
+                Cell::new("Feature Set"),
+                Cell::new(format!("{feature_set}")),
+            ]);
+        }
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
-        ])
-        .add_row(vec![
-            Cell::new("Solana Core"),
-            Cell::new(version.solana_core),
-        ]);
-
-    if let Some(feature_set) = version.feature_set {
-        table.add_row(vec![
-            Cell::new("Feature Set"),
-            Cell::new(format!("{feature_set}")),
-        ]);
+        println!("\n{}", style("CLUSTER VERSION").green().bold());
+        println!("{table}");
     }
+}
 
-    println!("\n{}", style("CLUSTER VERSION").green().bold());
-    println!("{table}");
+async fn fetch_cluster_version(ctx: &ScillaContext) -> anyhow::Result<ClusterVersionOutput> {
+    let version = ctx.rpc().get_version().await?;
 
-    Ok(())
+    Ok(ClusterVersionOutput {
+        solana_core: version.solana_core,
+        feature_set: version.feature_set,
+    })
 }
@@ -3,9 +3,14 @@ use {
         commands::CommandFlow,
         config::{ScillaConfig, scilla_config_path},
         context::ScillaContext,
+        error::ScillaResult,
+        misc::compute_budget::{ComputeUnitPrice, parse_compute_unit_limit},
+        output::CommandOutput,
         prompt::{prompt_input_data, prompt_keypair_path},
-        ui::print_error,
+        ui::report_error,
     },
+    anyhow::bail,
+    clap::{Args, Subcommand},
     comfy_table::{Cell, Table, presets::UTF8_FULL},
     console::style,
     inquire::{Confirm, Select},
@@ -15,18 +20,52 @@ use {
 };
 
 /// Commands related to configuration like RPC_URL , KEYAPAIR_PATH etc
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Subcommand)]
 pub enum ConfigCommand {
+    /// Display current config settings
     Show,
-    Edit,
+    /// Edit the config file
+    Edit(EditConfigArgs),
+    #[command(skip)]
     GoBack,
 }
 
+/// Fields to update without prompting. When none are given, the field to
+/// edit is chosen interactively.
+#[derive(Debug, Clone, Default, Args)]
+pub struct EditConfigArgs {
+    /// New RPC URL
+    #[arg(long)]
+    pub rpc_url: Option<String>,
+    /// New commitment level (processed, confirmed or finalized)
+    #[arg(long)]
+    pub commitment_level: Option<CommitmentLevel>,
+    /// New keypair path
+    #[arg(long)]
+    pub keypair_path: Option<PathBuf>,
+    /// New default compute unit limit
+    #[arg(long, value_parser = parse_compute_unit_limit)]
+    pub compute_unit_limit: Option<u32>,
+    /// New default compute unit price, in micro-lamports or `auto[:PERCENTILE]`
+    #[arg(long)]
+    pub compute_unit_price: Option<ComputeUnitPrice>,
+}
+
+impl EditConfigArgs {
+    fn is_empty(&self) -> bool {
+        self.rpc_url.is_none()
+            && self.commitment_level.is_none()
+            && self.keypair_path.is_none()
+            && self.compute_unit_limit.is_none()
+            && self.compute_unit_price.is_none()
+    }
+}
+
 impl ConfigCommand {
     pub fn spinner_msg(&self) -> &'static str {
         match self {
             ConfigCommand::Show => "Displaying current Scilla configuration…",
-            ConfigCommand::Edit => "Editing existing Scilla configuration…",
+            ConfigCommand::Edit(_) => "Editing existing Scilla configuration…",
             ConfigCommand::GoBack => "Going back…",
         }
     }
@@ -36,7 +75,7 @@ impl fmt::Display for ConfigCommand {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let command = match self {
             ConfigCommand::Show => "View ScillaConfig",
-            ConfigCommand::Edit => "Edit ScillaConfig",
+            ConfigCommand::Edit(_) => "Edit ScillaConfig",
             ConfigCommand::GoBack => "Go back",
         };
         write!(f, "{command}")
@@ -48,6 +87,8 @@ enum ConfigField {
     RpcUrl,
     CommitmentLevel,
     KeypairPath,
+    ComputeUnitLimit,
+    ComputeUnitPrice,
     None, // if None is chosen , we go back to previous context
 }
 
@@ -57,6 +98,8 @@ impl fmt::Display for ConfigField {
             ConfigField::RpcUrl => write!(f, "RPC URL"),
             ConfigField::CommitmentLevel => write!(f, "Commitment Level"),
             ConfigField::KeypairPath => write!(f, "Keypair Path"),
+            ConfigField::ComputeUnitLimit => write!(f, "Compute Unit Limit"),
+            ConfigField::ComputeUnitPrice => write!(f, "Compute Unit Price"),
             ConfigField::None => write!(f, "None"),
         }
     }
@@ -68,6 +111,8 @@ impl ConfigField {
             ConfigField::RpcUrl,
             ConfigField::CommitmentLevel,
             ConfigField::KeypairPath,
+            ConfigField::ComputeUnitLimit,
+            ConfigField::ComputeUnitPrice,
             ConfigField::None,
         ]
     }
@@ -98,44 +143,59 @@ fn get_commitment_levels() -> Vec<UICommitmentOptions> {
 }
 
 impl ConfigCommand {
-    pub fn process_command(&self, ctx: &mut ScillaContext) -> CommandFlow<()> {
+    pub fn process_command(&self, ctx: &mut ScillaContext) -> ScillaResult<()> {
         let res = match self {
-            ConfigCommand::Show => show_config(),
-            ConfigCommand::Edit => edit_config(ctx),
-            ConfigCommand::GoBack => return CommandFlow::GoBack,
+            ConfigCommand::Show => {
+                show_config().and_then(|config| ctx.output_format().render(&config))
+            }
+            ConfigCommand::Edit(args) => edit_config(ctx, args),
+            ConfigCommand::GoBack => return Ok(CommandFlow::GoBack),
         };
 
-        if let Err(e) = res {
-            print_error(e.to_string())
-        }
-
-        CommandFlow::Process(())
+        res.map(CommandFlow::Process).map_err(report_error)
     }
 }
 
-fn show_config() -> anyhow::Result<()> {
-    let mut table = Table::new();
-    let config = ScillaConfig::load()?;
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
-        ])
-        .add_row(vec![Cell::new("RPC URL"), Cell::new(config.rpc_url)])
-        .add_row(vec![
-            Cell::new("Commitment Level"),
-            Cell::new(config.commitment_level),
-        ])
-        .add_row(vec![
-            Cell::new("Keypair Path"),
-            Cell::new(config.keypair_path.display()),
-        ]);
-
-    println!("\n{}", style("SCILLA CONFIG").green().bold());
-    println!("{}", table);
+impl CommandOutput for ScillaConfig {
+    fn print_table(&self) {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_header(vec![
+                Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
+            ])
+            .add_row(vec![Cell::new("RPC URL"), Cell::new(&self.rpc_url)])
+            .add_row(vec![
+                Cell::new("Commitment Level"),
+                Cell::new(self.commitment_level),
+            ])
+            .add_row(vec![
+                Cell::new("Keypair Path"),
+                Cell::new(self.keypair_path.display()),
+            ])
+            .add_row(vec![
+                Cell::new("Compute Unit Limit"),
+                Cell::new(
+                    self.compute_unit_limit
+                        .map_or_else(|| "Not set".to_string(), |limit| limit.to_string()),
+                ),
+            ])
+            .add_row(vec![
+                Cell::new("Compute Unit Price"),
+                Cell::new(
+                    self.compute_unit_price
+                        .map_or_else(|| "Not set".to_string(), |price| price.to_string()),
+                ),
+            ]);
+
+        println!("\n{}", style("SCILLA CONFIG").green().bold());
+        println!("{}", table);
+    }
+}
 
-    Ok(())
+fn show_config() -> anyhow::Result<ScillaConfig> {
+    Ok(ScillaConfig::load()?)
 }
 
 pub fn generate_config() -> anyhow::Result<()> {
@@ -201,6 +261,8 @@ pub fn generate_config() -> anyhow::Result<()> {
             rpc_url,
             commitment_level,
             keypair_path,
+            compute_unit_limit: None,
+            compute_unit_price: None,
         }
     };
 
@@ -222,9 +284,32 @@ pub fn generate_config() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn edit_config(ctx: &mut ScillaContext) -> anyhow::Result<()> {
+fn edit_config(ctx: &mut ScillaContext, args: &EditConfigArgs) -> anyhow::Result<()> {
     let mut config = ScillaConfig::load()?;
 
+    if !args.is_empty() {
+        if let Some(rpc_url) = &args.rpc_url {
+            config.rpc_url = rpc_url.clone();
+        }
+        if let Some(commitment_level) = args.commitment_level {
+            config.commitment_level = commitment_level;
+        }
+        if let Some(keypair_path) = &args.keypair_path {
+            if !keypair_path.exists() {
+                bail!("Keypair file not found at: {}", keypair_path.display());
+            }
+            config.keypair_path = keypair_path.clone();
+        }
+        if let Some(compute_unit_limit) = args.compute_unit_limit {
+            config.compute_unit_limit = Some(compute_unit_limit);
+        }
+        if let Some(compute_unit_price) = args.compute_unit_price {
+            config.compute_unit_price = Some(compute_unit_price);
+        }
+
+        return save_config(ctx, config);
+    }
+
     println!("\n{}", style("Edit Config").green().bold());
 
     // Show current configuration
@@ -239,6 +324,16 @@ fn edit_config(ctx: &mut ScillaContext) -> anyhow::Result<()> {
         style("Current Keypair Path:").cyan(),
         config.keypair_path.display()
     );
+    println!(
+        "{} {:?}",
+        style("Current Compute Unit Limit:").cyan(),
+        config.compute_unit_limit
+    );
+    println!(
+        "{} {:?}",
+        style("Current Compute Unit Price:").cyan(),
+        config.compute_unit_price.map(|price| price.to_string())
+    );
 
     // Prompt user to select which field to edit
     let field_options = ConfigField::all();
@@ -277,9 +372,31 @@ fn edit_config(ctx: &mut ScillaContext) -> anyhow::Result<()> {
             config.keypair_path = keypair_input;
             break;
         },
+        ConfigField::ComputeUnitLimit => {
+            let input: String =
+                prompt_input_data("Enter compute unit limit (leave empty to unset):");
+            config.compute_unit_limit = match input.trim() {
+                "" => None,
+                limit => Some(parse_compute_unit_limit(limit)?),
+            };
+        }
+        ConfigField::ComputeUnitPrice => {
+            let input: String = prompt_input_data(
+                "Enter compute unit price in micro-lamports, `auto` or `auto:<percentile>` (leave \
+                 empty to unset):",
+            );
+            config.compute_unit_price = match input.trim() {
+                "" => None,
+                price => Some(price.parse()?),
+            };
+        }
         ConfigField::None => return Ok(()),
     }
 
+    save_config(ctx, config)
+}
+
+fn save_config(ctx: &mut ScillaContext, config: ScillaConfig) -> anyhow::Result<()> {
     // Write updated config
     let config_path = scilla_config_path();
     let toml_string = toml::to_string_pretty(&config)?;
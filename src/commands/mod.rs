@@ -5,7 +5,9 @@ use {
             stake::StakeCommand, transaction::TransactionCommand, vote::VoteCommand,
         },
         context::ScillaContext,
+        error::ScillaResult,
     },
+    clap::Subcommand,
     console::style,
     std::{
         fmt,
@@ -28,24 +30,39 @@ pub enum CommandFlow<T> {
 
 impl<T> Termination for CommandFlow<T> {
     fn report(self) -> std::process::ExitCode {
-        println!("{}", style("Goodbye 👋").dim());
+        if let CommandFlow::Exit = self {
+            println!("{}", style("Goodbye 👋").dim());
+        }
         ExitCode::SUCCESS
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Subcommand)]
 pub enum Command {
+    /// Query the state of the Solana cluster
+    #[command(subcommand)]
     Cluster(ClusterCommand),
+    /// Manage stake accounts
+    #[command(subcommand)]
     Stake(StakeCommand),
+    /// Manage wallets and on-chain accounts
+    #[command(subcommand)]
     Account(AccountCommand),
+    /// Manage validator vote accounts
+    #[command(subcommand)]
     Vote(VoteCommand),
+    /// Inspect and send transactions
+    #[command(subcommand)]
     Transaction(TransactionCommand),
+    /// Manage the Scilla configuration
+    #[command(subcommand, name = "config")]
     ScillaConfig(ConfigCommand),
+    #[command(skip)]
     Exit,
 }
 
 impl Command {
-    pub async fn process_command(&self, ctx: &mut ScillaContext) -> CommandFlow<()> {
+    pub async fn process_command(&self, ctx: &mut ScillaContext) -> ScillaResult<()> {
         match self {
             Command::Cluster(cluster_command) => cluster_command.process_command(ctx).await,
             Command::Stake(stake_command) => stake_command.process_command(ctx).await,
@@ -55,7 +72,7 @@ impl Command {
                 transaction_command.process_command(ctx).await
             }
             Command::ScillaConfig(config_command) => config_command.process_command(ctx),
-            Command::Exit => CommandFlow::Exit,
+            Command::Exit => Ok(CommandFlow::Exit),
         }
     }
 }
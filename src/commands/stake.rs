@@ -2,64 +2,821 @@ use {
     crate::{
         commands::CommandFlow,
         constants::{
-            ACTIVE_STAKE_EPOCH_BOUND, DEFAULT_EPOCH_LIMIT, LAMPORTS_PER_SOL,
-            STAKE_HISTORY_SYSVAR_ADDR,
+            ACTIVE_STAKE_EPOCH_BOUND, DEFAULT_BATCH_CONCURRENCY, DEFAULT_DERIVED_ADDRESS_COUNT,
+            DEFAULT_HISTORY_PAGE_SIZE, DEFAULT_REWARD_EPOCHS, LAMPORTS_PER_SOL,
+            MAX_MULTIPLE_ACCOUNTS, MAX_PROJECTED_EPOCHS, NEW_VALIDATOR_EPOCHS,
+            REDUCE_STAKE_WARMUP_COOLDOWN_FEATURE, STAKE_HISTORY_SYSVAR_ADDR, STAKE_STAKER_OFFSET,
+            STAKE_VOTER_OFFSET, STAKE_WITHDRAWER_OFFSET,
         },
         context::ScillaContext,
-        misc::helpers::{
-            SolAmount, bincode_deserialize, bincode_deserialize_with_limit, build_and_send_tx,
-            check_minimum_balance, fetch_account_with_epoch, lamports_to_sol,
-            read_keypair_from_path, sol_to_lamports,
+        error::ScillaResult,
+        misc::{
+            batch::{Batch, pack_instructions, pin_batch_tx_args},
+            compute_budget::ComputeBudgetArgs,
+            helpers::{
+                SolAmount, TxArgs, TxOutcome, bincode_deserialize, bincode_deserialize_with_limit,
+                build_and_send_tx, build_tx, check_minimum_balance, fetch_account_with_epoch,
+                format_duration, lamports_to_sol, read_keypair_from_path, sol_to_lamports,
+            },
+            rewards::{
+                StakeReward, epochs_credited_between, fetch_first_epoch_starting_from,
+                fetch_stake_rewards,
+            },
         },
-        prompt::{prompt_input_data, prompt_keypair_path},
-        ui::show_spinner,
+        output::CommandOutput,
+        prompt::{
+            prompt_input_data, prompt_keypair_path, prompt_optional_input_data, prompt_select_data,
+        },
+        ui::{print_error, print_status, report_error, show_spinner, suspend_spinner},
     },
-    anyhow::{anyhow, bail},
+    anyhow::{Context, anyhow, bail},
+    chrono::{NaiveDate, NaiveTime},
+    clap::{Args, Subcommand, ValueEnum},
     comfy_table::{Cell, Table, presets::UTF8_FULL},
     console::style,
-    solana_clock::Clock,
-    solana_keypair::Signer,
+    futures::{StreamExt, stream},
+    inquire::Confirm,
+    serde::Serialize,
+    solana_account::Account,
+    solana_account_decoder_client_types::UiAccountEncoding,
+    solana_clock::{Clock, DEFAULT_MS_PER_SLOT, Epoch, Slot, UnixTimestamp},
+    solana_epoch_info::EpochInfo,
+    solana_epoch_schedule::EpochSchedule,
+    solana_feature_gate_interface::Feature,
+    solana_instruction::Instruction,
+    solana_keypair::{Keypair, Signer},
     solana_pubkey::Pubkey,
     solana_rpc_client_api::{
-        config::RpcGetVoteAccountsConfig, request::DELINQUENT_VALIDATOR_SLOT_DISTANCE,
-        response::RpcVoteAccountStatus,
+        config::{RpcAccountInfoConfig, RpcGetVoteAccountsConfig, RpcProgramAccountsConfig},
+        filter::{Memcmp, RpcFilterType},
+        request::DELINQUENT_VALIDATOR_SLOT_DISTANCE,
+        response::{RpcVoteAccountInfo, RpcVoteAccountStatus},
     },
     solana_sdk_ids::sysvar::stake_history,
     solana_stake_interface::{
-        instruction::{self, deactivate_stake, merge, withdraw},
+        MINIMUM_DELINQUENT_EPOCHS_FOR_DEACTIVATION,
+        instruction::{
+            self, LockupArgs, deactivate_delinquent_stake, deactivate_stake, merge, withdraw,
+        },
         program::id as stake_program_id,
         stake_history::{StakeHistory, StakeHistoryEntry},
-        state::{Authorized, Lockup, Meta, StakeActivationStatus, StakeStateV2},
+        state::{
+            Authorized, Delegation, Lockup, Meta, StakeActivationStatus, StakeAuthorize,
+            StakeStateV2, warmup_cooldown_rate,
+        },
+        tools::{acceptable_reference_epoch_credits, eligible_for_deactivate_delinquent},
+    },
+    solana_sysvar::{clock, epoch_schedule},
+    solana_vote_interface::state::VoteStateV4,
+    std::{
+        cmp::Reverse,
+        collections::{BTreeMap, HashSet},
+        fmt, fs,
+        num::NonZeroUsize,
+        ops::{Div, RangeInclusive},
+        path::{Path, PathBuf},
+        str::FromStr,
     },
-    solana_sysvar::clock,
-    std::{fmt, ops::Div, path::PathBuf},
 };
 
 /// Commands related to staking operations
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Subcommand)]
 pub enum StakeCommand {
-    Create,
-    Delegate,
-    Deactivate,
-    Withdraw,
-    Merge,
-    Split,
-    Show,
-    History,
+    /// Create a new stake account
+    Create(CreateStakeArgs),
+    /// Delegate stake to a validator
+    Delegate(DelegateStakeArgs),
+    /// Begin stake cooldown
+    Deactivate(DeactivateStakeArgs),
+    /// Deactivate many stake accounts at once
+    BulkDeactivate(BulkStakeArgs),
+    /// Withdraw SOL from deactivated stake
+    Withdraw(WithdrawStakeArgs),
+    /// Withdraw the whole balance of many inactive stake accounts at once
+    BulkWithdraw(BulkWithdrawArgs),
+    /// Deactivate stake delegated to validators that stopped voting
+    DeactivateDelinquent(DeactivateDelinquentArgs),
+    /// Combine two stake accounts
+    Merge(MergeStakeArgs),
+    /// Merge every group of compatible stake accounts of an authority
+    MergeAll(MergeAllArgs),
+    /// Split stake into another account
+    Split(SplitStakeArgs),
+    /// Split a stake account into equal parts or by percentages
+    SplitParts(SplitPartsArgs),
+    /// Move active stake to another account delegated to the same validator
+    MoveStake(MoveStakeArgs),
+    /// Move undelegated lamports to another stake account
+    MoveLamports(MoveStakeArgs),
+    /// Display stake account details
+    Show(ShowStakeArgs),
+    /// List the stake accounts controlled by an authority
+    List(ListStakeArgs),
+    /// Change the stake or withdraw authority
+    Authorize(AuthorizeStakeArgs),
+    /// Update the lockup of a stake account
+    SetLockup(SetLockupArgs),
+    /// Show whether a stake account is locked up and for how long
+    ShowLockup(ShowStakeArgs),
+    /// List the stake account addresses derived from a base key for a seed
+    /// range
+    DerivedAddresses(DerivedAddressesArgs),
+    /// Show the inflation rewards earned by stake accounts over an epoch
+    /// range
+    Rewards(StakeRewardsArgs),
+    /// Write a ledger of the rewards credited to stake accounts over a date
+    /// or epoch range
+    IncomeReport(IncomeReportArgs),
+    /// Project when stake finishes warming up or cooling down
+    Eta(StakeEtaArgs),
+    /// View cluster stake history
+    History(StakeHistoryArgs),
+    #[command(skip)]
     GoBack,
 }
 
+#[derive(Debug, Clone, Default, Args)]
+pub struct StakeHistoryArgs {
+    /// Oldest epoch to show
+    #[arg(long)]
+    pub start_epoch: Option<Epoch>,
+    /// Newest epoch to show
+    #[arg(long)]
+    pub end_epoch: Option<Epoch>,
+    /// Epochs per page
+    #[arg(long)]
+    pub page_size: Option<NonZeroUsize>,
+    /// Page to show, counting from the newest epochs
+    #[arg(long)]
+    pub page: Option<NonZeroUsize>,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct CreateStakeArgs {
+    /// New stake account keypair path
+    #[arg(long, conflicts_with = "seed")]
+    pub stake_account: Option<PathBuf>,
+    /// Seed to derive the new stake account address from, instead of using a
+    /// keypair
+    #[arg(long)]
+    pub seed: Option<String>,
+    /// Base keypair path the address is derived from with `--seed` (defaults
+    /// to the configured keypair)
+    #[arg(long, requires = "seed")]
+    pub base: Option<PathBuf>,
+    /// Amount to stake in SOL
+    #[arg(long)]
+    pub amount: Option<SolAmount>,
+    /// Withdraw authority keypair path
+    #[arg(long)]
+    pub withdraw_authority: Option<PathBuf>,
+    /// Whether to set up a lockup
+    #[arg(long)]
+    pub lockup: Option<bool>,
+    /// Lockup epoch
+    #[arg(long)]
+    pub lockup_epoch: Option<u64>,
+    /// Lockup date as a unix timestamp
+    #[arg(long)]
+    pub lockup_unix_timestamp: Option<i64>,
+    /// Lockup custodian address
+    #[arg(long)]
+    pub custodian: Option<Pubkey>,
+    #[command(flatten)]
+    pub tx: TxArgs,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct DerivedAddressesArgs {
+    /// Base address (defaults to the configured keypair)
+    #[arg(long)]
+    pub base: Option<Pubkey>,
+    /// Seed prefix; each seed is the prefix followed by an index
+    #[arg(long)]
+    pub seed_prefix: Option<String>,
+    /// First index of the seed range
+    #[arg(long)]
+    pub start: Option<u32>,
+    /// Number of seeds to list
+    #[arg(long)]
+    pub count: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct StakeRewardsArgs {
+    /// Stake account to show the rewards of
+    #[arg(long, conflicts_with = "authority")]
+    pub stake: Option<Pubkey>,
+    /// Show the rewards of every stake account controlled by this authority
+    #[arg(long)]
+    pub authority: Option<Pubkey>,
+    /// First epoch of the range (defaults to covering the last 10 completed
+    /// epochs)
+    #[arg(long)]
+    pub start_epoch: Option<Epoch>,
+    /// Last epoch of the range (defaults to the last completed epoch)
+    #[arg(long)]
+    pub end_epoch: Option<Epoch>,
+    /// Write the rewards to this CSV file
+    #[arg(long)]
+    pub export: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct IncomeReportArgs {
+    /// Stake account to report on; repeat for several accounts
+    #[arg(long, conflicts_with = "authority")]
+    pub stake: Vec<Pubkey>,
+    /// Report on every stake account controlled by this authority
+    #[arg(long)]
+    pub authority: Option<Pubkey>,
+    /// First day of the period (YYYY-MM-DD, UTC)
+    #[arg(long, requires = "to", conflicts_with_all = ["start_epoch", "end_epoch"])]
+    pub from: Option<NaiveDate>,
+    /// Last day of the period, inclusive (YYYY-MM-DD, UTC)
+    #[arg(long, requires = "from")]
+    pub to: Option<NaiveDate>,
+    /// First epoch whose rewards are reported
+    #[arg(long, requires = "end_epoch")]
+    pub start_epoch: Option<Epoch>,
+    /// Last epoch whose rewards are reported
+    #[arg(long, requires = "start_epoch")]
+    pub end_epoch: Option<Epoch>,
+    /// Ledger format (defaults to the extension of the ledger file)
+    #[arg(long, value_enum)]
+    pub format: Option<LedgerFormat>,
+    /// Path of the ledger file to write
+    #[arg(long)]
+    pub out: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LedgerFormat {
+    Csv,
+    Json,
+}
+
+impl LedgerFormat {
+    fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("json") => LedgerFormat::Json,
+            _ => LedgerFormat::Csv,
+        }
+    }
+}
+
+impl fmt::Display for LedgerFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LedgerFormat::Csv => write!(f, "CSV"),
+            LedgerFormat::Json => write!(f, "JSON"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReportPeriod {
+    Dates,
+    Epochs,
+}
+
+impl fmt::Display for ReportPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReportPeriod::Dates => write!(f, "Date range"),
+            ReportPeriod::Epochs => write!(f, "Epoch range"),
+        }
+    }
+}
+
+/// Period an income report covers
+pub enum IncomePeriod {
+    Dates { from: NaiveDate, to: NaiveDate },
+    Epochs(RangeInclusive<Epoch>),
+}
+
+/// Comma separated list of pubkeys entered at a prompt
+struct PubkeyList(Vec<Pubkey>);
+
+impl FromStr for PubkeyList {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|pubkey| !pubkey.is_empty())
+            .map(|pubkey| {
+                pubkey
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid pubkey: {pubkey}"))
+            })
+            .collect::<anyhow::Result<_>>()
+            .map(PubkeyList)
+    }
+}
+
+/// Address of a new stake account, either the pubkey of a keypair or derived
+/// from a base keypair and a seed
+pub enum NewStakeAccount {
+    Keypair(PathBuf),
+    Seed { base: PathBuf, seed: String },
+}
+
+impl NewStakeAccount {
+    /// Resolves the address from the given arguments, asking for a seed or a
+    /// keypair when neither is set.
+    fn from_args(
+        keypair: Option<&PathBuf>,
+        seed: Option<&String>,
+        base: Option<&PathBuf>,
+        label: &str,
+        ctx: &ScillaContext,
+    ) -> Self {
+        let seed_account = |seed: String, base: Option<&PathBuf>| NewStakeAccount::Seed {
+            base: base
+                .cloned()
+                .unwrap_or_else(|| prompt_keypair_path("Enter Base Keypair Path: ", ctx)),
+            seed,
+        };
+
+        match (keypair, seed) {
+            (Some(path), _) => NewStakeAccount::Keypair(path.clone()),
+            (None, Some(seed)) => NewStakeAccount::Seed {
+                base: base.cloned().unwrap_or_else(|| ctx.keypair_path().clone()),
+                seed: seed.clone(),
+            },
+            (None, None) => match prompt_optional_input_data(&format!(
+                "Enter Seed to derive the {label} address from: "
+            )) {
+                Some(seed) => seed_account(seed, base),
+                None => NewStakeAccount::Keypair(prompt_keypair_path(
+                    &format!("Enter {label} Keypair Path: "),
+                    ctx,
+                )),
+            },
+        }
+    }
+}
+
+/// Address of the stake account derived from `base` and `seed`
+pub fn derive_stake_address(base: &Pubkey, seed: &str) -> anyhow::Result<Pubkey> {
+    Pubkey::create_with_seed(base, seed, &stake_program_id())
+        .map_err(|err| anyhow!("Invalid seed {seed:?}: {err}"))
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct ShowStakeArgs {
+    /// Stake account address
+    #[arg(long)]
+    pub stake: Option<Pubkey>,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct ListStakeArgs {
+    /// Authority to list stake accounts for (defaults to the configured
+    /// keypair)
+    #[arg(long)]
+    pub authority: Option<Pubkey>,
+    /// Authority role to match
+    #[arg(long, value_enum)]
+    pub role: Option<AuthorityRole>,
+    /// Column to sort the accounts by
+    #[arg(long, value_enum)]
+    pub sort: Option<StakeAccountSort>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum AuthorityRole {
+    Staker,
+    Withdrawer,
+    Any,
+}
+
+impl fmt::Display for AuthorityRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let role = match self {
+            AuthorityRole::Staker => "Stake authority",
+            AuthorityRole::Withdrawer => "Withdraw authority",
+            AuthorityRole::Any => "Stake or withdraw authority",
+        };
+        write!(f, "{role}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum StakeAccountSort {
+    Balance,
+    Stake,
+    State,
+    Validator,
+    Address,
+}
+
+impl fmt::Display for StakeAccountSort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sort = match self {
+            StakeAccountSort::Balance => "Balance",
+            StakeAccountSort::Stake => "Active stake",
+            StakeAccountSort::State => "State",
+            StakeAccountSort::Validator => "Validator",
+            StakeAccountSort::Address => "Address",
+        };
+        write!(f, "{sort}")
+    }
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct AuthorizeStakeArgs {
+    /// Stake account address
+    #[arg(long)]
+    pub stake: Option<Pubkey>,
+    /// Authority to change
+    #[arg(long, value_enum)]
+    pub authority_type: Option<StakeAuthorityType>,
+    /// Current authority keypair path, or the base keypair of a derived
+    /// authority with `--authority-seed`
+    #[arg(long)]
+    pub authority: Option<PathBuf>,
+    /// Seed the current authority address is derived from
+    #[arg(long, requires = "authority_owner")]
+    pub authority_seed: Option<String>,
+    /// Program owning the derived current authority address
+    #[arg(long, requires = "authority_seed")]
+    pub authority_owner: Option<Pubkey>,
+    /// New authority address
+    #[arg(long, conflicts_with = "new_authority_keypair")]
+    pub new_authority: Option<Pubkey>,
+    /// New authority keypair path. The new authority co-signs, proving the
+    /// key is held (checked authorize)
+    #[arg(long)]
+    pub new_authority_keypair: Option<PathBuf>,
+    /// Lockup custodian keypair path, needed to change the withdraw authority
+    /// while a lockup is in force
+    #[arg(long)]
+    pub custodian: Option<PathBuf>,
+    #[command(flatten)]
+    pub tx: TxArgs,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StakeAuthorityType {
+    Staker,
+    Withdrawer,
+}
+
+impl fmt::Display for StakeAuthorityType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let authority = match self {
+            StakeAuthorityType::Staker => "Stake authority",
+            StakeAuthorityType::Withdrawer => "Withdraw authority",
+        };
+        write!(f, "{authority}")
+    }
+}
+
+impl From<StakeAuthorityType> for StakeAuthorize {
+    fn from(authority_type: StakeAuthorityType) -> Self {
+        match authority_type {
+            StakeAuthorityType::Staker => StakeAuthorize::Staker,
+            StakeAuthorityType::Withdrawer => StakeAuthorize::Withdrawer,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct SetLockupArgs {
+    /// Stake account address
+    #[arg(long)]
+    pub stake: Option<Pubkey>,
+    /// Lockup custodian keypair path while the lockup is in force, otherwise
+    /// the withdraw authority keypair path
+    #[arg(long)]
+    pub authority: Option<PathBuf>,
+    /// New lockup epoch
+    #[arg(long)]
+    pub epoch: Option<u64>,
+    /// New lockup date as a unix timestamp
+    #[arg(long)]
+    pub unix_timestamp: Option<i64>,
+    /// New lockup custodian address
+    #[arg(long, conflicts_with = "new_custodian_keypair")]
+    pub new_custodian: Option<Pubkey>,
+    /// New lockup custodian keypair path. The new custodian co-signs, proving
+    /// the key is held (checked set lockup)
+    #[arg(long)]
+    pub new_custodian_keypair: Option<PathBuf>,
+    #[command(flatten)]
+    pub tx: TxArgs,
+}
+
+/// New authority, either only its address or its keypair for a checked
+/// authorize
+enum NewStakeAuthority {
+    Address(Pubkey),
+    Signer(PathBuf),
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct DelegateStakeArgs {
+    /// Stake account address
+    #[arg(long)]
+    pub stake: Option<Pubkey>,
+    /// Vote account address to delegate to
+    #[arg(long)]
+    pub vote: Option<Pubkey>,
+    /// Stake authority keypair path
+    #[arg(long)]
+    pub stake_authority: Option<PathBuf>,
+    /// Delegate to a delinquent validator without confirming (needed with
+    /// --yes)
+    #[arg(long)]
+    pub force: bool,
+    #[command(flatten)]
+    pub tx: TxArgs,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct DeactivateStakeArgs {
+    /// Stake account address
+    #[arg(long)]
+    pub stake: Option<Pubkey>,
+    #[command(flatten)]
+    pub tx: TxArgs,
+}
+
+/// Bulk operations are sent right away with the configured keypair as
+/// signer and fee payer, so they don't flatten `TxArgs` and take no
+/// --sign-only, --nonce or --fee-payer.
+#[derive(Debug, Clone, Default, Args)]
+pub struct BulkStakeArgs {
+    /// File with one stake account address per line (defaults to every stake
+    /// account of the configured keypair)
+    #[arg(long)]
+    pub file: Option<PathBuf>,
+    /// Number of transactions in flight at once
+    #[arg(long)]
+    pub concurrency: Option<NonZeroUsize>,
+    #[command(flatten, next_help_heading = "Transaction Options")]
+    pub compute_budget: ComputeBudgetArgs,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct DeactivateDelinquentArgs {
+    /// Delinquent vote account (defaults to every delinquent validator)
+    #[arg(long)]
+    pub vote: Option<Pubkey>,
+    /// Vote account that voted in each of the last 5 epochs, as proof the
+    /// cluster was live (defaults to the most staked one)
+    #[arg(long)]
+    pub reference_vote: Option<Pubkey>,
+    /// Number of transactions in flight at once
+    #[arg(long)]
+    pub concurrency: Option<NonZeroUsize>,
+    #[command(flatten, next_help_heading = "Transaction Options")]
+    pub compute_budget: ComputeBudgetArgs,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct BulkWithdrawArgs {
+    #[command(flatten)]
+    pub bulk: BulkStakeArgs,
+    /// Recipient address
+    #[arg(long)]
+    pub recipient: Option<Pubkey>,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct WithdrawStakeArgs {
+    /// Stake account address
+    #[arg(long)]
+    pub stake: Option<Pubkey>,
+    /// Recipient address
+    #[arg(long)]
+    pub recipient: Option<Pubkey>,
+    /// Amount to withdraw in SOL
+    #[arg(long)]
+    pub amount: Option<SolAmount>,
+    #[command(flatten)]
+    pub tx: TxArgs,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct MergeStakeArgs {
+    /// Destination stake account address
+    #[arg(long)]
+    pub destination: Option<Pubkey>,
+    /// Source stake account address
+    #[arg(long)]
+    pub source: Option<Pubkey>,
+    /// Stake authority keypair path
+    #[arg(long)]
+    pub stake_authority: Option<PathBuf>,
+    #[command(flatten)]
+    pub tx: TxArgs,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct MoveStakeArgs {
+    /// Stake account address to move from
+    #[arg(long)]
+    pub source: Option<Pubkey>,
+    /// Stake account address to move to
+    #[arg(long)]
+    pub destination: Option<Pubkey>,
+    /// Stake authority keypair path of both accounts
+    #[arg(long)]
+    pub stake_authority: Option<PathBuf>,
+    /// Amount to move in SOL
+    #[arg(long)]
+    pub amount: Option<SolAmount>,
+    #[command(flatten)]
+    pub tx: TxArgs,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct SplitStakeArgs {
+    /// Stake account address to split from
+    #[arg(long)]
+    pub stake: Option<Pubkey>,
+    /// Split stake account address
+    #[arg(long, conflicts_with = "split_stake_seed")]
+    pub split_stake: Option<Pubkey>,
+    /// Seed to derive the split stake account address from
+    #[arg(long)]
+    pub split_stake_seed: Option<String>,
+    /// Base keypair path the split stake account address is derived from with
+    /// `--split-stake-seed` (defaults to the configured keypair)
+    #[arg(long, requires = "split_stake_seed")]
+    pub base: Option<PathBuf>,
+    /// Stake authority keypair path
+    #[arg(long)]
+    pub stake_authority: Option<PathBuf>,
+    /// Amount to split in SOL
+    #[arg(long)]
+    pub amount: Option<f64>,
+    #[command(flatten)]
+    pub tx: TxArgs,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct StakeEtaArgs {
+    /// Stake account to project
+    #[arg(long, conflicts_with = "amount")]
+    pub stake: Option<Pubkey>,
+    /// Amount in SOL to project instead of a stake account
+    #[arg(long, requires = "direction")]
+    pub amount: Option<SolAmount>,
+    /// Whether the amount starts warming up or cooling down this epoch
+    #[arg(long, value_enum, requires = "amount")]
+    pub direction: Option<StakeDirection>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StakeDirection {
+    Activate,
+    Deactivate,
+}
+
+impl fmt::Display for StakeDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StakeDirection::Activate => write!(f, "Activate"),
+            StakeDirection::Deactivate => write!(f, "Deactivate"),
+        }
+    }
+}
+
+/// Stake whose warmup or cooldown is projected
+pub enum EtaTarget {
+    Account(Pubkey),
+    /// An amount in lamports delegated or deactivated this epoch
+    Amount(u64, StakeDirection),
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct MergeAllArgs {
+    /// Stake authority keypair path whose stake accounts are merged
+    #[arg(long)]
+    pub stake_authority: Option<PathBuf>,
+    /// Number of transactions in flight at once
+    #[arg(long)]
+    pub concurrency: Option<NonZeroUsize>,
+    #[command(flatten, next_help_heading = "Transaction Options")]
+    pub compute_budget: ComputeBudgetArgs,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct SplitPartsArgs {
+    /// Stake account address to split
+    #[arg(long)]
+    pub stake: Option<Pubkey>,
+    /// Number of equal parts, including the one staying in the account
+    #[arg(long, conflicts_with = "percentages")]
+    pub parts: Option<u32>,
+    /// Comma separated percentages of the stake per part, e.g. `50,30,20`;
+    /// the first part stays in the account
+    #[arg(long)]
+    pub percentages: Option<Percentages>,
+    /// Derive the new accounts from seeds `{prefix}{index}` instead of
+    /// generating keypairs
+    #[arg(long)]
+    pub seed_prefix: Option<String>,
+    /// First seed index
+    #[arg(long, requires = "seed_prefix")]
+    pub seed_start: Option<u32>,
+    /// Base keypair path the addresses are derived from (defaults to the
+    /// configured keypair)
+    #[arg(long, requires = "seed_prefix")]
+    pub base: Option<PathBuf>,
+    /// Stake authority keypair path
+    #[arg(long)]
+    pub stake_authority: Option<PathBuf>,
+    #[command(flatten, next_help_heading = "Transaction Options")]
+    pub compute_budget: ComputeBudgetArgs,
+}
+
+/// Comma separated list of percentages
+#[derive(Debug, Clone, PartialEq)]
+pub struct Percentages(Vec<f64>);
+
+impl FromStr for Percentages {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(|percentage| {
+                let percentage = percentage.trim().trim_end_matches('%');
+                percentage
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid percentage: {percentage}"))
+            })
+            .collect::<anyhow::Result<_>>()
+            .map(Percentages)
+    }
+}
+
+/// How a stake account is divided, the first part staying in the account
+#[derive(Debug, Clone, PartialEq)]
+pub enum SplitShares {
+    Equal(u32),
+    Percentages(Vec<f64>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SplitMode {
+    Equal,
+    Percentages,
+}
+
+impl fmt::Display for SplitMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SplitMode::Equal => write!(f, "Equal parts"),
+            SplitMode::Percentages => write!(f, "Percentages"),
+        }
+    }
+}
+
+/// Where the new accounts of a split into parts are created
+pub enum SplitDestinations {
+    /// At the addresses of newly generated keypairs
+    Generate,
+    /// At the addresses derived from `base` for the seeds `{prefix}{index}`
+    Seeds {
+        base: PathBuf,
+        prefix: String,
+        start: u32,
+    },
+}
+
 impl StakeCommand {
     pub fn spinner_msg(&self) -> &'static str {
         match self {
-            StakeCommand::Create => "Creating new stake account…",
-            StakeCommand::Delegate => "Delegating stake to validator…",
-            StakeCommand::Deactivate => "Deactivating stake (cooldown starting)…",
-            StakeCommand::Withdraw => "Withdrawing SOL from deactivated stake…",
-            StakeCommand::Merge => "Merging stake accounts…",
-            StakeCommand::Split => "Splitting stake into multiple accounts…",
-            StakeCommand::Show => "Fetching stake account details…",
-            StakeCommand::History => "Fetching stake account history…",
+            StakeCommand::Create(_) => "Creating new stake account…",
+            StakeCommand::Delegate(_) => "Delegating stake to validator…",
+            StakeCommand::Deactivate(_) => "Deactivating stake (cooldown starting)…",
+            StakeCommand::BulkDeactivate(_) => "Deactivating stake accounts…",
+            StakeCommand::DeactivateDelinquent(_) => "Deactivating delinquent stake…",
+            StakeCommand::Withdraw(_) => "Withdrawing SOL from deactivated stake…",
+            StakeCommand::BulkWithdraw(_) => "Withdrawing from stake accounts…",
+            StakeCommand::Merge(_) => "Merging stake accounts…",
+            StakeCommand::MergeAll(_) => "Merging compatible stake accounts…",
+            StakeCommand::Split(_) => "Splitting stake into multiple accounts…",
+            StakeCommand::SplitParts(_) => "Splitting stake into parts…",
+            StakeCommand::MoveStake(_) => "Moving stake…",
+            StakeCommand::MoveLamports(_) => "Moving lamports…",
+            StakeCommand::Show(_) => "Fetching stake account details…",
+            StakeCommand::List(_) => "Fetching stake accounts…",
+            StakeCommand::Authorize(_) => "Changing stake authority…",
+            StakeCommand::SetLockup(_) => "Updating stake lockup…",
+            StakeCommand::ShowLockup(_) => "Fetching stake lockup…",
+            StakeCommand::DerivedAddresses(_) => "Deriving stake account addresses…",
+            StakeCommand::Rewards(_) => "Fetching stake rewards…",
+            StakeCommand::IncomeReport(_) => "Building staking income report…",
+            StakeCommand::Eta(_) => "Projecting stake warmup and cooldown…",
+            StakeCommand::History(_) => "Fetching stake account history…",
             StakeCommand::GoBack => "Going back…",
         }
     }
@@ -68,14 +825,29 @@ impl StakeCommand {
 impl fmt::Display for StakeCommand {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let command = match self {
-            StakeCommand::Create => "Create stake account",
-            StakeCommand::Delegate => "Delegate stake",
-            StakeCommand::Deactivate => "Deactivate stake",
-            StakeCommand::Withdraw => "Withdraw stake",
-            StakeCommand::Merge => "Merge stake accounts",
-            StakeCommand::Split => "Split stake account",
-            StakeCommand::Show => "Show stake",
-            StakeCommand::History => "View stake history",
+            StakeCommand::Create(_) => "Create stake account",
+            StakeCommand::Delegate(_) => "Delegate stake",
+            StakeCommand::Deactivate(_) => "Deactivate stake",
+            StakeCommand::BulkDeactivate(_) => "Bulk deactivate stake",
+            StakeCommand::DeactivateDelinquent(_) => "Deactivate delinquent stake",
+            StakeCommand::Withdraw(_) => "Withdraw stake",
+            StakeCommand::BulkWithdraw(_) => "Bulk withdraw stake",
+            StakeCommand::Merge(_) => "Merge stake accounts",
+            StakeCommand::MergeAll(_) => "Merge all compatible stake accounts",
+            StakeCommand::Split(_) => "Split stake account",
+            StakeCommand::SplitParts(_) => "Split stake into parts",
+            StakeCommand::MoveStake(_) => "Move stake",
+            StakeCommand::MoveLamports(_) => "Move lamports",
+            StakeCommand::Show(_) => "Show stake",
+            StakeCommand::List(_) => "List stake accounts",
+            StakeCommand::Authorize(_) => "Change stake authority",
+            StakeCommand::SetLockup(_) => "Set stake lockup",
+            StakeCommand::ShowLockup(_) => "Show stake lockup",
+            StakeCommand::DerivedAddresses(_) => "List derived stake addresses",
+            StakeCommand::Rewards(_) => "Show stake rewards",
+            StakeCommand::IncomeReport(_) => "Staking income report",
+            StakeCommand::Eta(_) => "Stake activation ETA",
+            StakeCommand::History(_) => "View stake history",
             StakeCommand::GoBack => "Go back",
         };
         write!(f, "{command}")
@@ -83,22 +855,37 @@ impl fmt::Display for StakeCommand {
 }
 
 impl StakeCommand {
-    pub async fn process_command(&self, ctx: &ScillaContext) -> CommandFlow<()> {
+    pub async fn process_command(&self, ctx: &ScillaContext) -> ScillaResult<()> {
         match self {
-            StakeCommand::Create => {
-                let stake_account_keypair_path: PathBuf =
-                    prompt_keypair_path("Enter Stake Account Keypair Path: ", ctx);
-                let amount_sol: SolAmount = prompt_input_data("Enter amount to stake (in SOL):");
+            StakeCommand::Create(args) => {
+                let new_stake_account = NewStakeAccount::from_args(
+                    args.stake_account.as_ref(),
+                    args.seed.as_ref(),
+                    args.base.as_ref(),
+                    "Stake Account",
+                    ctx,
+                );
+                let amount_sol: SolAmount = args
+                    .amount
+                    .unwrap_or_else(|| prompt_input_data("Enter amount to stake (in SOL):"));
                 let withdraw_authority_keypair_path: PathBuf =
-                    prompt_keypair_path("Enter Withdraw Authority Keypair Path: ", ctx);
-                let configure_lockup: bool =
-                    prompt_input_data("Would you like to set up lockup configuration? (y/n): ");
+                    args.withdraw_authority.clone().unwrap_or_else(|| {
+                        prompt_keypair_path("Enter Withdraw Authority Keypair Path: ", ctx)
+                    });
+                let configure_lockup: bool = args.lockup.unwrap_or_else(|| {
+                    prompt_input_data("Would you like to set up lockup configuration? (y/n): ")
+                });
 
                 let lockup = if configure_lockup {
-                    let epoch: u64 = prompt_input_data("Enter Lockup Epoch: ");
-                    let unix_timestamp: i64 =
-                        prompt_input_data("Enter Lockup Date (Unix TimeStamp): ");
-                    let custodian: Pubkey = prompt_input_data("Enter Lockup Custodian Pubkey: ");
+                    let epoch: u64 = args
+                        .lockup_epoch
+                        .unwrap_or_else(|| prompt_input_data("Enter Lockup Epoch: "));
+                    let unix_timestamp: i64 = args.lockup_unix_timestamp.unwrap_or_else(|| {
+                        prompt_input_data("Enter Lockup Date (Unix TimeStamp): ")
+                    });
+                    let custodian: Pubkey = args
+                        .custodian
+                        .unwrap_or_else(|| prompt_input_data("Enter Lockup Custodian Pubkey: "));
 
                     Lockup {
                         epoch,
@@ -109,930 +896,6174 @@ impl StakeCommand {
                     Lockup::default()
                 };
 
-                show_spinner(
+                let created = show_spinner(
                     self.spinner_msg(),
                     process_create_stake_account(
                         ctx,
-                        stake_account_keypair_path,
+                        new_stake_account,
                         amount_sol,
                         withdraw_authority_keypair_path,
                         lockup,
+                        &args.tx,
                     ),
                 )
-                .await;
+                .await?;
+                ctx.output_format().render(&created)?;
             }
-            StakeCommand::Delegate => {
-                let stake_account_pubkey: Pubkey =
-                    prompt_input_data("Enter Stake Account Pubkey: ");
-                let vote_account_pubkey: Pubkey = prompt_input_data("Enter Vote Account Pubkey: ");
+            StakeCommand::Delegate(args) => {
+                let stake_account_pubkey: Pubkey = args
+                    .stake
+                    .unwrap_or_else(|| prompt_input_data("Enter Stake Account Pubkey: "));
+                let vote_account_pubkey: Pubkey = args
+                    .vote
+                    .unwrap_or_else(|| prompt_input_data("Enter Vote Account Pubkey: "));
                 let stake_authority_keypair_path: PathBuf =
-                    prompt_keypair_path("Enter Stake Authority Keypair Path: ", ctx);
+                    args.stake_authority.clone().unwrap_or_else(|| {
+                        prompt_keypair_path("Enter Stake Authority Keypair Path: ", ctx)
+                    });
 
-                show_spinner(
+                let delegated = show_spinner(
                     self.spinner_msg(),
                     delegate_stake_account(
                         ctx,
                         &stake_account_pubkey,
                         &vote_account_pubkey,
                         stake_authority_keypair_path,
+                        args.force,
+                        &args.tx,
                     ),
                 )
-                .await;
+                .await?;
+                ctx.output_format().render(&delegated)?;
             }
-            StakeCommand::Deactivate => {
-                let stake_pubkey: Pubkey =
-                    prompt_input_data("Enter Stake Account Pubkey to Deactivate:");
-                show_spinner(
+            StakeCommand::Deactivate(args) => {
+                let stake_pubkey: Pubkey = args.stake.unwrap_or_else(|| {
+                    prompt_input_data("Enter Stake Account Pubkey to Deactivate:")
+                });
+                let deactivated = show_spinner(
                     self.spinner_msg(),
-                    process_deactivate_stake_account(ctx, &stake_pubkey),
+                    process_deactivate_stake_account(ctx, &stake_pubkey, &args.tx),
                 )
-                .await;
+                .await?;
+                ctx.output_format().render(&deactivated)?;
+            }
+            StakeCommand::BulkDeactivate(args) => {
+                return process_bulk_stake_command(ctx, self, BulkOperation::Deactivate, args)
+                    .await;
             }
-            StakeCommand::Withdraw => {
-                let stake_pubkey: Pubkey =
-                    prompt_input_data("Enter Stake Account Pubkey to Withdraw from:");
-                let recipient: Pubkey = prompt_input_data("Enter Recipient Address:");
-                let amount: SolAmount = prompt_input_data("Enter Amount to Withdraw (SOL):");
+            StakeCommand::DeactivateDelinquent(args) => {
+                let vote = match args.vote {
+                    Some(vote) => Some(vote),
+                    None if ctx.is_interactive() => prompt_optional_input_data(
+                        "Enter Delinquent Vote Account Pubkey (empty for every delinquent \
+                         validator): ",
+                    ),
+                    None => None,
+                };
+                let concurrency = args
+                    .concurrency
+                    .map_or(DEFAULT_BATCH_CONCURRENCY, NonZeroUsize::get);
+
+                let plan = show_spinner(
+                    "Finding delinquent stake…",
+                    prepare_deactivate_delinquent(
+                        ctx,
+                        vote,
+                        args.reference_vote,
+                        &args.compute_budget,
+                    ),
+                )
+                .await?;
+                plan.print(ctx);
 
-                show_spinner(
+                if plan.stake.batches.is_empty() {
+                    print_status(
+                        ctx,
+                        style("No stake is delegated to a delinquent validator").yellow(),
+                    );
+                    if ctx.output_format().is_json() {
+                        ctx.output_format().render(&plan.stake.output)?;
+                    }
+                    return Ok(CommandFlow::Process(()));
+                }
+                if !ctx.skip_confirmation()
+                    && !Confirm::new(&format!("Send {} transactions?", plan.stake.batches.len()))
+                        .with_default(false)
+                        .prompt()
+                        .unwrap_or(false)
+                {
+                    return Err(report_error(anyhow!(
+                        "Delinquent stake deactivation aborted"
+                    )));
+                }
+
+                let deactivated = show_spinner(
                     self.spinner_msg(),
-                    process_withdraw_stake(ctx, &stake_pubkey, &recipient, amount.value()),
+                    process_bulk_stake(ctx, plan.stake, concurrency),
+                )
+                .await?;
+                ctx.output_format().render(&deactivated)?;
+
+                if deactivated.failed > 0 {
+                    let err = anyhow!("{} stake accounts failed to deactivate", deactivated.failed);
+                    return Err(report_error(err));
+                }
+            }
+            StakeCommand::BulkWithdraw(args) => {
+                let recipient: Pubkey = args
+                    .recipient
+                    .unwrap_or_else(|| prompt_input_data("Enter Recipient Address:"));
+                return process_bulk_stake_command(
+                    ctx,
+                    self,
+                    BulkOperation::Withdraw { recipient },
+                    &args.bulk,
                 )
                 .await;
             }
-            StakeCommand::Merge => {
-                let destination_stake_account_pubkey: Pubkey =
-                    prompt_input_data("Enter Stake Account Pubkey: ");
-                let source_stake_account_pubkey: Pubkey =
-                    prompt_input_data("Enter Source Stake Account Pubkey: ");
+            StakeCommand::Withdraw(args) => {
+                let stake_pubkey: Pubkey = args.stake.unwrap_or_else(|| {
+                    prompt_input_data("Enter Stake Account Pubkey to Withdraw from:")
+                });
+                let recipient: Pubkey = args
+                    .recipient
+                    .unwrap_or_else(|| prompt_input_data("Enter Recipient Address:"));
+                let amount: SolAmount = args
+                    .amount
+                    .unwrap_or_else(|| prompt_input_data("Enter Amount to Withdraw (SOL):"));
+
+                let withdrawn = show_spinner(
+                    self.spinner_msg(),
+                    process_withdraw_stake(
+                        ctx,
+                        &stake_pubkey,
+                        &recipient,
+                        amount.value(),
+                        &args.tx,
+                    ),
+                )
+                .await?;
+                ctx.output_format().render(&withdrawn)?;
+            }
+            StakeCommand::Merge(args) => {
+                let destination_stake_account_pubkey: Pubkey = args
+                    .destination
+                    .unwrap_or_else(|| prompt_input_data("Enter Stake Account Pubkey: "));
+                let source_stake_account_pubkey: Pubkey = args
+                    .source
+                    .unwrap_or_else(|| prompt_input_data("Enter Source Stake Account Pubkey: "));
                 let stake_authority_keypair_path =
-                    prompt_keypair_path("Enter Stake Authority Keypair Path: ", ctx);
+                    args.stake_authority.clone().unwrap_or_else(|| {
+                        prompt_keypair_path("Enter Stake Authority Keypair Path: ", ctx)
+                    });
 
-                show_spinner(
+                let merged = show_spinner(
                     self.spinner_msg(),
                     process_merge_stake(
                         ctx,
                         &destination_stake_account_pubkey,
                         &source_stake_account_pubkey,
                         &stake_authority_keypair_path,
+                        &args.tx,
                     ),
                 )
-                .await;
+                .await?;
+                ctx.output_format().render(&merged)?;
             }
-            StakeCommand::Split => {
-                let stake_account_pubkey: Pubkey =
-                    prompt_input_data("Enter Stake Account Pubkey: ");
-                let split_stake_account_pubkey: Pubkey =
-                    prompt_input_data("Enter Split Stake Account Pubkey: ");
+            StakeCommand::Split(args) => {
+                let stake_account_pubkey: Pubkey = args
+                    .stake
+                    .unwrap_or_else(|| prompt_input_data("Enter Stake Account Pubkey: "));
+                let split_stake_account = match (args.split_stake, &args.split_stake_seed) {
+                    (Some(pubkey), _) => SplitStakeTarget::Address(pubkey),
+                    (None, Some(seed)) => SplitStakeTarget::Seed {
+                        base: args
+                            .base
+                            .clone()
+                            .unwrap_or_else(|| ctx.keypair_path().clone()),
+                        seed: seed.clone(),
+                    },
+                    (None, None) => match prompt_optional_input_data(
+                        "Enter Seed to derive the Split Stake Account address from: ",
+                    ) {
+                        Some(seed) => SplitStakeTarget::Seed {
+                            base: prompt_keypair_path("Enter Base Keypair Path: ", ctx),
+                            seed,
+                        },
+                        None => SplitStakeTarget::Address(prompt_input_data(
+                            "Enter Split Stake Account Pubkey: ",
+                        )),
+                    },
+                };
                 let stake_authority_keypair_path =
-                    prompt_keypair_path("Enter Stake Authority Keypair Path: ", ctx);
-                let amount_to_split: f64 = prompt_input_data("Enter Stake Amount (SOL) to Split: ");
+                    args.stake_authority.clone().unwrap_or_else(|| {
+                        prompt_keypair_path("Enter Stake Authority Keypair Path: ", ctx)
+                    });
+                let amount_to_split: f64 = args
+                    .amount
+                    .unwrap_or_else(|| prompt_input_data("Enter Stake Amount (SOL) to Split: "));
 
-                show_spinner(
+                let split = show_spinner(
                     self.spinner_msg(),
                     process_split_stake(
                         ctx,
                         &stake_account_pubkey,
-                        &split_stake_account_pubkey,
+                        split_stake_account,
                         &stake_authority_keypair_path,
                         amount_to_split,
+                        &args.tx,
                     ),
                 )
-                .await;
-            }
-            StakeCommand::Show => todo!(),
-            StakeCommand::History => {
-                show_spinner(self.spinner_msg(), process_stake_history(ctx)).await;
+                .await?;
+                ctx.output_format().render(&split)?;
             }
+            StakeCommand::MergeAll(args) => {
+                let stake_authority_keypair_path =
+                    args.stake_authority.clone().unwrap_or_else(|| {
+                        prompt_keypair_path("Enter Stake Authority Keypair Path: ", ctx)
+                    });
+                let concurrency = args
+                    .concurrency
+                    .map_or(DEFAULT_BATCH_CONCURRENCY, NonZeroUsize::get);
 
-            StakeCommand::GoBack => return CommandFlow::GoBack,
-        }
-
-        CommandFlow::Process(())
-    }
-}
+                let plan = show_spinner(
+                    "Planning merges…",
+                    prepare_merge_all(ctx, &stake_authority_keypair_path, &args.compute_budget),
+                )
+                .await?;
+                plan.print(ctx);
 
-async fn process_create_stake_account(
-    ctx: &ScillaContext,
-    stake_account_keypair_path: PathBuf,
-    amount_sol: SolAmount,
-    withdraw_authority_keypair_path: PathBuf,
-    lockup: Lockup,
-) -> anyhow::Result<()> {
-    let stake_account_keypair = read_keypair_from_path(stake_account_keypair_path)?;
-    let withdraw_authority_pubkey =
-        read_keypair_from_path(withdraw_authority_keypair_path)?.pubkey();
+                if plan.batches.is_empty() {
+                    print_status(ctx, style("No stake accounts can be merged").yellow());
+                    if ctx.output_format().is_json() {
+                        ctx.output_format().render(&plan.output)?;
+                    }
+                    return Ok(CommandFlow::Process(()));
+                }
+                if !ctx.skip_confirmation()
+                    && !Confirm::new(&format!(
+                        "Merge {} stake accounts in {} transactions?",
+                        plan.output.merges.len(),
+                        plan.batches.len()
+                    ))
+                    .with_default(false)
+                    .prompt()
+                    .unwrap_or(false)
+                {
+                    return Err(report_error(anyhow!("Merge aborted")));
+                }
 
-    let lamports = amount_sol.to_lamports();
+                let merged = show_spinner(
+                    self.spinner_msg(),
+                    process_merge_all(ctx, plan, concurrency),
+                )
+                .await?;
+                ctx.output_format().render(&merged)?;
 
-    let minimum_rent_for_balance = ctx
-        .rpc()
-        .get_minimum_balance_for_rent_exemption(StakeStateV2::size_of())
-        .await?;
+                if merged.failed > 0 {
+                    let err = anyhow!("{} merges failed", merged.failed);
+                    return Err(report_error(err));
+                }
+            }
+            StakeCommand::SplitParts(args) => {
+                let stake_pubkey: Pubkey = args
+                    .stake
+                    .unwrap_or_else(|| prompt_input_data("Enter Stake Account Pubkey: "));
+                let shares = match (args.parts, &args.percentages) {
+                    (Some(parts), _) => SplitShares::Equal(parts),
+                    (None, Some(Percentages(percentages))) => {
+                        SplitShares::Percentages(percentages.clone())
+                    }
+                    (None, None) => match prompt_select_data(
+                        "Split the stake into:",
+                        vec![SplitMode::Equal, SplitMode::Percentages],
+                    ) {
+                        SplitMode::Equal => {
+                            SplitShares::Equal(prompt_input_data("Enter Number of Parts: "))
+                        }
+                        SplitMode::Percentages => {
+                            let Percentages(percentages) = prompt_input_data(
+                                "Enter Percentages, comma separated (e.g. 50,30,20): ",
+                            );
+                            SplitShares::Percentages(percentages)
+                        }
+                    },
+                };
+                let destinations = match &args.seed_prefix {
+                    Some(prefix) => SplitDestinations::Seeds {
+                        base: args
+                            .base
+                            .clone()
+                            .unwrap_or_else(|| ctx.keypair_path().clone()),
+                        prefix: prefix.clone(),
+                        start: args.seed_start.unwrap_or(0),
+                    },
+                    None if !ctx.is_interactive() => SplitDestinations::Generate,
+                    None => match prompt_optional_input_data(
+                        "Enter Seed Prefix to derive the new accounts from (empty to generate \
+                         keypairs): ",
+                    ) {
+                        Some(prefix) => SplitDestinations::Seeds {
+                            base: prompt_keypair_path("Enter Base Keypair Path: ", ctx),
+                            prefix,
+                            start: prompt_optional_input_data("Enter First Seed Index: ")
+                                .unwrap_or(0),
+                        },
+                        None => SplitDestinations::Generate,
+                    },
+                };
+                let stake_authority_keypair_path =
+                    args.stake_authority.clone().unwrap_or_else(|| {
+                        prompt_keypair_path("Enter Stake Authority Keypair Path: ", ctx)
+                    });
 
-    // amount in SOL + rent exempt
-    let total_lamports = lamports + minimum_rent_for_balance;
-    check_minimum_balance(ctx, ctx.pubkey(), total_lamports).await?;
+                let plan = show_spinner(
+                    "Planning split…",
+                    prepare_split_parts(
+                        ctx,
+                        &stake_pubkey,
+                        &shares,
+                        destinations,
+                        &stake_authority_keypair_path,
+                        &args.compute_budget,
+                    ),
+                )
+                .await?;
+                plan.print(ctx);
 
-    if ctx.pubkey() == &stake_account_keypair.pubkey() {
-        (bail!(
-            "Stake Account {} cannot be the same as fee payer account {}",
-            stake_account_keypair.pubkey(),
-            ctx.pubkey(),
-        ));
-    }
+                if !ctx.skip_confirmation()
+                    && !Confirm::new(&format!("Send {} transactions?", plan.batches.len()))
+                        .with_default(false)
+                        .prompt()
+                        .unwrap_or(false)
+                {
+                    return Err(report_error(anyhow!("Split aborted")));
+                }
 
-    let authorized = Authorized {
-        staker: *ctx.pubkey(),
-        withdrawer: withdraw_authority_pubkey,
-    };
+                let split =
+                    show_spinner(self.spinner_msg(), process_split_parts(ctx, plan)).await?;
+                ctx.output_format().render(&split)?;
 
-    let ix = instruction::create_account(
-        ctx.pubkey(),
-        &stake_account_keypair.pubkey(),
-        &authorized,
-        &lockup,
-        total_lamports,
-    );
+                if split.failed > 0 {
+                    let err = anyhow!("{} of the parts were not split off", split.failed);
+                    return Err(report_error(err));
+                }
+            }
+            StakeCommand::MoveStake(args) | StakeCommand::MoveLamports(args) => {
+                let operation = match self {
+                    StakeCommand::MoveStake(_) => MoveOperation::Stake,
+                    _ => MoveOperation::Lamports,
+                };
+                let source: Pubkey = args
+                    .source
+                    .unwrap_or_else(|| prompt_input_data("Enter Source Stake Account Pubkey: "));
+                let destination: Pubkey = args.destination.unwrap_or_else(|| {
+                    prompt_input_data("Enter Destination Stake Account Pubkey: ")
+                });
+                let stake_authority_keypair_path =
+                    args.stake_authority.clone().unwrap_or_else(|| {
+                        prompt_keypair_path("Enter Stake Authority Keypair Path: ", ctx)
+                    });
+                let amount: SolAmount = args.amount.unwrap_or_else(|| {
+                    prompt_input_data(&format!("Enter Amount of {operation} to Move (SOL): "))
+                });
 
-    let signature = build_and_send_tx(ctx, &ix, &[ctx.keypair(), &stake_account_keypair]).await?;
+                let moved = show_spinner(
+                    self.spinner_msg(),
+                    process_move_stake(
+                        ctx,
+                        operation,
+                        &source,
+                        &destination,
+                        &stake_authority_keypair_path,
+                        amount.value(),
+                        &args.tx,
+                    ),
+                )
+                .await?;
+                ctx.output_format().render(&moved)?;
+            }
+            StakeCommand::Show(args) => {
+                let stake_pubkey: Pubkey = args
+                    .stake
+                    .unwrap_or_else(|| prompt_input_data("Enter Stake Account Pubkey: "));
+                let stake_account = show_spinner(
+                    self.spinner_msg(),
+                    fetch_stake_account_output(ctx, &stake_pubkey),
+                )
+                .await?;
+                ctx.output_format().render(&stake_account)?;
+            }
+            StakeCommand::List(args) => {
+                let authority = args.authority.unwrap_or(*ctx.pubkey());
+                let role = args.role.unwrap_or_else(|| {
+                    prompt_select_data(
+                        "List stake accounts where the authority is the:",
+                        vec![
+                            AuthorityRole::Any,
+                            AuthorityRole::Staker,
+                            AuthorityRole::Withdrawer,
+                        ],
+                    )
+                });
+                let sort = args.sort.unwrap_or_else(|| {
+                    prompt_select_data(
+                        "Sort stake accounts by:",
+                        vec![
+                            StakeAccountSort::Balance,
+                            StakeAccountSort::Stake,
+                            StakeAccountSort::State,
+                            StakeAccountSort::Validator,
+                            StakeAccountSort::Address,
+                        ],
+                    )
+                });
 
-    println!(
-        "{}\n{}",
-        style("Stake Account created successfully!").yellow().bold(),
-        style(format!("Signature: {signature}")).green()
-    );
+                let list = show_spinner(
+                    self.spinner_msg(),
+                    fetch_stake_accounts_by_authority(ctx, &authority, role, sort),
+                )
+                .await?;
+                ctx.output_format().render(&list)?;
 
-    let accounts = ctx
-        .rpc()
-        .get_multiple_accounts(&[
-            stake_account_keypair.pubkey(),
-            stake_history::id(),
-            clock::id(),
-        ])
-        .await?;
+                if ctx.is_interactive()
+                    && !ctx.output_format().is_json()
+                    && let Some(command) = prompt_stake_account_action(&list.accounts)
+                {
+                    return Box::pin(command.process_command(ctx)).await;
+                }
+            }
+            StakeCommand::Authorize(args) => {
+                let stake_pubkey: Pubkey = args
+                    .stake
+                    .unwrap_or_else(|| prompt_input_data("Enter Stake Account Pubkey: "));
+                let authority_type = args.authority_type.unwrap_or_else(|| {
+                    prompt_select_data(
+                        "Authority to change:",
+                        vec![StakeAuthorityType::Staker, StakeAuthorityType::Withdrawer],
+                    )
+                });
+                let authority_keypair_path = args.authority.clone().unwrap_or_else(|| {
+                    prompt_keypair_path("Enter Current Authority Keypair Path: ", ctx)
+                });
+                let authority_seed = args.authority_seed.clone().zip(args.authority_owner);
+                let new_authority = match (args.new_authority, &args.new_authority_keypair) {
+                    (Some(pubkey), _) => NewStakeAuthority::Address(pubkey),
+                    (None, Some(path)) => NewStakeAuthority::Signer(path.clone()),
+                    (None, None) => {
+                        let checked: bool = prompt_input_data(
+                            "Should the new authority co-sign to prove it holds the key? (y/n): ",
+                        );
+                        if checked {
+                            NewStakeAuthority::Signer(prompt_keypair_path(
+                                "Enter New Authority Keypair Path: ",
+                                ctx,
+                            ))
+                        } else {
+                            NewStakeAuthority::Address(prompt_input_data(
+                                "Enter New Authority Pubkey: ",
+                            ))
+                        }
+                    }
+                };
 
-    let Some(Some(stake_account)) = accounts.first() else {
-        bail!("Failed to get stake account");
-    };
+                let authorized = show_spinner(
+                    self.spinner_msg(),
+                    process_authorize_stake(
+                        ctx,
+                        &stake_pubkey,
+                        authority_type,
+                        &authority_keypair_path,
+                        authority_seed,
+                        new_authority,
+                        args.custodian.as_ref(),
+                        &args.tx,
+                    ),
+                )
+                .await?;
+                ctx.output_format().render(&authorized)?;
+            }
+            StakeCommand::SetLockup(args) => {
+                let stake_pubkey: Pubkey = args
+                    .stake
+                    .unwrap_or_else(|| prompt_input_data("Enter Stake Account Pubkey: "));
+                let authority_keypair_path = args.authority.clone().unwrap_or_else(|| {
+                    prompt_keypair_path(
+                        "Enter Lockup Custodian or Withdraw Authority Keypair Path: ",
+                        ctx,
+                    )
+                });
 
-    let Some(Some(stake_history_account)) = accounts.get(1) else {
-        bail!("Failed to get stake account");
-    };
+                let mut lockup = LockupArgs {
+                    unix_timestamp: args.unix_timestamp,
+                    epoch: args.epoch,
+                    custodian: args.new_custodian,
+                };
+                if lockup == LockupArgs::default() && args.new_custodian_keypair.is_none() {
+                    lockup = LockupArgs {
+                        epoch: prompt_optional_input_data("Enter New Lockup Epoch: "),
+                        unix_timestamp: prompt_optional_input_data(
+                            "Enter New Lockup Date (Unix TimeStamp): ",
+                        ),
+                        custodian: prompt_optional_input_data(
+                            "Enter New Lockup Custodian Pubkey: ",
+                        ),
+                    };
+                }
 
-    let Some(Some(clock_account)) = accounts.get(2) else {
-        bail!("Failed to get stake account");
-    };
-
-    let stake_state: StakeStateV2 = bincode_deserialize(&stake_account.data, "stake account data")?;
+                let updated = show_spinner(
+                    self.spinner_msg(),
+                    process_set_lockup(
+                        ctx,
+                        &stake_pubkey,
+                        &authority_keypair_path,
+                        lockup,
+                        args.new_custodian_keypair.as_ref(),
+                        &args.tx,
+                    ),
+                )
+                .await?;
+                ctx.output_format().render(&updated)?;
+            }
+            StakeCommand::ShowLockup(args) => {
+                let stake_pubkey: Pubkey = args
+                    .stake
+                    .unwrap_or_else(|| prompt_input_data("Enter Stake Account Pubkey: "));
+                let lockup =
+                    show_spinner(self.spinner_msg(), fetch_lockup_status(ctx, &stake_pubkey))
+                        .await?;
+                ctx.output_format().render(&lockup)?;
+            }
+            StakeCommand::DerivedAddresses(args) => {
+                let base = args.base.unwrap_or(*ctx.pubkey());
+                let seed_prefix: String = args
+                    .seed_prefix
+                    .clone()
+                    .unwrap_or_else(|| prompt_input_data("Enter Seed Prefix: "));
+                let start = args.start.unwrap_or(0);
+                let count = args.count.unwrap_or(DEFAULT_DERIVED_ADDRESS_COUNT);
 
-    let stake_history: StakeHistory =
-        bincode_deserialize(&stake_history_account.data, "stake history data")?;
+                let derived = show_spinner(
+                    self.spinner_msg(),
+                    fetch_derived_stake_addresses(ctx, &base, &seed_prefix, start, count),
+                )
+                .await?;
+                ctx.output_format().render(&derived)?;
+            }
+            StakeCommand::Rewards(args) => {
+                let (stake, authority, start_epoch, end_epoch) =
+                    if ctx.is_interactive() && args.stake.is_none() && args.authority.is_none() {
+                        (
+                            prompt_optional_input_data(
+                                "Enter Stake Account Pubkey (empty for all your stake accounts): ",
+                            ),
+                            None,
+                            args.start_epoch
+                                .or_else(|| prompt_optional_input_data("Enter Start Epoch: ")),
+                            args.end_epoch
+                                .or_else(|| prompt_optional_input_data("Enter End Epoch: ")),
+                        )
+                    } else {
+                        (args.stake, args.authority, args.start_epoch, args.end_epoch)
+                    };
+                let accounts = match stake {
+                    Some(stake) => RewardAccounts::Stakes(vec![stake]),
+                    None => RewardAccounts::Authority(authority.unwrap_or(*ctx.pubkey())),
+                };
 
-    let clock: Clock = bincode_deserialize(&clock_account.data, "clock account data")?;
+                let mut rewards = show_spinner(
+                    self.spinner_msg(),
+                    process_stake_rewards(ctx, accounts, start_epoch, end_epoch),
+                )
+                .await?;
+                if let Some(path) = &args.export {
+                    rewards.export(path).map_err(report_error)?;
+                }
+                ctx.output_format().render(&rewards)?;
+            }
+            StakeCommand::IncomeReport(args) => {
+                let accounts = match (args.stake.as_slice(), args.authority) {
+                    ([], Some(authority)) => RewardAccounts::Authority(authority),
+                    ([], None) => match prompt_optional_input_data::<PubkeyList>(
+                        "Enter Stake Account Pubkeys, comma separated (empty for all your stake \
+                         accounts): ",
+                    ) {
+                        Some(PubkeyList(stakes)) if !stakes.is_empty() => {
+                            RewardAccounts::Stakes(stakes)
+                        }
+                        _ => RewardAccounts::Authority(*ctx.pubkey()),
+                    },
+                    (stakes, _) => RewardAccounts::Stakes(stakes.to_vec()),
+                };
 
-    let current_epoch = clock.epoch;
+                let period = match (args.from, args.to, args.start_epoch, args.end_epoch) {
+                    (Some(from), Some(to), ..) => IncomePeriod::Dates { from, to },
+                    (.., Some(start), Some(end)) => IncomePeriod::Epochs(start..=end),
+                    _ => match prompt_select_data(
+                        "Report on a:",
+                        vec![ReportPeriod::Dates, ReportPeriod::Epochs],
+                    ) {
+                        ReportPeriod::Dates => IncomePeriod::Dates {
+                            from: prompt_input_data("Enter First Day (YYYY-MM-DD): "),
+                            to: prompt_input_data("Enter Last Day (YYYY-MM-DD): "),
+                        },
+                        ReportPeriod::Epochs => IncomePeriod::Epochs(
+                            prompt_input_data("Enter Start Epoch: ")
+                                ..=prompt_input_data("Enter End Epoch: "),
+                        ),
+                    },
+                };
 
-    // Add stake state specific information
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
-        ])
-        .add_row(vec![
-            Cell::new("Stake Account Pubkey"),
-            Cell::new(stake_account_keypair.pubkey()),
-        ])
-        .add_row(vec![
-            Cell::new("Delegated Stake"),
-            Cell::new(stake_state.stake().unwrap_or_default().delegation.stake),
-        ])
-        .add_row(vec![
-            Cell::new("Account Balance (SOL)"),
-            Cell::new(lamports_to_sol(stake_account.lamports)),
-        ])
-        .add_row(vec![
-            Cell::new("Account Balance (Lamports)"),
-            Cell::new(format!("{}", stake_account.lamports)),
-        ]);
+                let path: PathBuf = args
+                    .out
+                    .clone()
+                    .unwrap_or_else(|| prompt_input_data("Enter Ledger File Path: "));
+                let format = args
+                    .format
+                    .unwrap_or_else(|| LedgerFormat::from_path(&path));
 
-    match &stake_state {
-        StakeStateV2::Uninitialized => {
-            table.add_row(vec![Cell::new("Stake State"), Cell::new("Uninitialized")]);
-        }
-        StakeStateV2::Initialized(Meta {
-            rent_exempt_reserve,
-            authorized,
-            lockup,
-        }) => {
-            table
-                .add_row(vec![Cell::new("Stake State"), Cell::new("Initialized")])
-                .add_row(vec![
-                    Cell::new("Rent Exempt Reserve (Lamports)"),
-                    Cell::new(format!("{:.9}", rent_exempt_reserve)),
-                ])
-                .add_row(vec![
-                    Cell::new("Stake Authority"),
-                    Cell::new(authorized.staker),
-                ])
-                .add_row(vec![
-                    Cell::new("Withdraw Authority"),
-                    Cell::new(authorized.withdrawer),
-                ]);
+                let report = show_spinner(
+                    self.spinner_msg(),
+                    process_income_report(ctx, accounts, period, &path, format),
+                )
+                .await?;
+                ctx.output_format().render(&report)?;
+            }
+            StakeCommand::Eta(args) => {
+                let target = match (args.stake, &args.amount, args.direction) {
+                    (Some(stake), ..) => EtaTarget::Account(stake),
+                    (None, Some(amount), Some(direction)) => {
+                        EtaTarget::Amount(amount.to_lamports(), direction)
+                    }
+                    _ => match prompt_optional_input_data(
+                        "Enter Stake Account Pubkey (empty to project an amount): ",
+                    ) {
+                        Some(stake) => EtaTarget::Account(stake),
+                        None => {
+                            let amount: SolAmount = prompt_input_data("Enter Amount (SOL): ");
+                            let direction = prompt_select_data(
+                                "Project the amount as it starts to:",
+                                vec![StakeDirection::Activate, StakeDirection::Deactivate],
+                            );
+                            EtaTarget::Amount(amount.to_lamports(), direction)
+                        }
+                    },
+                };
 
-            if !lockup.is_in_force(&clock, None) {
-                table
-                    .add_row(vec![
-                        Cell::new("Lockup Epoch"),
-                        Cell::new(format!("{}", lockup.epoch)),
-                    ])
-                    .add_row(vec![
-                        Cell::new("Lockup Unix Timestamp"),
-                        Cell::new(format!("{}", lockup.unix_timestamp)),
-                    ])
-                    .add_row(vec![
-                        Cell::new("Lockup Custodian"),
-                        Cell::new(lockup.custodian),
-                    ]);
-            }
-        }
-        StakeStateV2::Stake(
-            Meta {
-                authorized, lockup, ..
-            },
-            stake,
-            _,
-        ) => {
-            // Activation Status
-            let StakeActivationStatus {
-                effective: _,
-                activating: _,
-                deactivating: _,
-            } = stake.delegation.stake_activating_and_deactivating(
-                current_epoch,
-                &stake_history,
-                None,
-            );
+                let eta = show_spinner(self.spinner_msg(), process_stake_eta(ctx, target)).await?;
+                ctx.output_format().render(&eta)?;
+            }
+            StakeCommand::History(args) => {
+                let interactive = ctx.is_interactive();
+                let (start_epoch, end_epoch) = if interactive {
+                    (
+                        prompt_optional_input_data("Enter Start Epoch: "),
+                        prompt_optional_input_data("Enter End Epoch: "),
+                    )
+                } else {
+                    (args.start_epoch, args.end_epoch)
+                };
+                let page_size = args
+                    .page_size
+                    .map_or(DEFAULT_HISTORY_PAGE_SIZE, NonZeroUsize::get);
+                let mut page = args.page.map_or(1, NonZeroUsize::get);
 
-            table
-                .add_row(vec![
-                    Cell::new("Delegation State"),
-                    Cell::new("Undelegated"),
-                ])
-                .add_row(vec![
-                    Cell::new("Stake Authority"),
-                    Cell::new(authorized.staker),
-                ])
-                .add_row(vec![
-                    Cell::new("Withdraw Authority"),
-                    Cell::new(authorized.withdrawer),
-                ]);
+                let stake_history =
+                    show_spinner(self.spinner_msg(), fetch_stake_history(ctx)).await?;
+                loop {
+                    let history =
+                        stake_history_page(&stake_history, start_epoch, end_epoch, page, page_size)
+                            .map_err(report_error)?;
+                    ctx.output_format().render(&history)?;
 
-            if lockup.is_in_force(&clock, None) {
-                table
-                    .add_row(vec![
-                        Cell::new("Lockup Epoch"),
-                        Cell::new(format!("{}", lockup.epoch)),
-                    ])
-                    .add_row(vec![
-                        Cell::new("Lockup Unix Timestamp"),
-                        Cell::new(format!("{}", lockup.unix_timestamp)),
-                    ])
-                    .add_row(vec![
-                        Cell::new("Lockup Custodian"),
-                        Cell::new(lockup.custodian),
-                    ]);
+                    if !interactive
+                        || ctx.output_format().is_json()
+                        || page >= history.total_pages
+                        || !Confirm::new("Show older epochs?")
+                            .with_default(true)
+                            .prompt()
+                            .unwrap_or(false)
+                    {
+                        break;
+                    }
+                    page += 1;
+                }
             }
+
+            StakeCommand::GoBack => return Ok(CommandFlow::GoBack),
         }
-        StakeStateV2::RewardsPool => {
-            bail!("Cannot withdraw from rewards pool");
-        }
-    }
 
-    println!(
-        "\n{}",
-        style("NEW STAKE ACCOUNT INFORMATION").green().bold()
-    );
-    println!("{table}");
+        Ok(CommandFlow::Process(()))
+    }
+}
 
-    Ok(())
+#[derive(Debug, Serialize)]
+pub struct LockupOutput {
+    pub epoch: u64,
+    pub unix_timestamp: i64,
+    pub custodian: String,
 }
 
-async fn delegate_stake_account(
-    ctx: &ScillaContext,
-    stake_account_pubkey: &Pubkey,
-    vote_account_pubkey: &Pubkey,
-    stake_authority_keypair_path: PathBuf,
-) -> anyhow::Result<()> {
-    let stake_account = ctx.rpc().get_account(stake_account_pubkey).await?;
-    let stake_authority_keypair = read_keypair_from_path(stake_authority_keypair_path)?;
-    let stake_authority_pubkey = stake_authority_keypair.pubkey();
+#[derive(Debug, Serialize)]
+pub struct DelegationOutput {
+    pub vote_account: String,
+    pub stake: u64,
+    pub activation_epoch: Option<u64>,
+    pub deactivation_epoch: Option<u64>,
+    pub effective: u64,
+    pub activating: u64,
+    pub deactivating: u64,
+    /// Estimated epoch at which the pending activation or deactivation
+    /// completes
+    pub estimated_completion_epoch: Option<u64>,
+}
 
-    if stake_account.owner != stake_program_id() {
-        bail!("Account {} is not a stake account", stake_account_pubkey);
-    }
+#[derive(Debug, Serialize)]
+pub struct StakeAccountOutput {
+    pub address: String,
+    pub lamports: u64,
+    pub state: &'static str,
+    pub rent_exempt_reserve: Option<u64>,
+    /// Most that can be withdrawn right now, not taking the lockup into account
+    pub withdrawable: u64,
+    pub staker: Option<String>,
+    pub withdrawer: Option<String>,
+    /// Only present while the lockup is in force
+    pub lockup: Option<LockupOutput>,
+    pub delegation: Option<DelegationOutput>,
+}
 
-    let get_vote_account_config = RpcGetVoteAccountsConfig {
-        vote_pubkey: Some(vote_account_pubkey.to_string()),
-        commitment: Some(ctx.rpc().commitment()),
-        keep_unstaked_delinquents: Some(true),
-        ..RpcGetVoteAccountsConfig::default()
-    };
+impl StakeAccountOutput {
+    fn new(
+        pubkey: &Pubkey,
+        account: &Account,
+        stake_state: &StakeStateV2,
+        cluster: &StakeClusterState,
+    ) -> Self {
+        let state = match stake_state {
+            StakeStateV2::Uninitialized => "Uninitialized",
+            StakeStateV2::Initialized(_) => "Initialized",
+            StakeStateV2::Stake(..) => "Delegated",
+            StakeStateV2::RewardsPool => "Rewards Pool",
+        };
 
-    let RpcVoteAccountStatus {
-        current,
-        delinquent,
-    } = ctx
-        .rpc()
-        .get_vote_accounts_with_config(get_vote_account_config)
-        .await?;
+        let meta = stake_state.meta();
+        let lockup = meta
+            .as_ref()
+            .map(|meta| meta.lockup)
+            .filter(|lockup| lockup.is_in_force(&cluster.clock, None))
+            .map(|lockup| LockupOutput {
+                epoch: lockup.epoch,
+                unix_timestamp: lockup.unix_timestamp,
+                custodian: lockup.custodian.to_string(),
+            });
 
-    let vote_account = current
-        .first()
-        .or_else(|| delinquent.first())
-        .ok_or_else(|| anyhow!("Vote account not found: {vote_account_pubkey}"))?;
+        let delegation = stake_state.stake_ref().map(|stake| {
+            let StakeActivationStatus {
+                effective,
+                activating,
+                deactivating,
+            } = cluster.activation(&stake.delegation);
 
-    // checking if the vote account is delinquent (no. of slots behind)
-    let vote_account_activated_stake = vote_account.activated_stake;
-    let vote_account_root_slot = vote_account.root_slot;
-    let min_root_slot = ctx
-        .rpc()
-        .get_slot()
-        .await
-        .map(|slot| slot.saturating_sub(DELINQUENT_VALIDATOR_SLOT_DISTANCE))?;
+            DelegationOutput {
+                vote_account: stake.delegation.voter_pubkey.to_string(),
+                stake: stake.delegation.stake,
+                activation_epoch: Some(stake.delegation.activation_epoch)
+                    .filter(|epoch| *epoch < u64::MAX),
+                deactivation_epoch: Some(stake.delegation.deactivation_epoch)
+                    .filter(|epoch| *epoch < u64::MAX),
+                effective,
+                activating,
+                deactivating,
+                estimated_completion_epoch: cluster.estimate_completion_epoch(&stake.delegation),
+            }
+        });
 
-    if vote_account_root_slot >= min_root_slot || vote_account_activated_stake == 0 {
-        // valid vote account so we continue
-    } else if vote_account_root_slot == 0 {
-        bail!("Failed to delegate, vote account has no root slot");
-    } else {
-        bail!(
-            "Failed to delegate, vote account appears delinquent because its current root slot \
-             ({vote_account_root_slot}) is less than {min_root_slot}"
-        );
+        Self {
+            address: pubkey.to_string(),
+            lamports: account.lamports,
+            state,
+            rent_exempt_reserve: meta.as_ref().map(|meta| meta.rent_exempt_reserve),
+            withdrawable: withdrawable_lamports(account.lamports, stake_state, cluster),
+            staker: meta.as_ref().map(|meta| meta.authorized.staker.to_string()),
+            withdrawer: meta
+                .as_ref()
+                .map(|meta| meta.authorized.withdrawer.to_string()),
+            lockup,
+            delegation,
+        }
     }
 
-    let ix = instruction::delegate_stake(
-        stake_account_pubkey,
-        &stake_authority_pubkey,
-        vote_account_pubkey,
-    );
-
-    let signature =
-        build_and_send_tx(ctx, &[ix], &[ctx.keypair(), &stake_authority_keypair]).await?;
-
-    println!(
-        "{}\n{}",
-        style("Stake Delegated successfully!").yellow().bold(),
-        style(format!("Signature: {signature}")).green()
-    );
-
-    let accounts = ctx
-        .rpc()
-        .get_multiple_accounts(&[*stake_account_pubkey, stake_history::id(), clock::id()])
-        .await?;
-
-    let Some(Some(stake_account)) = accounts.first() else {
-        bail!("Failed to fetch stake account");
-    };
-
-    let Some(Some(stake_history_account)) = accounts.get(1) else {
-        bail!("Failed to fetch stake history account");
-    };
-
-    let Some(Some(clock_account)) = accounts.get(2) else {
-        bail!("Failed to fetch clock account");
-    };
-
-    let stake_state: StakeStateV2 = bincode_deserialize(&stake_account.data, "stake account data")?;
-
-    let stake_history: StakeHistory =
-        bincode_deserialize(&stake_history_account.data, "stake history data")?;
+    /// State including where a delegation is in its warmup or cooldown
+    fn status(&self) -> &'static str {
+        match &self.delegation {
+            None => self.state,
+            Some(delegation) if delegation.deactivating > 0 => "Deactivating",
+            Some(delegation) if delegation.activating > 0 => "Activating",
+            Some(delegation) if delegation.effective > 0 => "Active",
+            Some(_) => "Inactive",
+        }
+    }
 
-    let clock: Clock = bincode_deserialize(&clock_account.data, "clock account data")?;
+    fn print_table(&self, title: &str) {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_header(vec![
+                Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
+            ])
+            .add_row(vec![
+                Cell::new("Stake Account Pubkey"),
+                Cell::new(&self.address),
+            ])
+            .add_row(vec![
+                Cell::new("Account Balance (SOL)"),
+                Cell::new(lamports_to_sol(self.lamports)),
+            ])
+            .add_row(vec![
+                Cell::new("Account Balance (Lamports)"),
+                Cell::new(format!("{}", self.lamports)),
+            ])
+            .add_row(vec![Cell::new("Stake State"), Cell::new(self.state)]);
 
-    // New Stake Account Info Table
-    let current_epoch = clock.epoch;
+        if let Some(rent_exempt_reserve) = self.rent_exempt_reserve {
+            table.add_row(vec![
+                Cell::new("Rent Exempt Reserve (Lamports)"),
+                Cell::new(format!("{rent_exempt_reserve}")),
+            ]);
+        }
 
-    // Add stake state specific information
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
-        ])
-        .add_row(vec![
-            Cell::new("Stake Account Pubkey"),
-            Cell::new(stake_account_pubkey),
-        ])
-        .add_row(vec![
-            Cell::new("Delegated Stake"),
-            Cell::new(stake_state.stake().unwrap_or_default().delegation.stake),
-        ])
-        .add_row(vec![
-            Cell::new("Account Balance (SOL)"),
-            Cell::new(lamports_to_sol(stake_account.lamports)),
-        ])
-        .add_row(vec![
-            Cell::new("Account Balance (Lamports)"),
-            Cell::new(format!("{}", stake_account.lamports)),
+        table.add_row(vec![
+            Cell::new("Withdrawable (SOL)"),
+            Cell::new(match self.lockup {
+                Some(_) => format!(
+                    "{} {}",
+                    lamports_to_sol(self.withdrawable),
+                    style("(lockup in force)").yellow()
+                ),
+                None => format!("{}", lamports_to_sol(self.withdrawable)),
+            }),
         ]);
 
-    match &stake_state {
-        StakeStateV2::Uninitialized => {
-            table.add_row(vec![Cell::new("Stake State"), Cell::new("Uninitialized")]);
-        }
-        StakeStateV2::Initialized(Meta {
-            rent_exempt_reserve,
-            authorized,
-            lockup,
-        }) => {
+        if let (Some(staker), Some(withdrawer)) = (&self.staker, &self.withdrawer) {
             table
-                .add_row(vec![Cell::new("Stake State"), Cell::new("Initialized")])
-                .add_row(vec![
-                    Cell::new("Rent Exempt Reserve (Lamports)"),
-                    Cell::new(format!("{:.9}", rent_exempt_reserve)),
-                ])
-                .add_row(vec![
-                    Cell::new("Stake Authority"),
-                    Cell::new(authorized.staker),
-                ])
-                .add_row(vec![
-                    Cell::new("Withdraw Authority"),
-                    Cell::new(authorized.withdrawer),
-                ]);
-
-            if lockup.is_in_force(&clock, None) {
-                table
-                    .add_row(vec![
-                        Cell::new("Lockup Epoch"),
-                        Cell::new(format!("{}", lockup.epoch)),
-                    ])
-                    .add_row(vec![
-                        Cell::new("Lockup Unix Timestamp"),
-                        Cell::new(format!("{}", lockup.unix_timestamp)),
-                    ])
-                    .add_row(vec![
-                        Cell::new("Lockup Custodian"),
-                        Cell::new(lockup.custodian),
-                    ]);
-            }
-        }
-        StakeStateV2::Stake(
-            Meta {
-                authorized, lockup, ..
-            },
-            stake,
-            _,
-        ) => {
-            // Activation Status
-            let StakeActivationStatus {
-                effective,
-                activating: _,
-                deactivating: _,
-            } = stake.delegation.stake_activating_and_deactivating(
-                current_epoch,
-                &stake_history,
-                None,
-            );
+                .add_row(vec![Cell::new("Stake Authority"), Cell::new(staker)])
+                .add_row(vec![Cell::new("Withdraw Authority"), Cell::new(withdrawer)]);
+        }
 
+        if let Some(delegation) = &self.delegation {
             table
-                .add_row(vec![Cell::new("Stake State"), Cell::new("Delegated")])
-                .add_row(vec![
-                    Cell::new("Stake Authority"),
-                    Cell::new(authorized.staker),
-                ])
-                .add_row(vec![
-                    Cell::new("Withdraw Authority"),
-                    Cell::new(authorized.withdrawer),
-                ])
                 .add_row(vec![
                     Cell::new("Delegated Vote Account"),
-                    Cell::new(stake.delegation.voter_pubkey),
+                    Cell::new(&delegation.vote_account),
                 ])
                 .add_row(vec![
                     Cell::new("Delegated Stake (SOL)"),
                     Cell::new(format!(
                         "{:.9}",
-                        (stake.delegation.stake as f64).div(LAMPORTS_PER_SOL as f64)
+                        (delegation.stake as f64).div(LAMPORTS_PER_SOL as f64)
                     )),
                 ])
                 .add_row(vec![
                     Cell::new("Activation Epoch"),
-                    Cell::new(match stake.delegation.activation_epoch {
-                        epoch if epoch < u64::MAX => format!("{epoch}"),
-                        _ => "N/A".into(),
+                    Cell::new(match delegation.activation_epoch {
+                        Some(epoch) => format!("{epoch}"),
+                        None => "N/A".into(),
                     }),
                 ])
                 .add_row(vec![
                     Cell::new("Deactivation Epoch"),
-                    Cell::new(match stake.delegation.deactivation_epoch {
-                        epoch if epoch < u64::MAX => format!("{epoch}"),
-                        _ => "N/A".into(),
+                    Cell::new(match delegation.deactivation_epoch {
+                        Some(epoch) => format!("{epoch}"),
+                        None => "N/A".into(),
                     }),
                 ])
                 .add_row(vec![
                     Cell::new("Active Stake (SOL)"),
                     Cell::new(format!(
                         "{:.9}",
-                        (effective as f64).div(LAMPORTS_PER_SOL as f64)
+                        (delegation.effective as f64).div(LAMPORTS_PER_SOL as f64)
+                    )),
+                ])
+                .add_row(vec![
+                    Cell::new("Activating Stake (SOL)"),
+                    Cell::new(format!(
+                        "{:.9}",
+                        (delegation.activating as f64).div(LAMPORTS_PER_SOL as f64)
+                    )),
+                ])
+                .add_row(vec![
+                    Cell::new("Deactivating Stake (SOL)"),
+                    Cell::new(format!(
+                        "{:.9}",
+                        (delegation.deactivating as f64).div(LAMPORTS_PER_SOL as f64)
                     )),
                 ]);
 
-            if lockup.is_in_force(&clock, None) {
-                table
-                    .add_row(vec![
-                        Cell::new("Lockup Epoch"),
-                        Cell::new(format!("{}", lockup.epoch)),
-                    ])
-                    .add_row(vec![
-                        Cell::new("Lockup Unix Timestamp"),
-                        Cell::new(format!("{}", lockup.unix_timestamp)),
-                    ])
-                    .add_row(vec![
-                        Cell::new("Lockup Custodian"),
-                        Cell::new(lockup.custodian),
-                    ]);
+            if let Some(epoch) = delegation.estimated_completion_epoch {
+                let label = if delegation.activating > 0 {
+                    "Fully Active From Epoch (est.)"
+                } else {
+                    "Fully Inactive From Epoch (est.)"
+                };
+                table.add_row(vec![Cell::new(label), Cell::new(epoch)]);
             }
         }
-        StakeStateV2::RewardsPool => {
-            table.add_row(vec![Cell::new("Stake State"), Cell::new("Rewards Pool")]);
+
+        if let Some(lockup) = &self.lockup {
+            table
+                .add_row(vec![
+                    Cell::new("Lockup Epoch"),
+                    Cell::new(format!("{}", lockup.epoch)),
+                ])
+                .add_row(vec![
+                    Cell::new("Lockup Unix Timestamp"),
+                    Cell::new(format!("{}", lockup.unix_timestamp)),
+                ])
+                .add_row(vec![
+                    Cell::new("Lockup Custodian"),
+                    Cell::new(&lockup.custodian),
+                ]);
         }
-    }
 
-    println!(
-        "\n{}",
-        style("DELEGATE STAKE ACCOUNT INFORMATION").green().bold()
-    );
-    println!("{table}");
-    Ok(())
+        println!("\n{}", style(title).green().bold());
+        println!("{table}");
+    }
 }
 
-async fn process_deactivate_stake_account(
-    ctx: &ScillaContext,
-    stake_pubkey: &Pubkey,
-) -> anyhow::Result<()> {
-    let account = ctx.rpc().get_account(stake_pubkey).await?;
-
-    if account.owner != stake_program_id() {
-        bail!("Account is not owned by the stake program");
+impl CommandOutput for StakeAccountOutput {
+    fn print_table(&self) {
+        self.print_table("STAKE ACCOUNT INFORMATION");
     }
+}
 
-    let stake_state: StakeStateV2 = bincode_deserialize(&account.data, "stake account data")?;
+/// Cluster state that stake activation depends on.
+pub struct StakeClusterState {
+    pub stake_history: StakeHistory,
+    pub clock: Clock,
+    /// Epoch from which the lower warmup/cooldown rate applies
+    pub new_rate_activation_epoch: Option<Epoch>,
+}
 
-    match stake_state {
-        StakeStateV2::Stake(meta, stake, _) => {
-            if stake.delegation.deactivation_epoch != ACTIVE_STAKE_EPOCH_BOUND {
-                bail!(
-                    "Stake is already deactivating at epoch {}",
-                    stake.delegation.deactivation_epoch
-                );
-            }
+impl StakeClusterState {
+    /// Effective, activating and deactivating stake of `delegation` in the
+    /// current epoch
+    pub fn activation(&self, delegation: &Delegation) -> StakeActivationStatus {
+        delegation.stake_activating_and_deactivating(
+            self.clock.epoch,
+            &self.stake_history,
+            self.new_rate_activation_epoch,
+        )
+    }
 
-            if &meta.authorized.staker != ctx.pubkey() {
-                bail!(
-                    "You are not the authorized staker. Authorized staker: {}",
-                    meta.authorized.staker
-                );
+    /// Estimates the first epoch in which `delegation` is neither activating
+    /// nor deactivating, assuming the cluster keeps warming up and cooling
+    /// down stake as it did in the last recorded epoch.
+    pub fn estimate_completion_epoch(&self, delegation: &Delegation) -> Option<Epoch> {
+        let projection = self.project_activation(delegation);
+        match projection.as_slice() {
+            [_, .., (epoch, last)] if last.activating == 0 && last.deactivating == 0 => {
+                Some(*epoch)
             }
-        }
-        StakeStateV2::Initialized(_) => {
-            bail!("Stake account is initialized but not delegated");
-        }
-        _ => {
-            bail!("Stake account is not in a valid state for deactivation");
+            _ => None,
         }
     }
 
-    let authorized_pubkey = ctx.pubkey();
-    let instruction = deactivate_stake(stake_pubkey, authorized_pubkey);
-
-    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair()]).await?;
-
-    println!(
-        "{} {}\n{}\n{}",
-        style("Stake Deactivated Successfully!").green().bold(),
-        style("(Cooldown will take 1-2 epochs ≈ 2-4 days)").yellow(),
-        style(format!("Stake Account: {stake_pubkey}")).yellow(),
-        style(format!("Signature: {signature}")).cyan()
-    );
+    /// Projects the activation of `delegation` epoch by epoch from the
+    /// current epoch until it is neither activating nor deactivating, for at
+    /// most `MAX_PROJECTED_EPOCHS`. The cluster is assumed to keep warming up
+    /// and cooling down stake as it did in the last recorded epoch.
+    pub fn project_activation(
+        &self,
+        delegation: &Delegation,
+    ) -> Vec<(Epoch, StakeActivationStatus)> {
+        let current = self.activation(delegation);
+        let mut projection = vec![(self.clock.epoch, current.clone())];
+        if current.activating == 0 && current.deactivating == 0 {
+            return projection;
+        }
 
-    Ok(())
-}
+        let Some((_, latest)) = self.stake_history.first() else {
+            return projection;
+        };
 
-async fn process_withdraw_stake(
-    ctx: &ScillaContext,
-    stake_pubkey: &Pubkey,
-    recipient: &Pubkey,
-    amount_sol: f64,
-) -> anyhow::Result<()> {
-    let amount_lamports = sol_to_lamports(amount_sol);
+        let mut stake_history = self.stake_history.clone();
+        let mut status = current;
+        for epoch in self.clock.epoch..self.clock.epoch + MAX_PROJECTED_EPOCHS {
+            // The cluster totals include whatever this delegation still has
+            // pending
+            let projected = StakeHistoryEntry {
+                effective: latest.effective,
+                activating: latest.activating.max(status.activating),
+                deactivating: latest.deactivating.max(status.deactivating),
+            };
+            stake_history.add(epoch, projected);
 
-    let (account, epoch_info) = fetch_account_with_epoch(ctx, stake_pubkey).await?;
+            status = delegation.stake_activating_and_deactivating(
+                epoch + 1,
+                &stake_history,
+                self.new_rate_activation_epoch,
+            );
+            let complete = status.activating == 0 && status.deactivating == 0;
+            projection.push((epoch + 1, status.clone()));
+            if complete {
+                break;
+            }
+        }
 
-    if account.owner != stake_program_id() {
-        bail!("Account is not owned by the stake program");
+        projection
     }
+}
 
-    let stake_state: StakeStateV2 = bincode_deserialize(&account.data, "stake account data")?;
-
+/// Lamports the stake program lets the withdraw authority take out now. Only
+/// an account without any stake left can be emptied entirely.
+fn withdrawable_lamports(
+    lamports: u64,
+    stake_state: &StakeStateV2,
+    cluster: &StakeClusterState,
+) -> u64 {
     match stake_state {
         StakeStateV2::Stake(meta, stake, _) => {
-            if &meta.authorized.withdrawer != ctx.pubkey() {
-                bail!(
-                    "You are not the authorized withdrawer. Authorized withdrawer: {}",
-                    meta.authorized.withdrawer
-                );
-            }
-
-            if stake.delegation.deactivation_epoch == ACTIVE_STAKE_EPOCH_BOUND {
-                bail!(
-                    "Stake is still active. You must deactivate it first and wait for the \
-                     cooldown period."
-                );
-            }
-
-            if epoch_info.epoch <= stake.delegation.deactivation_epoch {
-                let epochs_remaining = stake.delegation.deactivation_epoch - epoch_info.epoch;
-                bail!(
-                    "Stake is still cooling down. Current epoch: {}, deactivation epoch: {}, \
-                     epochs remaining: {}",
-                    epoch_info.epoch,
-                    stake.delegation.deactivation_epoch,
-                    epochs_remaining
-                );
-            }
-        }
-        StakeStateV2::Initialized(meta) => {
-            if &meta.authorized.withdrawer != ctx.pubkey() {
-                bail!(
-                    "You are not the authorized withdrawer. Authorized withdrawer: {}",
-                    meta.authorized.withdrawer
-                );
+            let staked = if cluster.clock.epoch >= stake.delegation.deactivation_epoch {
+                cluster.activation(&stake.delegation).effective
+            } else {
+                stake.delegation.stake
+            };
+            if staked == 0 {
+                lamports
+            } else {
+                lamports.saturating_sub(staked + meta.rent_exempt_reserve)
             }
         }
-        StakeStateV2::Uninitialized => {
-            bail!("Stake account is uninitialized");
-        }
-        StakeStateV2::RewardsPool => {
-            bail!("Cannot withdraw from rewards pool");
-        }
-    }
-
-    if amount_lamports > account.lamports {
-        bail!(
-            "Insufficient balance. Have {:.6} SOL, trying to withdraw {:.6} SOL",
-            lamports_to_sol(account.lamports),
-            amount_sol
-        );
+        StakeStateV2::Initialized(_) | StakeStateV2::Uninitialized => lamports,
+        StakeStateV2::RewardsPool => 0,
     }
+}
 
-    let withdrawer_pubkey = ctx.pubkey();
+/// Fetches `pubkeys` together with the `StakeHistory`, `Clock` and
+/// `EpochSchedule` sysvars and the warmup/cooldown feature gate in a single
+/// round trip.
+pub async fn fetch_with_stake_cluster_state(
+    ctx: &ScillaContext,
+    pubkeys: &[Pubkey],
+) -> anyhow::Result<(Vec<Option<Account>>, StakeClusterState)> {
+    let feature_id = Pubkey::from_str_const(REDUCE_STAKE_WARMUP_COOLDOWN_FEATURE);
+    let mut keys = vec![
+        stake_history::id(),
+        clock::id(),
+        epoch_schedule::id(),
+        feature_id,
+    ];
+    keys.extend_from_slice(pubkeys);
 
-    let instruction = withdraw(
-        stake_pubkey,
-        withdrawer_pubkey,
-        recipient,
-        amount_lamports,
-        None,
-    );
+    let mut accounts = ctx.rpc().get_multiple_accounts(&keys).await?;
+    let requested = accounts.split_off(4);
 
-    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair()]).await?;
+    let Some(Some(stake_history_account)) = accounts.first() else {
+        bail!("Failed to fetch stake history account");
+    };
 
-    println!(
-        "{} {}\n{}\n{}\n{}",
-        style("Stake Withdrawn Successfully!").green().bold(),
-        style(format!("From Stake Account: {stake_pubkey}")).yellow(),
-        style(format!("To Recipient: {recipient}")).yellow(),
-        style(format!("Amount: {amount_sol} SOL")).cyan(),
-        style(format!("Signature: {signature}")).cyan()
-    );
+    let Some(Some(clock_account)) = accounts.get(1) else {
+        bail!("Failed to fetch clock account");
+    };
 
-    Ok(())
+    let Some(Some(epoch_schedule_account)) = accounts.get(2) else {
+        bail!("Failed to fetch epoch schedule account");
+    };
+
+    let stake_history: StakeHistory =
+        bincode_deserialize(&stake_history_account.data, "stake history data")?;
+
+    let clock: Clock = bincode_deserialize(&clock_account.data, "clock account data")?;
+
+    let epoch_schedule: EpochSchedule =
+        bincode_deserialize(&epoch_schedule_account.data, "epoch schedule data")?;
+
+    // The feature account only exists once the feature has been scheduled
+    let new_rate_activation_epoch = match accounts.get(3) {
+        Some(Some(feature_account)) => {
+            bincode_deserialize::<Feature>(&feature_account.data, "feature account data")?
+                .activated_at
+                .map(|slot| epoch_schedule.get_epoch(slot))
+        }
+        _ => None,
+    };
+
+    Ok((
+        requested,
+        StakeClusterState {
+            stake_history,
+            clock,
+            new_rate_activation_epoch,
+        },
+    ))
 }
 
-async fn process_merge_stake(
+/// Fetches a stake account together with the cluster state needed to compute
+/// its activation in a single round trip.
+async fn fetch_stake_account_output(
     ctx: &ScillaContext,
-    destination_stake_account_pubkey: &Pubkey,
-    source_stake_account_pubkey: &Pubkey,
-    stake_authority_keypair_path: &PathBuf,
-) -> anyhow::Result<()> {
-    let stake_authority_keypair = read_keypair_from_path(stake_authority_keypair_path)?;
+    stake_account_pubkey: &Pubkey,
+) -> anyhow::Result<StakeAccountOutput> {
+    let (accounts, cluster) = fetch_with_stake_cluster_state(ctx, &[*stake_account_pubkey]).await?;
 
-    // checks for unique pubkeys
-    if destination_stake_account_pubkey == source_stake_account_pubkey {
-        bail!(
-            "Destination Stake Account {} & Source Stake Account {} must not be the same",
-            destination_stake_account_pubkey,
-            source_stake_account_pubkey
-        );
+    let Some(Some(stake_account)) = accounts.first() else {
+        bail!("Failed to fetch stake account");
+    };
+
+    if stake_account.owner != stake_program_id() {
+        bail!("Account {stake_account_pubkey} is not a stake account");
     }
 
-    let stake_accounts = ctx
-        .rpc()
-        .get_multiple_accounts(&[
-            *destination_stake_account_pubkey,
-            *source_stake_account_pubkey,
-        ])
-        .await?;
+    let stake_state: StakeStateV2 = bincode_deserialize(&stake_account.data, "stake account data")?;
 
-    let Some(destination_stake_account) = stake_accounts[0].as_ref() else {
-        bail!("Failed to get stake account");
+    Ok(StakeAccountOutput::new(
+        stake_account_pubkey,
+        stake_account,
+        &stake_state,
+        &cluster,
+    ))
+}
+
+#[derive(Debug, Serialize)]
+pub struct StakeAccountListOutput {
+    pub authority: String,
+    pub total_lamports: u64,
+    pub accounts: Vec<StakeAccountOutput>,
+}
+
+impl CommandOutput for StakeAccountListOutput {
+    fn print_table(&self) {
+        if self.accounts.is_empty() {
+            println!(
+                "{}",
+                style(format!("No stake accounts found for {}", self.authority)).yellow()
+            );
+            return;
+        }
+
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL).set_header(vec![
+            Cell::new("#").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Address").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("State").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Vote Account").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Balance (SOL)").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Active Stake (SOL)").add_attribute(comfy_table::Attribute::Bold),
+        ]);
+
+        for (idx, account) in self.accounts.iter().enumerate() {
+            let delegation = account.delegation.as_ref();
+            table.add_row(vec![
+                Cell::new(format!("{}", idx + 1)),
+                Cell::new(&account.address),
+                Cell::new(account.status()),
+                Cell::new(
+                    delegation
+                        .map(|delegation| delegation.vote_account.as_str())
+                        .unwrap_or("-"),
+                ),
+                Cell::new(lamports_to_sol(account.lamports)),
+                Cell::new(lamports_to_sol(
+                    delegation
+                        .map(|delegation| delegation.effective)
+                        .unwrap_or_default(),
+                )),
+            ]);
+        }
+
+        println!("\n{}", style("STAKE ACCOUNTS").green().bold());
+        println!("{table}");
+        println!(
+            "{} accounts, {} SOL in total",
+            self.accounts.len(),
+            lamports_to_sol(self.total_lamports)
+        );
+    }
+}
+
+/// Finds the stake accounts whose stake and/or withdraw authority is
+/// `authority` by matching the authority bytes in the account data.
+pub async fn fetch_stake_accounts_of_authority(
+    ctx: &ScillaContext,
+    authority: &Pubkey,
+    role: AuthorityRole,
+) -> anyhow::Result<BTreeMap<Pubkey, Account>> {
+    let offsets: &[usize] = match role {
+        AuthorityRole::Staker => &[STAKE_STAKER_OFFSET],
+        AuthorityRole::Withdrawer => &[STAKE_WITHDRAWER_OFFSET],
+        AuthorityRole::Any => &[STAKE_STAKER_OFFSET, STAKE_WITHDRAWER_OFFSET],
     };
 
-    let Some(source_stake_account) = stake_accounts[1].as_ref() else {
-        bail!("Failed to get stake account");
+    let fetch_matching = |offset: usize| async move {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::DataSize(StakeStateV2::size_of() as u64),
+                RpcFilterType::Memcmp(Memcmp::new_base58_encoded(offset, authority.as_ref())),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(ctx.rpc().commitment()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let accounts = ctx
+            .rpc()
+            .get_program_ui_accounts_with_config(&stake_program_id(), config)
+            .await?;
+
+        accounts
+            .into_iter()
+            .map(|(pubkey, account)| {
+                account
+                    .decode::<Account>()
+                    .map(|account| (pubkey, account))
+                    .ok_or_else(|| anyhow!("Failed to decode stake account {pubkey}"))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()
     };
 
-    let destination_stake_state: StakeStateV2 = bincode_deserialize(
-        &destination_stake_account.data,
-        "destination stake account data",
+    let matches =
+        futures::future::try_join_all(offsets.iter().map(|offset| fetch_matching(*offset))).await?;
+
+    // An account matching both offsets is only listed once
+    Ok(matches.into_iter().flatten().collect())
+}
+
+async fn fetch_stake_accounts_by_authority(
+    ctx: &ScillaContext,
+    authority: &Pubkey,
+    role: AuthorityRole,
+    sort: StakeAccountSort,
+) -> anyhow::Result<StakeAccountListOutput> {
+    let (matches, (_, cluster)) = tokio::try_join!(
+        fetch_stake_accounts_of_authority(ctx, authority, role),
+        fetch_with_stake_cluster_state(ctx, &[]),
     )?;
 
-    let source_stake_state: StakeStateV2 =
-        bincode_deserialize(&source_stake_account.data, "source stake account data")?;
+    let mut accounts = matches
+        .iter()
+        .map(|(pubkey, account)| {
+            let stake_state: StakeStateV2 =
+                bincode_deserialize(&account.data, "stake account data")?;
+            Ok(StakeAccountOutput::new(
+                pubkey,
+                account,
+                &stake_state,
+                &cluster,
+            ))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
 
-    match &destination_stake_state {
-        StakeStateV2::Initialized(meta) => {
-            // Initialized destination is valid
-            (meta, None)
-        }
-        StakeStateV2::Stake(meta, stake, _) => {
-            // Delegated destination is valid
-            (meta, Some(&stake.delegation))
-        }
-        _ => bail!("Destination stake account is not in a valid state"),
-    };
+    match sort {
+        StakeAccountSort::Balance => accounts.sort_by_key(|account| Reverse(account.lamports)),
+        StakeAccountSort::Stake => accounts.sort_by_key(|account| {
+            Reverse(
+                account
+                    .delegation
+                    .as_ref()
+                    .map(|delegation| delegation.effective)
+                    .unwrap_or_default(),
+            )
+        }),
+        StakeAccountSort::State => accounts.sort_by_key(|account| account.status()),
+        StakeAccountSort::Validator => accounts.sort_by(|a, b| {
+            let vote_account = |account: &StakeAccountOutput| {
+                account
+                    .delegation
+                    .as_ref()
+                    .map(|delegation| delegation.vote_account.clone())
+            };
+            vote_account(a).cmp(&vote_account(b))
+        }),
+        StakeAccountSort::Address => accounts.sort_by(|a, b| a.address.cmp(&b.address)),
+    }
 
-    match &source_stake_state {
-        StakeStateV2::Initialized(meta) => {
-            // CHECK: Verify authority for initialized source
-            if meta.authorized.staker != stake_authority_keypair.pubkey() {
-                bail!(
-                    "Provided keypair is not the stake authority for source account\nExpected: \
-                     {}\nProvided: {}",
-                    meta.authorized.staker,
-                    stake_authority_keypair.pubkey()
-                );
-            }
+    Ok(StakeAccountListOutput {
+        authority: authority.to_string(),
+        total_lamports: accounts.iter().map(|account| account.lamports).sum(),
+        accounts,
+    })
+}
 
-            (meta, None)
-        }
-        StakeStateV2::Stake(meta, stake, _) => {
-            // CHECK: Verify authority for delegated source
-            if meta.authorized.staker != stake_authority_keypair.pubkey() {
-                bail!(
-                    "Provided keypair is not the stake authority for source account\nExpected: \
-                     {}\nProvided: {}",
-                    meta.authorized.staker,
-                    stake_authority_keypair.pubkey()
-                );
-            }
+/// Stake command that can be run on an account picked from a listing
+#[derive(Debug, Clone, Copy)]
+enum StakeAccountAction {
+    Delegate,
+    Deactivate,
+    Withdraw,
+    Merge,
+    Split,
+    Done,
+}
 
-            // CHECK: Source must not be deactivating
-            if stake.delegation.deactivation_epoch != u64::MAX {
-                bail!(
-                    "Cannot merge: source stake account is deactivating at epoch {}",
-                    stake.delegation.deactivation_epoch
-                );
-            }
+impl fmt::Display for StakeAccountAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = match self {
+            StakeAccountAction::Delegate => "Delegate an account",
+            StakeAccountAction::Deactivate => "Deactivate an account",
+            StakeAccountAction::Withdraw => "Withdraw from an account",
+            StakeAccountAction::Merge => "Merge two accounts",
+            StakeAccountAction::Split => "Split an account",
+            StakeAccountAction::Done => "Done",
+        };
+        write!(f, "{action}")
+    }
+}
 
-            (meta, Some(&stake.delegation))
-        }
-        _ => bail!("Source stake account is not in a valid state"),
-    };
+/// Listed stake account, shown as an option in a selection prompt
+#[derive(Debug, Clone)]
+struct StakeAccountChoice {
+    pubkey: Pubkey,
+    label: String,
+}
 
-    let stake_authority_pubkey = stake_authority_keypair.pubkey();
+impl fmt::Display for StakeAccountChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
 
-    let ixs = merge(
-        destination_stake_account_pubkey,
-        source_stake_account_pubkey,
-        &stake_authority_pubkey,
+/// Asks which stake command to run next and on which of the listed accounts,
+/// returning `None` once the user is done.
+fn prompt_stake_account_action(accounts: &[StakeAccountOutput]) -> Option<StakeCommand> {
+    if accounts.is_empty() {
+        return None;
+    }
+
+    let action = prompt_select_data(
+        "What would you like to do next?",
+        vec![
+            StakeAccountAction::Delegate,
+            StakeAccountAction::Deactivate,
+            StakeAccountAction::Withdraw,
+            StakeAccountAction::Merge,
+            StakeAccountAction::Split,
+            StakeAccountAction::Done,
+        ],
     );
 
-    let signature =
-        build_and_send_tx(ctx, &ixs, &[ctx.keypair(), &stake_authority_keypair]).await?;
+    let choices: Vec<_> = accounts
+        .iter()
+        .filter_map(|account| {
+            Some(StakeAccountChoice {
+                pubkey: account.address.parse().ok()?,
+                label: format!(
+                    "{} · {} · {} SOL",
+                    account.address,
+                    account.status(),
+                    lamports_to_sol(account.lamports)
+                ),
+            })
+        })
+        .collect();
+    let pick =
+        |msg: &str, choices: Vec<StakeAccountChoice>| prompt_select_data(msg, choices).pubkey;
 
-    println!(
-        "{}\n{}\n{}\n{}\n{}\n{}",
-        style("Stake Merged successfully!").yellow().bold(),
-        style(format!(
-            "Destination Stake Account: {}",
-            destination_stake_account_pubkey
-        ))
-        .yellow(),
-        style(format!(
-            "Source Stake Account: {}",
-            source_stake_account_pubkey
-        ))
-        .yellow(),
-        style(format!("Stake Authority: {}", stake_authority_pubkey)).yellow(),
-        style(format!(
-            "After Merge: {} SOL",
-            lamports_to_sol(destination_stake_account.lamports)
-        ))
-        .cyan(),
-        style(format!("Signature: {}", signature)).green()
-    );
+    let command = match action {
+        StakeAccountAction::Delegate => StakeCommand::Delegate(DelegateStakeArgs {
+            stake: Some(pick("Stake account to delegate:", choices)),
+            ..Default::default()
+        }),
+        StakeAccountAction::Deactivate => StakeCommand::Deactivate(DeactivateStakeArgs {
+            stake: Some(pick("Stake account to deactivate:", choices)),
+            ..Default::default()
+        }),
+        StakeAccountAction::Withdraw => StakeCommand::Withdraw(WithdrawStakeArgs {
+            stake: Some(pick("Stake account to withdraw from:", choices)),
+            ..Default::default()
+        }),
+        StakeAccountAction::Merge => {
+            if choices.len() < 2 {
+                print_error("At least two stake accounts are needed to merge");
+                return None;
+            }
+            let destination = pick("Stake account to merge into:", choices.clone());
+            let source = pick(
+                "Stake account to merge from:",
+                choices
+                    .into_iter()
+                    .filter(|choice| choice.pubkey != destination)
+                    .collect(),
+            );
+            StakeCommand::Merge(MergeStakeArgs {
+                destination: Some(destination),
+                source: Some(source),
+                ..Default::default()
+            })
+        }
+        StakeAccountAction::Split => StakeCommand::Split(SplitStakeArgs {
+            stake: Some(pick("Stake account to split:", choices)),
+            ..Default::default()
+        }),
+        StakeAccountAction::Done => return None,
+    };
 
-    Ok(())
+    Some(command)
 }
 
-async fn process_split_stake(
-    ctx: &ScillaContext,
-    stake_account_pubkey: &Pubkey,
-    split_stake_account_pubkey: &Pubkey,
-    stake_authority_keypair_path: &PathBuf,
-    amount_to_split: f64,
-) -> anyhow::Result<()> {
-    let stake_authority_keypair = read_keypair_from_path(stake_authority_keypair_path)?;
-    let stake_authority_pubkey = stake_authority_keypair.pubkey();
-    let lamports: u64 = sol_to_lamports(amount_to_split);
+#[derive(Debug, Serialize)]
+pub struct CreateStakeOutput {
+    pub signature: String,
+    pub stake_account: StakeAccountOutput,
+}
 
-    if stake_account_pubkey == split_stake_account_pubkey {
-        bail!(
-            "Existing Stake Account {} and New Split Stake Account {} must not be the same",
-            stake_account_pubkey,
-            split_stake_account_pubkey
+impl CommandOutput for CreateStakeOutput {
+    fn print_table(&self) {
+        println!(
+            "{}\n{}",
+            style("Stake Account created successfully!").yellow().bold(),
+            style(format!("Signature: {}", self.signature)).green()
         );
+        self.stake_account
+            .print_table("NEW STAKE ACCOUNT INFORMATION");
     }
+}
 
-    let stake_minimum_delegation = ctx.rpc().get_stake_minimum_delegation().await?;
-
-    if lamports < stake_minimum_delegation {
+async fn process_create_stake_account(
+    ctx: &ScillaContext,
+    new_stake_account: NewStakeAccount,
+    amount_sol: SolAmount,
+    withdraw_authority_keypair_path: PathBuf,
+    lockup: Lockup,
+    tx_args: &TxArgs,
+) -> anyhow::Result<TxOutcome<CreateStakeOutput>> {
+    if tx_args.sign_only {
         bail!(
-            "Need at least {} lamports for minimum stake delegation, but you provided {}",
-            stake_minimum_delegation,
-            lamports
+            "Creating a stake account needs the rent-exempt minimum from RPC, so it can't be \
+             signed with --sign-only"
         );
     }
 
-    let ix = instruction::split(
-        stake_account_pubkey,
-        &stake_authority_pubkey,
-        lamports,
-        split_stake_account_pubkey,
-    );
+    // A seed-derived account is signed for by its base key
+    let (stake_account_signer, seed) = match new_stake_account {
+        NewStakeAccount::Keypair(path) => (read_keypair_from_path(path)?, None),
+        NewStakeAccount::Seed { base, seed } => (read_keypair_from_path(base)?, Some(seed)),
+    };
+    let stake_account_pubkey = match &seed {
+        Some(seed) => derive_stake_address(&stake_account_signer.pubkey(), seed)?,
+        None => stake_account_signer.pubkey(),
+    };
+    let withdraw_authority_pubkey =
+        read_keypair_from_path(withdraw_authority_keypair_path)?.pubkey();
 
-    let signature = build_and_send_tx(ctx, &ix, &[ctx.keypair(), &stake_authority_keypair]).await?;
+    let lamports = amount_sol.to_lamports();
 
-    println!(
-        "{}\n{}\n{}\n{}\n{}",
-        style("Split Stake successfully!").yellow().bold(),
-        style(format!("Stake Account: {}", stake_account_pubkey)).yellow(),
-        style(format!(
-            "Split Stake Account: {}",
-            split_stake_account_pubkey
-        ))
-        .yellow(),
-        style(format!("Stake Authority: {}", stake_authority_pubkey)).yellow(),
-        style(format!("Signature: {}", signature)).green()
-    );
+    let minimum_rent_for_balance = ctx
+        .rpc()
+        .get_minimum_balance_for_rent_exemption(StakeStateV2::size_of())
+        .await?;
 
-    Ok(())
+    // amount in SOL + rent exempt
+    let total_lamports = lamports + minimum_rent_for_balance;
+    check_minimum_balance(ctx, ctx.pubkey(), total_lamports).await?;
+
+    if ctx.pubkey() == &stake_account_pubkey {
+        (bail!(
+            "Stake Account {} cannot be the same as fee payer account {}",
+            stake_account_pubkey,
+            ctx.pubkey(),
+        ));
+    }
+
+    if seed.is_some()
+        && ctx
+            .rpc()
+            .get_account_with_commitment(&stake_account_pubkey, ctx.rpc().commitment())
+            .await?
+            .value
+            .is_some()
+    {
+        bail!("Derived address {stake_account_pubkey} is already in use, choose another seed");
+    }
+
+    let authorized = Authorized {
+        staker: *ctx.pubkey(),
+        withdrawer: withdraw_authority_pubkey,
+    };
+
+    let ix = match &seed {
+        Some(seed) => instruction::create_account_with_seed(
+            ctx.pubkey(),
+            &stake_account_pubkey,
+            &stake_account_signer.pubkey(),
+            seed,
+            &authorized,
+            &lockup,
+            total_lamports,
+        ),
+        None => instruction::create_account(
+            ctx.pubkey(),
+            &stake_account_pubkey,
+            &authorized,
+            &lockup,
+            total_lamports,
+        ),
+    };
+
+    let signature = match build_and_send_tx(
+        ctx,
+        &ix,
+        &[ctx.keypair(), &stake_account_signer],
+        tx_args,
+    )
+    .await?
+    {
+        TxOutcome::Sent(signature) => signature,
+        TxOutcome::SignedOnly(signed) => return Ok(TxOutcome::SignedOnly(signed)),
+    };
+
+    let stake_account = fetch_stake_account_output(ctx, &stake_account_pubkey).await?;
+
+    Ok(TxOutcome::Sent(CreateStakeOutput {
+        signature: signature.to_string(),
+        stake_account,
+    }))
 }
 
-async fn process_stake_history(ctx: &ScillaContext) -> anyhow::Result<()> {
-    let stake_history_sysvar = Pubkey::from_str_const(STAKE_HISTORY_SYSVAR_ADDR);
+#[derive(Debug, Serialize)]
+pub struct DelegateStakeOutput {
+    pub signature: String,
+    pub stake_account: StakeAccountOutput,
+    /// Not checked when signing offline
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validator: Option<ValidatorReport>,
+}
 
-    let account = ctx.rpc().get_account(&stake_history_sysvar).await?;
+#[derive(Debug, Serialize)]
+pub struct EpochCreditsOutput {
+    pub epoch: Epoch,
+    pub credits: u64,
+}
 
-    let stake_history: StakeHistory =
-        bincode_deserialize_with_limit(account.data.len() as u64, &account.data, "stake history")?;
+/// What a staker should know about a validator before delegating to it
+#[derive(Debug, Serialize)]
+pub struct ValidatorReport {
+    pub vote_account: String,
+    pub identity: String,
+    pub commission: u8,
+    pub activated_stake: u64,
+    pub last_vote: Slot,
+    pub root_slot: Slot,
+    /// Slots between the last vote and the current slot
+    pub vote_lag: u64,
+    pub delinquent: bool,
+    /// Credits earned in each of the most recent epochs
+    pub epoch_credits: Vec<EpochCreditsOutput>,
+    /// Software version the identity advertises in gossip
+    pub version: Option<String>,
+    pub warnings: Vec<String>,
+}
 
-    if stake_history.is_empty() {
-        println!("{}", style("No stake history available").yellow());
-        return Ok(());
+impl ValidatorReport {
+    fn new(
+        vote_account: &RpcVoteAccountInfo,
+        listed_delinquent: bool,
+        version: Option<String>,
+        current_slot: Slot,
+        current_epoch: Epoch,
+    ) -> Self {
+        let min_root_slot = current_slot.saturating_sub(DELINQUENT_VALIDATOR_SLOT_DISTANCE);
+        let mut warnings = Vec::new();
+
+        // Unstaked validators can't root blocks, so only staked ones are
+        // judged by their root slot
+        let staked = vote_account.activated_stake > 0;
+        if staked && vote_account.root_slot == 0 {
+            warnings.push("DELINQUENT: the vote account has no root slot".to_string());
+        } else if listed_delinquent || (staked && vote_account.root_slot < min_root_slot) {
+            warnings.push(format!(
+                "DELINQUENT: root slot {} is more than {DELINQUENT_VALIDATOR_SLOT_DISTANCE} slots \
+                 behind the current slot {current_slot}",
+                vote_account.root_slot
+            ));
+        }
+        let delinquent = !warnings.is_empty();
+
+        if vote_account.commission == 100 {
+            warnings.push("100% COMMISSION: the validator keeps every reward".to_string());
+        }
+
+        let first_epoch = vote_account.epoch_credits.first().map(|(epoch, ..)| *epoch);
+        if first_epoch
+            .is_none_or(|epoch| epoch > current_epoch.saturating_sub(NEW_VALIDATOR_EPOCHS - 1))
+        {
+            warnings.push(format!(
+                "RECENTLY CREATED: the validator has voted in fewer than {NEW_VALIDATOR_EPOCHS} \
+                 epochs"
+            ));
+        }
+
+        ValidatorReport {
+            vote_account: vote_account.vote_pubkey.clone(),
+            identity: vote_account.node_pubkey.clone(),
+            commission: vote_account.commission,
+            activated_stake: vote_account.activated_stake,
+            last_vote: vote_account.last_vote,
+            root_slot: vote_account.root_slot,
+            vote_lag: current_slot.saturating_sub(vote_account.last_vote),
+            delinquent,
+            epoch_credits: vote_account
+                .epoch_credits
+                .iter()
+                .map(|(epoch, credits, prev_credits)| EpochCreditsOutput {
+                    epoch: *epoch,
+                    credits: credits.saturating_sub(*prev_credits),
+                })
+                .collect(),
+            version,
+            warnings,
+        }
     }
 
-    let mut table = Table::new();
-    table.load_preset(UTF8_FULL).set_header(vec![
-        Cell::new("Epoch").add_attribute(comfy_table::Attribute::Bold),
-        Cell::new("Effective Stake").add_attribute(comfy_table::Attribute::Bold),
-        Cell::new("Activating Stake").add_attribute(comfy_table::Attribute::Bold),
-        Cell::new("Deactivating Stake").add_attribute(comfy_table::Attribute::Bold),
-    ]);
+    fn render(&self) -> String {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_header(vec![
+                Cell::new("Validator").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
+            ])
+            .add_row(vec![
+                Cell::new("Vote Account"),
+                Cell::new(&self.vote_account),
+            ])
+            .add_row(vec![Cell::new("Identity"), Cell::new(&self.identity)])
+            .add_row(vec![
+                Cell::new("Commission"),
+                Cell::new(format!("{}%", self.commission)),
+            ])
+            .add_row(vec![
+                Cell::new("Activated Stake (SOL)"),
+                Cell::new(lamports_to_sol(self.activated_stake)),
+            ])
+            .add_row(vec![
+                Cell::new("Last Vote"),
+                Cell::new(format!(
+                    "{} ({} slots behind)",
+                    self.last_vote, self.vote_lag
+                )),
+            ])
+            .add_row(vec![Cell::new("Root Slot"), Cell::new(self.root_slot)])
+            .add_row(vec![
+                Cell::new("Status"),
+                Cell::new(if self.delinquent {
+                    style("Delinquent").red().bold().to_string()
+                } else {
+                    style("Current").green().to_string()
+                }),
+            ])
+            .add_row(vec![
+                Cell::new("Epoch Credits"),
+                Cell::new(
+                    self.epoch_credits
+                        .iter()
+                        .map(|entry| format!("{}: {}", entry.epoch, entry.credits))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                ),
+            ])
+            .add_row(vec![
+                Cell::new("Version"),
+                Cell::new(self.version.as_deref().unwrap_or("unknown (not in gossip)")),
+            ]);
 
-    for (epoch, entry) in stake_history.iter().take(DEFAULT_EPOCH_LIMIT) {
-        let StakeHistoryEntry {
-            effective,
-            activating,
-            deactivating,
-        } = entry;
+        let mut rendered = format!("\n{}\n{table}", style("VALIDATOR").green().bold());
+        for warning in &self.warnings {
+            rendered.push_str(&format!("\n{}", style(format!("⚠ {warning}")).red().bold()));
+        }
+        rendered
+    }
 
-        table.add_row(vec![
-            Cell::new(epoch),
-            Cell::new(lamports_to_sol(*effective)),
-            Cell::new(lamports_to_sol(*activating)),
-            Cell::new(lamports_to_sol(*deactivating)),
-        ]);
+    /// Prints the report ahead of the command result.
+    fn print(&self, ctx: &ScillaContext) {
+        let rendered = self.render();
+        print_status(ctx, rendered);
+    }
+}
+
+/// Asks to go ahead when the report has warnings. Without prompts, a
+/// delinquent validator is refused unless `force` is set.
+fn confirm_validator(
+    ctx: &ScillaContext,
+    validator: &ValidatorReport,
+    force: bool,
+) -> anyhow::Result<()> {
+    if validator.warnings.is_empty() {
+        return Ok(());
+    }
+    if ctx.skip_confirmation() {
+        if validator.delinquent && !force {
+            bail!(
+                "Failed to delegate, the validator is delinquent. Pass --force to delegate anyway"
+            );
+        }
+        return Ok(());
     }
 
-    println!("\n{}", style("CLUSTER STAKE HISTORY").green().bold());
-    println!("{}", table);
+    let confirmed = suspend_spinner(|| {
+        Confirm::new("Delegate to this validator despite the warnings?")
+            .with_default(false)
+            .prompt()
+    });
+    match confirmed {
+        Ok(true) => Ok(()),
+        Ok(false) => bail!("Delegation aborted"),
+        Err(err) => bail!("Unable to confirm delegation: {err}"),
+    }
+}
 
-    Ok(())
+/// Looks up the vote account, gossip entry and voting record of the
+/// validator behind `vote_pubkey`.
+async fn fetch_validator_report(
+    ctx: &ScillaContext,
+    vote_pubkey: &Pubkey,
+) -> anyhow::Result<ValidatorReport> {
+    let config = RpcGetVoteAccountsConfig {
+        vote_pubkey: Some(vote_pubkey.to_string()),
+        commitment: Some(ctx.rpc().commitment()),
+        keep_unstaked_delinquents: Some(true),
+        ..RpcGetVoteAccountsConfig::default()
+    };
+
+    let (
+        RpcVoteAccountStatus {
+            current,
+            delinquent,
+        },
+        nodes,
+        epoch_info,
+    ) = tokio::try_join!(
+        ctx.rpc().get_vote_accounts_with_config(config),
+        ctx.rpc().get_cluster_nodes(),
+        ctx.rpc().get_epoch_info(),
+    )?;
+
+    let (vote_account, listed_delinquent) = match (current.first(), delinquent.first()) {
+        (Some(vote_account), _) => (vote_account, false),
+        (None, Some(vote_account)) => (vote_account, true),
+        (None, None) => bail!("Vote account not found: {vote_pubkey}"),
+    };
+    let version = nodes
+        .into_iter()
+        .find(|node| node.pubkey == vote_account.node_pubkey)
+        .and_then(|node| node.version);
+
+    Ok(ValidatorReport::new(
+        vote_account,
+        listed_delinquent,
+        version,
+        epoch_info.absolute_slot,
+        epoch_info.epoch,
+    ))
+}
+
+impl CommandOutput for DelegateStakeOutput {
+    fn print_table(&self) {
+        println!(
+            "{}\n{}",
+            style("Stake Delegated successfully!").yellow().bold(),
+            style(format!("Signature: {}", self.signature)).green()
+        );
+        self.stake_account
+            .print_table("DELEGATE STAKE ACCOUNT INFORMATION");
+    }
+}
+
+async fn delegate_stake_account(
+    ctx: &ScillaContext,
+    stake_account_pubkey: &Pubkey,
+    vote_account_pubkey: &Pubkey,
+    stake_authority_keypair_path: PathBuf,
+    force: bool,
+    tx_args: &TxArgs,
+) -> anyhow::Result<TxOutcome<DelegateStakeOutput>> {
+    let stake_authority_keypair = read_keypair_from_path(stake_authority_keypair_path)?;
+    let stake_authority_pubkey = stake_authority_keypair.pubkey();
+
+    // The stake account and the validator can only be checked online, so
+    // skip them when signing offline
+    let validator = if tx_args.sign_only {
+        None
+    } else {
+        let stake_account = ctx.rpc().get_account(stake_account_pubkey).await?;
+        if stake_account.owner != stake_program_id() {
+            bail!("Account {} is not a stake account", stake_account_pubkey);
+        }
+
+        let validator = fetch_validator_report(ctx, vote_account_pubkey).await?;
+        validator.print(ctx);
+        confirm_validator(ctx, &validator, force)?;
+        Some(validator)
+    };
+
+    let ix = instruction::delegate_stake(
+        stake_account_pubkey,
+        &stake_authority_pubkey,
+        vote_account_pubkey,
+    );
+
+    let signature = match build_and_send_tx(
+        ctx,
+        &[ix],
+        &[ctx.keypair(), &stake_authority_keypair],
+        tx_args,
+    )
+    .await?
+    {
+        TxOutcome::Sent(signature) => signature,
+        TxOutcome::SignedOnly(signed) => return Ok(TxOutcome::SignedOnly(signed)),
+    };
+
+    let stake_account = fetch_stake_account_output(ctx, stake_account_pubkey).await?;
+
+    Ok(TxOutcome::Sent(DelegateStakeOutput {
+        signature: signature.to_string(),
+        stake_account,
+        validator,
+    }))
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeactivateStakeOutput {
+    pub signature: String,
+    pub stake_account: String,
+}
+
+impl CommandOutput for DeactivateStakeOutput {
+    fn print_table(&self) {
+        println!(
+            "{} {}\n{}\n{}",
+            style("Stake Deactivated Successfully!").green().bold(),
+            style("(Cooldown will take 1-2 epochs ≈ 2-4 days)").yellow(),
+            style(format!("Stake Account: {}", self.stake_account)).yellow(),
+            style(format!("Signature: {}", self.signature)).cyan()
+        );
+    }
+}
+
+async fn process_deactivate_stake_account(
+    ctx: &ScillaContext,
+    stake_pubkey: &Pubkey,
+    tx_args: &TxArgs,
+) -> anyhow::Result<TxOutcome<DeactivateStakeOutput>> {
+    // The account state can only be checked online, so skip it when signing offline
+    if !tx_args.sign_only {
+        let account = ctx.rpc().get_account(stake_pubkey).await?;
+
+        if account.owner != stake_program_id() {
+            bail!("Account is not owned by the stake program");
+        }
+
+        let stake_state: StakeStateV2 = bincode_deserialize(&account.data, "stake account data")?;
+
+        match stake_state {
+            StakeStateV2::Stake(meta, stake, _) => {
+                if stake.delegation.deactivation_epoch != ACTIVE_STAKE_EPOCH_BOUND {
+                    bail!(
+                        "Stake is already deactivating at epoch {}",
+                        stake.delegation.deactivation_epoch
+                    );
+                }
+
+                if &meta.authorized.staker != ctx.pubkey() {
+                    bail!(
+                        "You are not the authorized staker. Authorized staker: {}",
+                        meta.authorized.staker
+                    );
+                }
+            }
+            StakeStateV2::Initialized(_) => {
+                bail!("Stake account is initialized but not delegated");
+            }
+            _ => {
+                bail!("Stake account is not in a valid state for deactivation");
+            }
+        }
+    }
+
+    let authorized_pubkey = ctx.pubkey();
+    let instruction = deactivate_stake(stake_pubkey, authorized_pubkey);
+
+    let signature = match build_and_send_tx(ctx, &[instruction], &[ctx.keypair()], tx_args).await? {
+        TxOutcome::Sent(signature) => signature,
+        TxOutcome::SignedOnly(signed) => return Ok(TxOutcome::SignedOnly(signed)),
+    };
+
+    Ok(TxOutcome::Sent(DeactivateStakeOutput {
+        signature: signature.to_string(),
+        stake_account: stake_pubkey.to_string(),
+    }))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BulkOperation {
+    Deactivate,
+    /// Withdraw the whole balance to `recipient`
+    Withdraw {
+        recipient: Pubkey,
+    },
+}
+
+impl BulkOperation {
+    fn instruction(&self, stake_pubkey: &Pubkey, authority: &Pubkey, lamports: u64) -> Instruction {
+        match self {
+            BulkOperation::Deactivate => deactivate_stake(stake_pubkey, authority),
+            BulkOperation::Withdraw { recipient } => {
+                withdraw(stake_pubkey, authority, recipient, lamports, None)
+            }
+        }
+    }
+}
+
+impl fmt::Display for BulkOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BulkOperation::Deactivate => write!(f, "deactivate"),
+            BulkOperation::Withdraw { .. } => write!(f, "withdraw"),
+        }
+    }
+}
+
+/// Checks that `authority` can apply `operation` to a stake account right
+/// away and returns the lamports it affects: the delegated stake when
+/// deactivating, the whole balance when withdrawing.
+fn check_bulk_stake_account(
+    operation: BulkOperation,
+    stake_state: &StakeStateV2,
+    lamports: u64,
+    authority: &Pubkey,
+    cluster: &StakeClusterState,
+) -> anyhow::Result<u64> {
+    let meta = match stake_state {
+        StakeStateV2::Stake(meta, _, _) | StakeStateV2::Initialized(meta) => meta,
+        StakeStateV2::Uninitialized => bail!("Stake account is uninitialized"),
+        StakeStateV2::RewardsPool => bail!("Account is a rewards pool"),
+    };
+
+    match operation {
+        BulkOperation::Deactivate => {
+            let StakeStateV2::Stake(_, stake, _) = stake_state else {
+                bail!("Stake account is initialized but not delegated");
+            };
+            if stake.delegation.deactivation_epoch != ACTIVE_STAKE_EPOCH_BOUND {
+                bail!(
+                    "Stake is already deactivating at epoch {}",
+                    stake.delegation.deactivation_epoch
+                );
+            }
+            if &meta.authorized.staker != authority {
+                bail!("Authorized staker is {}", meta.authorized.staker);
+            }
+            Ok(stake.delegation.stake)
+        }
+        BulkOperation::Withdraw { .. } => {
+            if &meta.authorized.withdrawer != authority {
+                bail!("Authorized withdrawer is {}", meta.authorized.withdrawer);
+            }
+            if meta.lockup.is_in_force(&cluster.clock, None) {
+                bail!(
+                    "Locked up until epoch {} / unix timestamp {}",
+                    meta.lockup.epoch,
+                    meta.lockup.unix_timestamp
+                );
+            }
+            if withdrawable_lamports(lamports, stake_state, cluster) < lamports {
+                bail!("Stake is still active or cooling down");
+            }
+            Ok(lamports)
+        }
+    }
+}
+
+/// Reads stake account addresses from `path`, one per line. Blank lines and
+/// lines starting with `#` are skipped, and anything after a comma is
+/// ignored so CSV exports can be used directly.
+fn read_stake_account_file(path: &Path) -> anyhow::Result<Vec<Pubkey>> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let mut seen = HashSet::new();
+    let mut stake_accounts = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let address = line.split(',').next().unwrap_or_default().trim();
+        if address.is_empty() || address.starts_with('#') {
+            continue;
+        }
+        let pubkey: Pubkey = address
+            .parse()
+            .map_err(|_| anyhow!("Line {}: invalid address {address}", index + 1))?;
+        if seen.insert(pubkey) {
+            stake_accounts.push(pubkey);
+        }
+    }
+
+    if stake_accounts.is_empty() {
+        bail!("No stake account addresses found in {}", path.display());
+    }
+    Ok(stake_accounts)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BulkStatus {
+    Pending,
+    Skipped,
+    Succeeded,
+    Failed,
+}
+
+impl fmt::Display for BulkStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self {
+            BulkStatus::Pending => "pending",
+            BulkStatus::Skipped => "skipped",
+            BulkStatus::Succeeded => "succeeded",
+            BulkStatus::Failed => "failed",
+        };
+        write!(f, "{status}")
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkStakeAccountOutput {
+    pub stake_account: String,
+    pub lamports: u64,
+    pub status: BulkStatus,
+    pub signature: Option<String>,
+    /// Why the account was skipped or its transaction failed
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkStakeOutput {
+    pub operation: String,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub lamports: u64,
+    pub accounts: Vec<BulkStakeAccountOutput>,
+}
+
+impl BulkStakeOutput {
+    fn count(&self, status: BulkStatus) -> usize {
+        self.accounts
+            .iter()
+            .filter(|account| account.status == status)
+            .count()
+    }
+
+    fn table(&self) -> Table {
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL).set_header(vec![
+            Cell::new("Stake Account").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Amount (SOL)").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Status").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Signature / Reason").add_attribute(comfy_table::Attribute::Bold),
+        ]);
+        for account in &self.accounts {
+            let status = match account.status {
+                BulkStatus::Succeeded => style(account.status).green(),
+                BulkStatus::Pending => style(account.status).cyan(),
+                BulkStatus::Skipped => style(account.status).yellow(),
+                BulkStatus::Failed => style(account.status).red(),
+            };
+            table.add_row(vec![
+                Cell::new(&account.stake_account),
+                Cell::new(lamports_to_sol(account.lamports)),
+                Cell::new(status.to_string()),
+                Cell::new(
+                    account
+                        .reason
+                        .as_deref()
+                        .or(account.signature.as_deref())
+                        .unwrap_or("-"),
+                ),
+            ]);
+        }
+        table
+    }
+}
+
+impl CommandOutput for BulkStakeOutput {
+    fn print_table(&self) {
+        println!(
+            "\n{}",
+            style(format!("BULK {}", self.operation.to_uppercase()))
+                .green()
+                .bold()
+        );
+        println!("{}", self.table());
+        println!(
+            "{}",
+            style(format!(
+                "Succeeded: {}, failed: {}, skipped: {} ({} SOL)",
+                self.succeeded,
+                self.failed,
+                self.skipped,
+                lamports_to_sol(self.lamports)
+            ))
+            .cyan()
+        );
+    }
+}
+
+/// Accounts of a bulk operation with the transactions for the ones that
+/// passed validation.
+struct BulkStakePlan {
+    output: BulkStakeOutput,
+    batches: Vec<Batch<usize>>,
+    tx_args: TxArgs,
+}
+
+impl BulkStakePlan {
+    /// Prints the plan ahead of the command result.
+    fn print(&self, ctx: &ScillaContext) {
+        let rendered = format!(
+            "\n{}\n{}\n{}",
+            style(format!(
+                "BULK {} PLAN",
+                self.output.operation.to_uppercase()
+            ))
+            .green()
+            .bold(),
+            self.output.table(),
+            style(format!(
+                "{} accounts to {} in {} transactions, {} skipped",
+                self.output.count(BulkStatus::Pending),
+                self.output.operation,
+                self.batches.len(),
+                self.output.count(BulkStatus::Skipped)
+            ))
+            .cyan()
+        );
+        print_status(ctx, rendered);
+    }
+}
+
+async fn process_bulk_stake_command(
+    ctx: &ScillaContext,
+    command: &StakeCommand,
+    operation: BulkOperation,
+    args: &BulkStakeArgs,
+) -> ScillaResult<()> {
+    let file = match &args.file {
+        Some(file) => Some(file.clone()),
+        None if ctx.is_interactive() => prompt_optional_input_data(
+            "Enter File of Stake Account Addresses (empty for all your stake accounts):",
+        ),
+        None => None,
+    };
+    let stake_accounts = match &file {
+        Some(file) => Some(read_stake_account_file(file).map_err(report_error)?),
+        None => None,
+    };
+    let concurrency = args
+        .concurrency
+        .map_or(DEFAULT_BATCH_CONCURRENCY, NonZeroUsize::get);
+
+    let plan = show_spinner(
+        "Validating stake accounts…",
+        prepare_bulk_stake(ctx, operation, stake_accounts, &args.compute_budget),
+    )
+    .await?;
+    plan.print(ctx);
+
+    if plan.batches.is_empty() {
+        print_status(
+            ctx,
+            style(format!("No stake account can be {operation}d")).yellow(),
+        );
+        if ctx.output_format().is_json() {
+            ctx.output_format().render(&plan.output)?;
+        }
+        return Ok(CommandFlow::Process(()));
+    }
+    if !ctx.skip_confirmation()
+        && !Confirm::new(&format!("Send {} transactions?", plan.batches.len()))
+            .with_default(false)
+            .prompt()
+            .unwrap_or(false)
+    {
+        return Err(report_error(anyhow!("Bulk {operation} aborted")));
+    }
+
+    let summary = show_spinner(
+        command.spinner_msg(),
+        process_bulk_stake(ctx, plan, concurrency),
+    )
+    .await?;
+    ctx.output_format().render(&summary)?;
+
+    if summary.failed > 0 {
+        let err = anyhow!("{} stake accounts failed to {operation}", summary.failed);
+        return Err(report_error(err));
+    }
+
+    Ok(CommandFlow::Process(()))
+}
+
+/// Validates every account against the operation and packs the instructions
+/// of the valid ones into as few transactions as possible. Without
+/// `stake_accounts`, every account the configured keypair has the needed
+/// authority over is used.
+async fn prepare_bulk_stake(
+    ctx: &ScillaContext,
+    operation: BulkOperation,
+    stake_accounts: Option<Vec<Pubkey>>,
+    compute_budget: &ComputeBudgetArgs,
+) -> anyhow::Result<BulkStakePlan> {
+    let authority = *ctx.pubkey();
+    let role = match operation {
+        BulkOperation::Deactivate => AuthorityRole::Staker,
+        BulkOperation::Withdraw { .. } => AuthorityRole::Withdrawer,
+    };
+
+    let (accounts, (_, cluster)) = tokio::try_join!(
+        async {
+            match &stake_accounts {
+                Some(stake_accounts) => {
+                    let chunks = stake_accounts
+                        .chunks(MAX_MULTIPLE_ACCOUNTS)
+                        .map(|chunk| ctx.rpc().get_multiple_accounts(chunk));
+                    let accounts = futures::future::try_join_all(chunks).await?;
+                    Ok(stake_accounts
+                        .iter()
+                        .copied()
+                        .zip(accounts.into_iter().flatten())
+                        .collect::<Vec<_>>())
+                }
+                None => Ok(fetch_stake_accounts_of_authority(ctx, &authority, role)
+                    .await?
+                    .into_iter()
+                    .map(|(pubkey, account)| (pubkey, Some(account)))
+                    .collect()),
+            }
+        },
+        fetch_with_stake_cluster_state(ctx, &[]),
+    )?;
+    if accounts.is_empty() {
+        bail!("No stake accounts found for {authority}");
+    }
+
+    let checked: Vec<(Pubkey, anyhow::Result<u64>)> = accounts
+        .iter()
+        .map(|(pubkey, account)| {
+            let result = account
+                .as_ref()
+                .ok_or_else(|| anyhow!("Account does not exist"))
+                .and_then(|account| {
+                    if account.owner != stake_program_id() {
+                        bail!("Account is not owned by the stake program");
+                    }
+                    let stake_state: StakeStateV2 =
+                        bincode_deserialize(&account.data, "stake account data")?;
+                    check_bulk_stake_account(
+                        operation,
+                        &stake_state,
+                        account.lamports,
+                        &authority,
+                        &cluster,
+                    )
+                });
+            (*pubkey, result)
+        })
+        .collect();
+
+    let instructions: Vec<(usize, Vec<Instruction>)> = checked
+        .iter()
+        .enumerate()
+        .filter_map(|(i, (pubkey, result))| {
+            let lamports = *result.as_ref().ok()?;
+            Some((i, vec![operation.instruction(pubkey, &authority, lamports)]))
+        })
+        .collect();
+
+    let (tx_args, prefix) = pin_batch_tx_args(ctx, compute_budget, &instructions).await?;
+
+    let batches = pack_instructions(instructions, &authority, &prefix)?;
+
+    let accounts = checked
+        .into_iter()
+        .map(|(pubkey, result)| {
+            let (lamports, status, reason) = match result {
+                Ok(lamports) => (lamports, BulkStatus::Pending, None),
+                Err(err) => (0, BulkStatus::Skipped, Some(err.to_string())),
+            };
+            BulkStakeAccountOutput {
+                stake_account: pubkey.to_string(),
+                lamports,
+                status,
+                signature: None,
+                reason,
+            }
+        })
+        .collect::<Vec<_>>();
+    let skipped = accounts
+        .iter()
+        .filter(|account| account.status == BulkStatus::Skipped)
+        .count();
+
+    Ok(BulkStakePlan {
+        output: BulkStakeOutput {
+            operation: operation.to_string(),
+            succeeded: 0,
+            failed: 0,
+            skipped,
+            lamports: 0,
+            accounts,
+        },
+        batches,
+        tx_args,
+    })
+}
+
+async fn process_bulk_stake(
+    ctx: &ScillaContext,
+    plan: BulkStakePlan,
+    concurrency: usize,
+) -> anyhow::Result<BulkStakeOutput> {
+    let BulkStakePlan {
+        mut output,
+        batches,
+        tx_args,
+    } = plan;
+
+    let results: Vec<(Vec<usize>, BulkBatchResult)> = stream::iter(batches)
+        .map(|batch| {
+            let tx_args = &tx_args;
+            async move {
+                let result = send_batch(ctx, &batch.instructions, &[ctx.keypair()], tx_args).await;
+                (batch.items, result)
+            }
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
+
+    for (items, result) in results {
+        for i in items {
+            let account = &mut output.accounts[i];
+            account.signature = result.signature.clone();
+            account.reason = result.error.clone();
+            account.status = match result.error {
+                Some(_) => BulkStatus::Failed,
+                None => BulkStatus::Succeeded,
+            };
+        }
+    }
+
+    output.succeeded = output.count(BulkStatus::Succeeded);
+    output.failed = output.count(BulkStatus::Failed);
+    output.skipped = output.count(BulkStatus::Skipped);
+    output.lamports = output
+        .accounts
+        .iter()
+        .filter(|account| account.status == BulkStatus::Succeeded)
+        .map(|account| account.lamports)
+        .sum();
+
+    Ok(output)
+}
+
+struct BulkBatchResult {
+    /// Signature of the transaction, if it could be built
+    signature: Option<String>,
+    error: Option<String>,
+}
+
+/// Sends one transaction of a multi-transaction operation. A failure is
+/// recorded in the result so it only affects the items of this transaction.
+async fn send_batch(
+    ctx: &ScillaContext,
+    instructions: &[Instruction],
+    signers: &[&dyn Signer],
+    tx_args: &TxArgs,
+) -> BulkBatchResult {
+    let tx = match build_tx(ctx, instructions, signers, tx_args).await {
+        Ok(tx) => tx,
+        Err(err) => {
+            return BulkBatchResult {
+                signature: None,
+                error: Some(err.to_string()),
+            };
+        }
+    };
+    let error = ctx
+        .rpc()
+        .send_and_confirm_transaction(&tx)
+        .await
+        .err()
+        .map(|err| err.to_string());
+    BulkBatchResult {
+        signature: Some(tx.signatures[0].to_string()),
+        error,
+    }
+}
+
+/// Reads the voting record of a vote account.
+fn vote_epoch_credits(
+    vote_pubkey: &Pubkey,
+    account: Option<&Account>,
+) -> anyhow::Result<Vec<(Epoch, u64, u64)>> {
+    let account = account.ok_or_else(|| anyhow!("{vote_pubkey} account does not exist"))?;
+    if account.owner != solana_vote_interface::program::id() {
+        bail!("{vote_pubkey} is not a vote account");
+    }
+    let vote_state = VoteStateV4::deserialize(&account.data, vote_pubkey)
+        .map_err(|_| anyhow!("Account data could not be deserialized to vote state"))?;
+    Ok(vote_state.epoch_credits)
+}
+
+/// Delinquent validators and the deactivations of the stake still
+/// delegated to them.
+struct DeactivateDelinquentPlan {
+    reference_vote: Pubkey,
+    /// Delinquent vote accounts with the last epoch they earned credits in
+    delinquent: Vec<(Pubkey, Option<Epoch>)>,
+    stake: BulkStakePlan,
+}
+
+impl DeactivateDelinquentPlan {
+    /// Prints the plan ahead of the command result.
+    fn print(&self, ctx: &ScillaContext) {
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL).set_header(vec![
+            Cell::new("Delinquent Vote Account").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Last Voted Epoch").add_attribute(comfy_table::Attribute::Bold),
+        ]);
+        for (vote_pubkey, last_epoch) in &self.delinquent {
+            table.add_row(vec![
+                Cell::new(vote_pubkey),
+                Cell::new(
+                    last_epoch.map_or_else(|| "never".to_string(), |epoch| epoch.to_string()),
+                ),
+            ]);
+        }
+        let rendered = format!(
+            "\n{}\n{table}\n{}",
+            style("DELINQUENT VALIDATORS").green().bold(),
+            style(format!("Reference vote account: {}", self.reference_vote)).cyan()
+        );
+        print_status(ctx, rendered);
+        self.stake.print(ctx);
+    }
+}
+
+/// Finds the stake delegated to `vote` (or to every delinquent validator)
+/// that the stake program lets anyone deactivate, proving the cluster was
+/// live with `reference_vote` (or the most staked vote account that
+/// qualifies).
+async fn prepare_deactivate_delinquent(
+    ctx: &ScillaContext,
+    vote: Option<Pubkey>,
+    reference_vote: Option<Pubkey>,
+    compute_budget: &ComputeBudgetArgs,
+) -> anyhow::Result<DeactivateDelinquentPlan> {
+    let (
+        RpcVoteAccountStatus {
+            mut current,
+            delinquent,
+        },
+        epoch_info,
+    ) = tokio::try_join!(ctx.rpc().get_vote_accounts(), ctx.rpc().get_epoch_info())?;
+    let current_epoch = epoch_info.epoch;
+
+    let reference_vote = match reference_vote {
+        Some(reference_vote) => {
+            let account = ctx
+                .rpc()
+                .get_account_with_commitment(&reference_vote, ctx.rpc().commitment())
+                .await?
+                .value;
+            let epoch_credits = vote_epoch_credits(&reference_vote, account.as_ref())?;
+            if !acceptable_reference_epoch_credits(&epoch_credits, current_epoch) {
+                bail!(
+                    "Reference vote account {reference_vote} has not voted in each of the last \
+                     {MINIMUM_DELINQUENT_EPOCHS_FOR_DEACTIVATION} epochs"
+                );
+            }
+            reference_vote
+        }
+        None => {
+            current.sort_by_key(|vote_account| Reverse(vote_account.activated_stake));
+            let vote_account = current
+                .iter()
+                .find(|vote_account| {
+                    acceptable_reference_epoch_credits(&vote_account.epoch_credits, current_epoch)
+                })
+                .ok_or_else(|| {
+                    anyhow!(
+                        "No vote account has voted in each of the last \
+                         {MINIMUM_DELINQUENT_EPOCHS_FOR_DEACTIVATION} epochs"
+                    )
+                })?;
+            Pubkey::from_str(&vote_account.vote_pubkey)?
+        }
+    };
+
+    // The RPC only reports recent credits, so eligibility is checked against
+    // the full voting record the stake program reads
+    let candidates = match vote {
+        Some(vote) => vec![vote],
+        None => delinquent
+            .iter()
+            .map(|vote_account| Pubkey::from_str(&vote_account.vote_pubkey))
+            .collect::<Result<_, _>>()?,
+    };
+    let chunks = candidates
+        .chunks(MAX_MULTIPLE_ACCOUNTS)
+        .map(|chunk| ctx.rpc().get_multiple_accounts(chunk));
+    let vote_accounts = futures::future::try_join_all(chunks).await?;
+
+    let mut delinquent = Vec::new();
+    for (vote_pubkey, account) in candidates.iter().zip(vote_accounts.into_iter().flatten()) {
+        let epoch_credits = match vote_epoch_credits(vote_pubkey, account.as_ref()) {
+            Ok(epoch_credits) => epoch_credits,
+            Err(err) if vote.is_some() => return Err(err),
+            // Closed since the vote accounts were listed
+            Err(_) => continue,
+        };
+        let last_epoch = epoch_credits.last().map(|(epoch, ..)| *epoch);
+        if eligible_for_deactivate_delinquent(&epoch_credits, current_epoch) {
+            delinquent.push((*vote_pubkey, last_epoch));
+        } else if vote.is_some() {
+            bail!(
+                "{vote_pubkey} voted in epoch {}; its stake can be deactivated from epoch {}",
+                last_epoch.unwrap_or_default(),
+                last_epoch.unwrap_or_default()
+                    + MINIMUM_DELINQUENT_EPOCHS_FOR_DEACTIVATION as Epoch
+            );
+        }
+    }
+
+    let delegated = futures::future::try_join_all(
+        delinquent
+            .iter()
+            .map(|(vote_pubkey, _)| fetch_stake_accounts_delegated_to(ctx, vote_pubkey)),
+    )
+    .await?;
+
+    // Stake that is already cooling down can't be deactivated again
+    let stake_accounts: Vec<(Pubkey, Pubkey, u64)> = delinquent
+        .iter()
+        .zip(delegated)
+        .flat_map(|((vote_pubkey, _), accounts)| {
+            accounts
+                .into_iter()
+                .filter_map(move |(stake_pubkey, account)| {
+                    let stake_state: StakeStateV2 =
+                        bincode_deserialize(&account.data, "stake account data").ok()?;
+                    let stake = stake_state.stake()?;
+                    (stake.delegation.voter_pubkey == *vote_pubkey
+                        && stake.delegation.deactivation_epoch == ACTIVE_STAKE_EPOCH_BOUND)
+                        .then_some((stake_pubkey, *vote_pubkey, stake.delegation.stake))
+                })
+        })
+        .collect();
+
+    let instructions: Vec<(usize, Vec<Instruction>)> = stake_accounts
+        .iter()
+        .enumerate()
+        .map(|(i, (stake_pubkey, vote_pubkey, _))| {
+            (
+                i,
+                vec![deactivate_delinquent_stake(
+                    stake_pubkey,
+                    vote_pubkey,
+                    &reference_vote,
+                )],
+            )
+        })
+        .collect();
+
+    let (tx_args, prefix) = pin_batch_tx_args(ctx, compute_budget, &instructions).await?;
+
+    let batches = pack_instructions(instructions, ctx.pubkey(), &prefix)?;
+
+    let accounts = stake_accounts
+        .into_iter()
+        .map(|(stake_pubkey, _, lamports)| BulkStakeAccountOutput {
+            stake_account: stake_pubkey.to_string(),
+            lamports,
+            status: BulkStatus::Pending,
+            signature: None,
+            reason: None,
+        })
+        .collect();
+
+    Ok(DeactivateDelinquentPlan {
+        reference_vote,
+        delinquent,
+        stake: BulkStakePlan {
+            output: BulkStakeOutput {
+                operation: "deactivate delinquent".to_string(),
+                succeeded: 0,
+                failed: 0,
+                skipped: 0,
+                lamports: 0,
+                accounts,
+            },
+            batches,
+            tx_args,
+        },
+    })
+}
+
+async fn fetch_stake_accounts_delegated_to(
+    ctx: &ScillaContext,
+    vote_pubkey: &Pubkey,
+) -> anyhow::Result<Vec<(Pubkey, Account)>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::DataSize(StakeStateV2::size_of() as u64),
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                STAKE_VOTER_OFFSET,
+                vote_pubkey.as_ref(),
+            )),
+        ]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(ctx.rpc().commitment()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let accounts = ctx
+        .rpc()
+        .get_program_ui_accounts_with_config(&stake_program_id(), config)
+        .await?;
+
+    accounts
+        .into_iter()
+        .map(|(pubkey, account)| {
+            account
+                .decode::<Account>()
+                .map(|account| (pubkey, account))
+                .ok_or_else(|| anyhow!("Failed to decode stake account {pubkey}"))
+        })
+        .collect()
+}
+
+#[derive(Debug, Serialize)]
+pub struct WithdrawStakeOutput {
+    pub signature: String,
+    pub stake_account: String,
+    pub recipient: String,
+    pub lamports: u64,
+}
+
+impl CommandOutput for WithdrawStakeOutput {
+    fn print_table(&self) {
+        println!(
+            "{} {}\n{}\n{}\n{}",
+            style("Stake Withdrawn Successfully!").green().bold(),
+            style(format!("From Stake Account: {}", self.stake_account)).yellow(),
+            style(format!("To Recipient: {}", self.recipient)).yellow(),
+            style(format!("Amount: {} SOL", lamports_to_sol(self.lamports))).cyan(),
+            style(format!("Signature: {}", self.signature)).cyan()
+        );
+    }
+}
+
+async fn process_withdraw_stake(
+    ctx: &ScillaContext,
+    stake_pubkey: &Pubkey,
+    recipient: &Pubkey,
+    amount_sol: f64,
+    tx_args: &TxArgs,
+) -> anyhow::Result<TxOutcome<WithdrawStakeOutput>> {
+    let amount_lamports = sol_to_lamports(amount_sol);
+
+    // The account state can only be checked online, so skip it when signing offline
+    if !tx_args.sign_only {
+        let (account, epoch_info) = fetch_account_with_epoch(ctx, stake_pubkey).await?;
+
+        if account.owner != stake_program_id() {
+            bail!("Account is not owned by the stake program");
+        }
+
+        let stake_state: StakeStateV2 = bincode_deserialize(&account.data, "stake account data")?;
+
+        match stake_state {
+            StakeStateV2::Stake(meta, stake, _) => {
+                if &meta.authorized.withdrawer != ctx.pubkey() {
+                    bail!(
+                        "You are not the authorized withdrawer. Authorized withdrawer: {}",
+                        meta.authorized.withdrawer
+                    );
+                }
+
+                if stake.delegation.deactivation_epoch == ACTIVE_STAKE_EPOCH_BOUND {
+                    bail!(
+                        "Stake is still active. You must deactivate it first and wait for the \
+                         cooldown period."
+                    );
+                }
+
+                if epoch_info.epoch <= stake.delegation.deactivation_epoch {
+                    let epochs_remaining = stake.delegation.deactivation_epoch - epoch_info.epoch;
+                    bail!(
+                        "Stake is still cooling down. Current epoch: {}, deactivation epoch: {}, \
+                         epochs remaining: {}",
+                        epoch_info.epoch,
+                        stake.delegation.deactivation_epoch,
+                        epochs_remaining
+                    );
+                }
+            }
+            StakeStateV2::Initialized(meta) => {
+                if &meta.authorized.withdrawer != ctx.pubkey() {
+                    bail!(
+                        "You are not the authorized withdrawer. Authorized withdrawer: {}",
+                        meta.authorized.withdrawer
+                    );
+                }
+            }
+            StakeStateV2::Uninitialized => {
+                bail!("Stake account is uninitialized");
+            }
+            StakeStateV2::RewardsPool => {
+                bail!("Cannot withdraw from rewards pool");
+            }
+        }
+
+        if amount_lamports > account.lamports {
+            bail!(
+                "Insufficient balance. Have {:.6} SOL, trying to withdraw {:.6} SOL",
+                lamports_to_sol(account.lamports),
+                amount_sol
+            );
+        }
+    }
+
+    let withdrawer_pubkey = ctx.pubkey();
+
+    let instruction = withdraw(
+        stake_pubkey,
+        withdrawer_pubkey,
+        recipient,
+        amount_lamports,
+        None,
+    );
+
+    let signature = match build_and_send_tx(ctx, &[instruction], &[ctx.keypair()], tx_args).await? {
+        TxOutcome::Sent(signature) => signature,
+        TxOutcome::SignedOnly(signed) => return Ok(TxOutcome::SignedOnly(signed)),
+    };
+
+    Ok(TxOutcome::Sent(WithdrawStakeOutput {
+        signature: signature.to_string(),
+        stake_account: stake_pubkey.to_string(),
+        recipient: recipient.to_string(),
+        lamports: amount_lamports,
+    }))
+}
+
+#[derive(Debug, Serialize)]
+pub struct MergeStakeOutput {
+    pub signature: String,
+    pub destination_stake_account: String,
+    pub source_stake_account: String,
+    pub stake_authority: String,
+    /// Destination balance once the source has been merged into it
+    pub lamports_after_merge: u64,
+}
+
+impl CommandOutput for MergeStakeOutput {
+    fn print_table(&self) {
+        println!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            style("Stake Merged successfully!").yellow().bold(),
+            style(format!(
+                "Destination Stake Account: {}",
+                self.destination_stake_account
+            ))
+            .yellow(),
+            style(format!(
+                "Source Stake Account: {}",
+                self.source_stake_account
+            ))
+            .yellow(),
+            style(format!("Stake Authority: {}", self.stake_authority)).yellow(),
+            style(format!(
+                "After Merge: {} SOL",
+                lamports_to_sol(self.lamports_after_merge)
+            ))
+            .cyan(),
+            style(format!("Signature: {}", self.signature)).green()
+        );
+    }
+}
+
+async fn process_merge_stake(
+    ctx: &ScillaContext,
+    destination_stake_account_pubkey: &Pubkey,
+    source_stake_account_pubkey: &Pubkey,
+    stake_authority_keypair_path: &PathBuf,
+    tx_args: &TxArgs,
+) -> anyhow::Result<TxOutcome<MergeStakeOutput>> {
+    let stake_authority_keypair = read_keypair_from_path(stake_authority_keypair_path)?;
+
+    // checks for unique pubkeys
+    if destination_stake_account_pubkey == source_stake_account_pubkey {
+        bail!(
+            "Destination Stake Account {} & Source Stake Account {} must not be the same",
+            destination_stake_account_pubkey,
+            source_stake_account_pubkey
+        );
+    }
+
+    let stake_authority_pubkey = stake_authority_keypair.pubkey();
+
+    // The account states can only be checked online, so skip them when signing
+    // offline
+    let lamports_after_merge = if tx_args.sign_only {
+        0
+    } else {
+        check_merge_accounts(
+            ctx,
+            destination_stake_account_pubkey,
+            source_stake_account_pubkey,
+            &stake_authority_pubkey,
+        )
+        .await?
+    };
+
+    let ixs = merge(
+        destination_stake_account_pubkey,
+        source_stake_account_pubkey,
+        &stake_authority_pubkey,
+    );
+
+    let signature = match build_and_send_tx(
+        ctx,
+        &ixs,
+        &[ctx.keypair(), &stake_authority_keypair],
+        tx_args,
+    )
+    .await?
+    {
+        TxOutcome::Sent(signature) => signature,
+        TxOutcome::SignedOnly(signed) => return Ok(TxOutcome::SignedOnly(signed)),
+    };
+
+    Ok(TxOutcome::Sent(MergeStakeOutput {
+        signature: signature.to_string(),
+        destination_stake_account: destination_stake_account_pubkey.to_string(),
+        source_stake_account: source_stake_account_pubkey.to_string(),
+        stake_authority: stake_authority_pubkey.to_string(),
+        lamports_after_merge,
+    }))
+}
+
+/// Checks that both accounts can be merged by the stake authority and
+/// returns the lamports the destination holds after the merge.
+async fn check_merge_accounts(
+    ctx: &ScillaContext,
+    destination_stake_account_pubkey: &Pubkey,
+    source_stake_account_pubkey: &Pubkey,
+    stake_authority_pubkey: &Pubkey,
+) -> anyhow::Result<u64> {
+    let stake_accounts = ctx
+        .rpc()
+        .get_multiple_accounts(&[
+            *destination_stake_account_pubkey,
+            *source_stake_account_pubkey,
+        ])
+        .await?;
+
+    let Some(destination_stake_account) = stake_accounts[0].as_ref() else {
+        bail!("Failed to get stake account");
+    };
+
+    let Some(source_stake_account) = stake_accounts[1].as_ref() else {
+        bail!("Failed to get stake account");
+    };
+
+    let destination_stake_state: StakeStateV2 = bincode_deserialize(
+        &destination_stake_account.data,
+        "destination stake account data",
+    )?;
+
+    let source_stake_state: StakeStateV2 =
+        bincode_deserialize(&source_stake_account.data, "source stake account data")?;
+
+    match &destination_stake_state {
+        StakeStateV2::Initialized(meta) => {
+            // Initialized destination is valid
+            (meta, None)
+        }
+        StakeStateV2::Stake(meta, stake, _) => {
+            // Delegated destination is valid
+            (meta, Some(&stake.delegation))
+        }
+        _ => bail!("Destination stake account is not in a valid state"),
+    };
+
+    match &source_stake_state {
+        StakeStateV2::Initialized(meta) => {
+            // CHECK: Verify authority for initialized source
+            if meta.authorized.staker != *stake_authority_pubkey {
+                bail!(
+                    "Provided keypair is not the stake authority for source account\nExpected: \
+                     {}\nProvided: {}",
+                    meta.authorized.staker,
+                    stake_authority_pubkey
+                );
+            }
+
+            (meta, None)
+        }
+        StakeStateV2::Stake(meta, stake, _) => {
+            // CHECK: Verify authority for delegated source
+            if meta.authorized.staker != *stake_authority_pubkey {
+                bail!(
+                    "Provided keypair is not the stake authority for source account\nExpected: \
+                     {}\nProvided: {}",
+                    meta.authorized.staker,
+                    stake_authority_pubkey
+                );
+            }
+
+            // CHECK: Source must not be deactivating
+            if stake.delegation.deactivation_epoch != u64::MAX {
+                bail!(
+                    "Cannot merge: source stake account is deactivating at epoch {}",
+                    stake.delegation.deactivation_epoch
+                );
+            }
+
+            (meta, Some(&stake.delegation))
+        }
+        _ => bail!("Source stake account is not in a valid state"),
+    };
+
+    Ok(destination_stake_account.lamports + source_stake_account.lamports)
+}
+
+/// Stake accounts the stake program can merge into each other: same
+/// authorities, same lockup unless neither is in force, and the same merge
+/// kind and validator. Inactive accounts are kept apart from stake still in
+/// its activation epoch, which merging would delegate them into.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct MergeGroupKey {
+    staker: Pubkey,
+    withdrawer: Pubkey,
+    /// Lockup epoch, date and custodian while the lockup is in force
+    lockup: Option<(Epoch, i64, Pubkey)>,
+    state: MergeState,
+    voter: Option<Pubkey>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeState {
+    Active,
+    Activating,
+    Inactive,
+}
+
+impl fmt::Display for MergeState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeState::Active => write!(f, "Active"),
+            MergeState::Activating => write!(f, "Activating"),
+            MergeState::Inactive => write!(f, "Inactive"),
+        }
+    }
+}
+
+/// Accounts of a group that merge into `destination`
+#[derive(Debug, Clone, PartialEq)]
+struct MergeGroup {
+    destination: Pubkey,
+    state: MergeState,
+    voter: Option<Pubkey>,
+    /// Sources with their balance
+    sources: Vec<(Pubkey, u64)>,
+}
+
+/// Groups the stake accounts that can be merged, each into its largest
+/// account. Accounts in transition, or without any account to merge with,
+/// are left out; the ones in transition are returned with the reason.
+fn plan_merges(
+    accounts: &[(Pubkey, u64, StakeStateV2)],
+    cluster: &StakeClusterState,
+) -> (Vec<MergeGroup>, Vec<(Pubkey, String)>) {
+    let mut groups: BTreeMap<MergeGroupKey, Vec<(Pubkey, u64)>> = BTreeMap::new();
+    let mut unmergeable = Vec::new();
+
+    for (pubkey, lamports, stake_state) in accounts {
+        let (meta, kind) = match MovableStake::new(stake_state, cluster, "The") {
+            Ok(movable) => movable,
+            Err(err) => {
+                unmergeable.push((*pubkey, err.to_string()));
+                continue;
+            }
+        };
+        let (state, voter) = match kind {
+            MovableStake::Inactive => (MergeState::Inactive, None),
+            MovableStake::ActivationEpoch(delegation) => {
+                (MergeState::Activating, Some(delegation.voter_pubkey))
+            }
+            MovableStake::FullyActive(delegation) => {
+                (MergeState::Active, Some(delegation.voter_pubkey))
+            }
+        };
+        let lockup = meta.lockup;
+        let key = MergeGroupKey {
+            staker: meta.authorized.staker,
+            withdrawer: meta.authorized.withdrawer,
+            lockup: lockup.is_in_force(&cluster.clock, None).then_some((
+                lockup.epoch,
+                lockup.unix_timestamp,
+                lockup.custodian,
+            )),
+            state,
+            voter,
+        };
+        groups.entry(key).or_default().push((*pubkey, *lamports));
+    }
+
+    let groups = groups
+        .into_iter()
+        .filter(|(_, accounts)| accounts.len() > 1)
+        .map(|(key, mut accounts)| {
+            accounts.sort_by_key(|(pubkey, lamports)| (Reverse(*lamports), *pubkey));
+            let (destination, _) = accounts.remove(0);
+            MergeGroup {
+                destination,
+                state: key.state,
+                voter: key.voter,
+                sources: accounts,
+            }
+        })
+        .collect();
+
+    (groups, unmergeable)
+}
+
+#[derive(Debug, Serialize)]
+pub struct MergeOutput {
+    pub destination: String,
+    pub source: String,
+    pub state: MergeState,
+    pub validator: Option<String>,
+    pub lamports: u64,
+    pub status: BulkStatus,
+    pub signature: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UnmergeableOutput {
+    pub stake_account: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MergeAllOutput {
+    pub authority: String,
+    pub accounts_before: usize,
+    pub accounts_after: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub merges: Vec<MergeOutput>,
+    pub unmergeable: Vec<UnmergeableOutput>,
+}
+
+impl MergeAllOutput {
+    fn table(&self) -> Table {
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL).set_header(vec![
+            Cell::new("Destination").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Source").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("State").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Validator").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Amount (SOL)").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Status").add_attribute(comfy_table::Attribute::Bold),
+        ]);
+        for merge in &self.merges {
+            let status = match merge.status {
+                BulkStatus::Succeeded => style(merge.status).green(),
+                BulkStatus::Pending | BulkStatus::Skipped => style(merge.status).cyan(),
+                BulkStatus::Failed => style(merge.status).red(),
+            };
+            table.add_row(vec![
+                Cell::new(&merge.destination),
+                Cell::new(&merge.source),
+                Cell::new(merge.state),
+                Cell::new(merge.validator.as_deref().unwrap_or("-")),
+                Cell::new(lamports_to_sol(merge.lamports)),
+                Cell::new(status.to_string()),
+            ]);
+        }
+        table
+    }
+
+    fn details(&self) -> String {
+        let mut lines = Vec::new();
+        for merge in &self.merges {
+            if let Some(error) = &merge.error {
+                lines.push(
+                    style(format!("{}: {error}", merge.source))
+                        .red()
+                        .to_string(),
+                );
+            }
+        }
+        for unmergeable in &self.unmergeable {
+            lines.push(
+                style(format!(
+                    "{} can't be merged now: {}",
+                    unmergeable.stake_account, unmergeable.reason
+                ))
+                .yellow()
+                .to_string(),
+            );
+        }
+        lines.push(
+            style(format!(
+                "Stake accounts: {} -> {}",
+                self.accounts_before, self.accounts_after
+            ))
+            .cyan()
+            .to_string(),
+        );
+        lines.join("\n")
+    }
+}
+
+impl CommandOutput for MergeAllOutput {
+    fn print_table(&self) {
+        println!("\n{}", style("MERGED STAKE ACCOUNTS").green().bold());
+        println!("{}", self.table());
+        println!("{}", self.details());
+    }
+}
+
+/// Merges of compatible stake accounts, packed into transactions.
+struct MergeAllPlan {
+    output: MergeAllOutput,
+    batches: Vec<Batch<usize>>,
+    stake_authority: Keypair,
+    tx_args: TxArgs,
+}
+
+impl MergeAllPlan {
+    /// Prints the plan ahead of the command result.
+    fn print(&self, ctx: &ScillaContext) {
+        let rendered = format!(
+            "\n{}\n{}\n{}",
+            style("STAKE MERGE PLAN").green().bold(),
+            self.output.table(),
+            self.output.details()
+        );
+        print_status(ctx, rendered);
+    }
+}
+
+async fn prepare_merge_all(
+    ctx: &ScillaContext,
+    stake_authority_keypair_path: &PathBuf,
+    compute_budget: &ComputeBudgetArgs,
+) -> anyhow::Result<MergeAllPlan> {
+    let stake_authority = read_keypair_from_path(stake_authority_keypair_path)?;
+    let authority = stake_authority.pubkey();
+
+    let (accounts, (_, cluster)) = tokio::try_join!(
+        fetch_stake_accounts_of_authority(ctx, &authority, AuthorityRole::Staker),
+        fetch_with_stake_cluster_state(ctx, &[]),
+    )?;
+    if accounts.is_empty() {
+        bail!("No stake accounts found for stake authority {authority}");
+    }
+    let accounts: Vec<(Pubkey, u64, StakeStateV2)> = accounts
+        .into_iter()
+        .map(|(pubkey, account)| {
+            let stake_state = bincode_deserialize(&account.data, "stake account data")?;
+            Ok((pubkey, account.lamports, stake_state))
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    let (groups, unmergeable) = plan_merges(&accounts, &cluster);
+    let merges: Vec<MergeOutput> = groups
+        .iter()
+        .flat_map(|group| {
+            group.sources.iter().map(|(source, lamports)| MergeOutput {
+                destination: group.destination.to_string(),
+                source: source.to_string(),
+                state: group.state,
+                validator: group.voter.map(|voter| voter.to_string()),
+                lamports: *lamports,
+                status: BulkStatus::Pending,
+                signature: None,
+                error: None,
+            })
+        })
+        .collect();
+
+    let instructions: Vec<(usize, Vec<Instruction>)> = groups
+        .iter()
+        .flat_map(|group| {
+            group
+                .sources
+                .iter()
+                .map(|(source, _)| merge(&group.destination, source, &authority))
+        })
+        .enumerate()
+        .collect();
+
+    let (tx_args, prefix) = pin_batch_tx_args(ctx, compute_budget, &instructions).await?;
+    let batches = pack_instructions(instructions, ctx.pubkey(), &prefix)?;
+
+    Ok(MergeAllPlan {
+        output: MergeAllOutput {
+            authority: authority.to_string(),
+            accounts_before: accounts.len(),
+            accounts_after: accounts.len() - merges.len(),
+            succeeded: 0,
+            failed: 0,
+            merges,
+            unmergeable: unmergeable
+                .into_iter()
+                .map(|(stake_account, reason)| UnmergeableOutput {
+                    stake_account: stake_account.to_string(),
+                    reason,
+                })
+                .collect(),
+        },
+        batches,
+        stake_authority,
+        tx_args,
+    })
+}
+
+async fn process_merge_all(
+    ctx: &ScillaContext,
+    plan: MergeAllPlan,
+    concurrency: usize,
+) -> anyhow::Result<MergeAllOutput> {
+    let MergeAllPlan {
+        mut output,
+        batches,
+        stake_authority,
+        tx_args,
+    } = plan;
+    let signers: [&dyn Signer; 2] = [ctx.keypair(), &stake_authority];
+
+    let results: Vec<(Vec<usize>, BulkBatchResult)> = stream::iter(batches)
+        .map(|batch| {
+            let (signers, tx_args) = (&signers, &tx_args);
+            async move {
+                let result = send_batch(ctx, &batch.instructions, signers, tx_args).await;
+                (batch.items, result)
+            }
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
+
+    for (items, result) in results {
+        for i in items {
+            let merge = &mut output.merges[i];
+            merge.signature = result.signature.clone();
+            merge.error = result.error.clone();
+            merge.status = match result.error {
+                Some(_) => BulkStatus::Failed,
+                None => BulkStatus::Succeeded,
+            };
+        }
+    }
+
+    output.succeeded = output
+        .merges
+        .iter()
+        .filter(|merge| merge.status == BulkStatus::Succeeded)
+        .count();
+    output.failed = output.merges.len() - output.succeeded;
+    output.accounts_after = output.accounts_before - output.succeeded;
+
+    Ok(output)
+}
+
+/// What a move transfers between two stake accounts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MoveOperation {
+    /// Active stake, keeping it active in the destination
+    Stake,
+    /// Lamports that are neither delegated nor reserved for rent
+    Lamports,
+}
+
+impl fmt::Display for MoveOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operation = match self {
+            MoveOperation::Stake => "stake",
+            MoveOperation::Lamports => "lamports",
+        };
+        write!(f, "{operation}")
+    }
+}
+
+/// Stake account states the stake program can move stake or lamports
+/// between, mirroring its merge rules. Accounts still warming up past their
+/// activation epoch or cooling down can't take part.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MovableStake {
+    Inactive,
+    ActivationEpoch(Delegation),
+    FullyActive(Delegation),
+}
+
+impl MovableStake {
+    fn new(
+        stake_state: &StakeStateV2,
+        cluster: &StakeClusterState,
+        label: &str,
+    ) -> anyhow::Result<(Meta, Self)> {
+        match stake_state {
+            StakeStateV2::Initialized(meta) => Ok((*meta, MovableStake::Inactive)),
+            StakeStateV2::Stake(meta, stake, _) => {
+                let movable = match cluster.activation(&stake.delegation) {
+                    StakeActivationStatus {
+                        effective: 0,
+                        activating: 0,
+                        deactivating: 0,
+                    } => MovableStake::Inactive,
+                    StakeActivationStatus { effective: 0, .. } => {
+                        MovableStake::ActivationEpoch(stake.delegation)
+                    }
+                    StakeActivationStatus {
+                        activating: 0,
+                        deactivating: 0,
+                        ..
+                    } => MovableStake::FullyActive(stake.delegation),
+                    _ => bail!(
+                        "{label} stake account is still activating or deactivating. Wait until \
+                         the stake is fully active or inactive"
+                    ),
+                };
+                Ok((*meta, movable))
+            }
+            _ => bail!("{label} stake account is not in a valid state"),
+        }
+    }
+}
+
+/// Checks a move of `lamports` from `source` to `destination` (state and
+/// balance of each) against the stake program's rules.
+fn check_move_stake(
+    operation: MoveOperation,
+    source: (&StakeStateV2, u64),
+    destination: (&StakeStateV2, u64),
+    stake_authority: &Pubkey,
+    lamports: u64,
+    minimum_delegation: u64,
+    cluster: &StakeClusterState,
+) -> anyhow::Result<()> {
+    let (source_meta, source_kind) = MovableStake::new(source.0, cluster, "Source")?;
+    let (destination_meta, destination_kind) =
+        MovableStake::new(destination.0, cluster, "Destination")?;
+
+    // CHECK: Both accounts are controlled by the same authorities
+    if source_meta.authorized != destination_meta.authorized {
+        bail!(
+            "Source and destination stake accounts must have the same stake and withdraw \
+             authorities"
+        );
+    }
+    if source_meta.authorized.staker != *stake_authority {
+        bail!(
+            "Provided keypair is not the stake authority\nExpected: {}\nProvided: {}",
+            source_meta.authorized.staker,
+            stake_authority
+        );
+    }
+
+    // CHECK: Lockups in force must match
+    if source_meta.lockup != destination_meta.lockup
+        && (source_meta.lockup.is_in_force(&cluster.clock, None)
+            || destination_meta.lockup.is_in_force(&cluster.clock, None))
+    {
+        bail!("Source and destination stake accounts have different lockups in force");
+    }
+
+    match operation {
+        MoveOperation::Stake => {
+            let MovableStake::FullyActive(source_delegation) = source_kind else {
+                bail!("Cannot move stake: source stake account is not fully active");
+            };
+
+            let destination_stake = match destination_kind {
+                MovableStake::FullyActive(delegation) => {
+                    // CHECK: Same vote account
+                    if delegation.voter_pubkey != source_delegation.voter_pubkey {
+                        bail!(
+                            "Cannot move stake: source is delegated to {} but destination to {}",
+                            source_delegation.voter_pubkey,
+                            delegation.voter_pubkey
+                        );
+                    }
+                    delegation.stake
+                }
+                MovableStake::Inactive => 0,
+                MovableStake::ActivationEpoch(_) => {
+                    bail!("Cannot move stake: destination stake account is activating")
+                }
+            };
+
+            if lamports > source_delegation.stake {
+                bail!(
+                    "Cannot move {} SOL: source only has {} SOL of active stake",
+                    lamports_to_sol(lamports),
+                    lamports_to_sol(source_delegation.stake)
+                );
+            }
+            let remaining = source_delegation.stake - lamports;
+            if remaining != 0 && remaining < minimum_delegation {
+                bail!(
+                    "Source would keep {} SOL of stake, below the minimum delegation of {} SOL. \
+                     Move all of it or leave at least the minimum",
+                    lamports_to_sol(remaining),
+                    lamports_to_sol(minimum_delegation)
+                );
+            }
+            if destination_stake + lamports < minimum_delegation {
+                bail!(
+                    "Destination would hold {} SOL of stake, below the minimum delegation of {} \
+                     SOL",
+                    lamports_to_sol(destination_stake + lamports),
+                    lamports_to_sol(minimum_delegation)
+                );
+            }
+        }
+        MoveOperation::Lamports => {
+            let delegated = match source_kind {
+                MovableStake::FullyActive(delegation) => delegation.stake,
+                MovableStake::Inactive => 0,
+                MovableStake::ActivationEpoch(_) => {
+                    bail!("Cannot move lamports: source stake account is activating")
+                }
+            };
+
+            let free = source
+                .1
+                .saturating_sub(delegated)
+                .saturating_sub(source_meta.rent_exempt_reserve);
+            if lamports > free {
+                bail!(
+                    "Cannot move {} SOL: only {} SOL of the source is neither delegated nor \
+                     reserved for rent",
+                    lamports_to_sol(lamports),
+                    lamports_to_sol(free)
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+pub struct MoveStakeOutput {
+    pub signature: String,
+    pub source_stake_account: String,
+    pub destination_stake_account: String,
+    pub stake_authority: String,
+    pub lamports: u64,
+}
+
+impl CommandOutput for MoveStakeOutput {
+    fn print_table(&self) {
+        println!(
+            "{}\n{}\n{}\n{}\n{}",
+            style(format!(
+                "Moved {} SOL successfully!",
+                lamports_to_sol(self.lamports)
+            ))
+            .yellow()
+            .bold(),
+            style(format!(
+                "Source Stake Account: {}",
+                self.source_stake_account
+            ))
+            .yellow(),
+            style(format!(
+                "Destination Stake Account: {}",
+                self.destination_stake_account
+            ))
+            .yellow(),
+            style(format!("Stake Authority: {}", self.stake_authority)).yellow(),
+            style(format!("Signature: {}", self.signature)).green()
+        );
+    }
+}
+
+async fn process_move_stake(
+    ctx: &ScillaContext,
+    operation: MoveOperation,
+    source_stake_account_pubkey: &Pubkey,
+    destination_stake_account_pubkey: &Pubkey,
+    stake_authority_keypair_path: &PathBuf,
+    amount_sol: f64,
+    tx_args: &TxArgs,
+) -> anyhow::Result<TxOutcome<MoveStakeOutput>> {
+    let stake_authority_keypair = read_keypair_from_path(stake_authority_keypair_path)?;
+    let stake_authority_pubkey = stake_authority_keypair.pubkey();
+    let lamports = sol_to_lamports(amount_sol);
+
+    // checks for unique pubkeys
+    if source_stake_account_pubkey == destination_stake_account_pubkey {
+        bail!(
+            "Source Stake Account {} & Destination Stake Account {} must not be the same",
+            source_stake_account_pubkey,
+            destination_stake_account_pubkey
+        );
+    }
+    if lamports == 0 {
+        bail!("Amount to move must be greater than zero");
+    }
+
+    // The account state can only be checked online, so skip it when signing offline
+    if !tx_args.sign_only {
+        let pubkeys = [
+            *source_stake_account_pubkey,
+            *destination_stake_account_pubkey,
+        ];
+        let ((accounts, cluster), minimum_delegation) =
+            tokio::try_join!(fetch_with_stake_cluster_state(ctx, &pubkeys), async {
+                Ok(ctx.rpc().get_stake_minimum_delegation().await?)
+            },)?;
+
+        let [Some(source_account), Some(destination_account)] = accounts.as_slice() else {
+            bail!("Failed to get stake account");
+        };
+        if source_account.owner != stake_program_id()
+            || destination_account.owner != stake_program_id()
+        {
+            bail!("Source and destination must both be stake accounts");
+        }
+
+        let source_stake_state: StakeStateV2 =
+            bincode_deserialize(&source_account.data, "source stake account data")?;
+        let destination_stake_state: StakeStateV2 =
+            bincode_deserialize(&destination_account.data, "destination stake account data")?;
+
+        check_move_stake(
+            operation,
+            (&source_stake_state, source_account.lamports),
+            (&destination_stake_state, destination_account.lamports),
+            &stake_authority_pubkey,
+            lamports,
+            minimum_delegation,
+            &cluster,
+        )?;
+    }
+
+    let ix = match operation {
+        MoveOperation::Stake => instruction::move_stake(
+            source_stake_account_pubkey,
+            destination_stake_account_pubkey,
+            &stake_authority_pubkey,
+            lamports,
+        ),
+        MoveOperation::Lamports => instruction::move_lamports(
+            source_stake_account_pubkey,
+            destination_stake_account_pubkey,
+            &stake_authority_pubkey,
+            lamports,
+        ),
+    };
+
+    let signature = match build_and_send_tx(
+        ctx,
+        &[ix],
+        &[ctx.keypair(), &stake_authority_keypair],
+        tx_args,
+    )
+    .await?
+    {
+        TxOutcome::Sent(signature) => signature,
+        TxOutcome::SignedOnly(signed) => return Ok(TxOutcome::SignedOnly(signed)),
+    };
+
+    Ok(TxOutcome::Sent(MoveStakeOutput {
+        signature: signature.to_string(),
+        source_stake_account: source_stake_account_pubkey.to_string(),
+        destination_stake_account: destination_stake_account_pubkey.to_string(),
+        stake_authority: stake_authority_pubkey.to_string(),
+        lamports,
+    }))
+}
+
+#[derive(Debug, Serialize)]
+pub struct SplitStakeOutput {
+    pub signature: String,
+    pub stake_account: String,
+    pub split_stake_account: String,
+    pub stake_authority: String,
+    pub lamports: u64,
+}
+
+impl CommandOutput for SplitStakeOutput {
+    fn print_table(&self) {
+        println!(
+            "{}\n{}\n{}\n{}\n{}",
+            style("Split Stake successfully!").yellow().bold(),
+            style(format!("Stake Account: {}", self.stake_account)).yellow(),
+            style(format!("Split Stake Account: {}", self.split_stake_account)).yellow(),
+            style(format!("Stake Authority: {}", self.stake_authority)).yellow(),
+            style(format!("Signature: {}", self.signature)).green()
+        );
+    }
+}
+
+/// Account a split moves stake into
+pub enum SplitStakeTarget {
+    Address(Pubkey),
+    /// Address derived from a base keypair and a seed
+    Seed {
+        base: PathBuf,
+        seed: String,
+    },
+}
+
+async fn process_split_stake(
+    ctx: &ScillaContext,
+    stake_account_pubkey: &Pubkey,
+    split_stake_account: SplitStakeTarget,
+    stake_authority_keypair_path: &PathBuf,
+    amount_to_split: f64,
+    tx_args: &TxArgs,
+) -> anyhow::Result<TxOutcome<SplitStakeOutput>> {
+    let stake_authority_keypair = read_keypair_from_path(stake_authority_keypair_path)?;
+    let stake_authority_pubkey = stake_authority_keypair.pubkey();
+    let lamports: u64 = sol_to_lamports(amount_to_split);
+
+    let (split_stake_account_pubkey, seed) = match split_stake_account {
+        SplitStakeTarget::Address(pubkey) => (pubkey, None),
+        SplitStakeTarget::Seed { base, seed } => {
+            let base_keypair = read_keypair_from_path(base)?;
+            let pubkey = derive_stake_address(&base_keypair.pubkey(), &seed)?;
+            (pubkey, Some((base_keypair, seed)))
+        }
+    };
+    let split_stake_account_pubkey = &split_stake_account_pubkey;
+
+    if stake_account_pubkey == split_stake_account_pubkey {
+        bail!(
+            "Existing Stake Account {} and New Split Stake Account {} must not be the same",
+            stake_account_pubkey,
+            split_stake_account_pubkey
+        );
+    }
+
+    // The minimum delegation can only be fetched online, so skip it when signing
+    // offline
+    if !tx_args.sign_only {
+        let stake_minimum_delegation = ctx.rpc().get_stake_minimum_delegation().await?;
+
+        if lamports < stake_minimum_delegation {
+            bail!(
+                "Need at least {} lamports for minimum stake delegation, but you provided {}",
+                stake_minimum_delegation,
+                lamports
+            );
+        }
+    }
+
+    let mut signers: Vec<&dyn Signer> = vec![ctx.keypair(), &stake_authority_keypair];
+    let ix = match &seed {
+        Some((base_keypair, seed)) => {
+            signers.push(base_keypair);
+            instruction::split_with_seed(
+                stake_account_pubkey,
+                &stake_authority_pubkey,
+                lamports,
+                split_stake_account_pubkey,
+                &base_keypair.pubkey(),
+                seed,
+            )
+        }
+        None => instruction::split(
+            stake_account_pubkey,
+            &stake_authority_pubkey,
+            lamports,
+            split_stake_account_pubkey,
+        ),
+    };
+
+    let signature = match build_and_send_tx(ctx, &ix, &signers, tx_args).await? {
+        TxOutcome::Sent(signature) => signature,
+        TxOutcome::SignedOnly(signed) => return Ok(TxOutcome::SignedOnly(signed)),
+    };
+
+    Ok(TxOutcome::Sent(SplitStakeOutput {
+        signature: signature.to_string(),
+        stake_account: stake_account_pubkey.to_string(),
+        split_stake_account: split_stake_account_pubkey.to_string(),
+        stake_authority: stake_authority_pubkey.to_string(),
+        lamports,
+    }))
+}
+
+/// Stake of each part of a split, the first staying in the account. Amounts
+/// are rounded down, with the remainder staying in the account.
+fn split_parts(total: u64, shares: &SplitShares) -> anyhow::Result<Vec<u64>> {
+    let mut parts = match shares {
+        SplitShares::Equal(count) => {
+            if *count < 2 {
+                bail!("Need at least 2 parts, got {count}");
+            }
+            vec![total / u64::from(*count); *count as usize]
+        }
+        SplitShares::Percentages(percentages) => {
+            if percentages.len() < 2 {
+                bail!("Need at least 2 percentages, got {}", percentages.len());
+            }
+            if let Some(percentage) = percentages.iter().find(|percentage| **percentage <= 0.0) {
+                bail!("Percentages must be positive, got {percentage}");
+            }
+            let sum: f64 = percentages.iter().sum();
+            if (sum - 100.0).abs() > 1e-6 {
+                bail!("Percentages must add up to 100, got {sum}");
+            }
+            percentages
+                .iter()
+                .map(|percentage| (total as f64 * percentage / 100.0) as u64)
+                .collect()
+        }
+    };
+
+    let split_off: u64 = parts[1..].iter().sum();
+    parts[0] = total.saturating_sub(split_off);
+    Ok(parts)
+}
+
+#[derive(Debug, Serialize)]
+pub struct SplitPartOutput {
+    pub stake_account: String,
+    pub seed: Option<String>,
+    pub lamports: u64,
+    pub status: BulkStatus,
+    pub signature: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SplitPartsOutput {
+    pub stake_account: String,
+    /// Stake staying in the split account
+    pub remaining_lamports: u64,
+    /// Rent-exempt reserve funded into each new account by the fee payer
+    pub rent_exempt_reserve: u64,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub parts: Vec<SplitPartOutput>,
+}
+
+impl SplitPartsOutput {
+    fn table(&self) -> Table {
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL).set_header(vec![
+            Cell::new("Stake Account").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Seed").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Stake (SOL)").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Status").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Signature / Error").add_attribute(comfy_table::Attribute::Bold),
+        ]);
+        table.add_row(vec![
+            Cell::new(&self.stake_account),
+            Cell::new("-"),
+            Cell::new(lamports_to_sol(self.remaining_lamports)),
+            Cell::new("source"),
+            Cell::new("-"),
+        ]);
+        for part in &self.parts {
+            let status = match part.status {
+                BulkStatus::Succeeded => style(part.status).green(),
+                BulkStatus::Pending | BulkStatus::Skipped => style(part.status).cyan(),
+                BulkStatus::Failed => style(part.status).red(),
+            };
+            table.add_row(vec![
+                Cell::new(&part.stake_account),
+                Cell::new(part.seed.as_deref().unwrap_or("-")),
+                Cell::new(lamports_to_sol(part.lamports)),
+                Cell::new(status.to_string()),
+                Cell::new(
+                    part.error
+                        .as_deref()
+                        .or(part.signature.as_deref())
+                        .unwrap_or("-"),
+                ),
+            ]);
+        }
+        table
+    }
+}
+
+impl CommandOutput for SplitPartsOutput {
+    fn print_table(&self) {
+        println!("\n{}", style("STAKE SPLIT INTO PARTS").green().bold());
+        println!("{}", self.table());
+        println!(
+            "{}",
+            style(format!(
+                "Split off {} of {} parts",
+                self.succeeded,
+                self.parts.len()
+            ))
+            .cyan()
+        );
+    }
+}
+
+/// New accounts of a split into parts, packed into transactions.
+pub struct SplitPartsPlan {
+    output: SplitPartsOutput,
+    batches: Vec<Batch<usize>>,
+    signers: Vec<Keypair>,
+    tx_args: TxArgs,
+}
+
+impl SplitPartsPlan {
+    /// Prints the plan ahead of the command result.
+    fn print(&self, ctx: &ScillaContext) {
+        let rendered = format!(
+            "\n{}\n{}\n{}",
+            style("STAKE SPLIT PLAN").green().bold(),
+            self.output.table(),
+            style(format!(
+                "{} new accounts in {} transactions, each funded with {} SOL rent-exempt reserve",
+                self.output.parts.len(),
+                self.batches.len(),
+                lamports_to_sol(self.output.rent_exempt_reserve)
+            ))
+            .cyan()
+        );
+        print_status(ctx, rendered);
+    }
+}
+
+/// Works out the parts of the split, checks each against the minimum
+/// delegation and prepares the transactions that fund the new accounts with
+/// the rent-exempt reserve and split the stake into them.
+async fn prepare_split_parts(
+    ctx: &ScillaContext,
+    stake_pubkey: &Pubkey,
+    shares: &SplitShares,
+    destinations: SplitDestinations,
+    stake_authority_keypair_path: &PathBuf,
+    compute_budget: &ComputeBudgetArgs,
+) -> anyhow::Result<SplitPartsPlan> {
+    let stake_authority_keypair = read_keypair_from_path(stake_authority_keypair_path)?;
+    let stake_authority = stake_authority_keypair.pubkey();
+
+    let (account, rent_exempt_reserve, minimum_delegation) = tokio::try_join!(
+        async { Ok(ctx.rpc().get_account(stake_pubkey).await?) },
+        async {
+            Ok(ctx
+                .rpc()
+                .get_minimum_balance_for_rent_exemption(StakeStateV2::size_of())
+                .await?)
+        },
+        async { anyhow::Ok(ctx.rpc().get_stake_minimum_delegation().await?) },
+    )?;
+    if account.owner != stake_program_id() {
+        bail!("Account is not owned by the stake program");
+    }
+    let stake_state: StakeStateV2 = bincode_deserialize(&account.data, "stake account data")?;
+    let (meta, total) = match &stake_state {
+        StakeStateV2::Stake(meta, stake, _) => (meta, stake.delegation.stake),
+        StakeStateV2::Initialized(meta) => (
+            meta,
+            account.lamports.saturating_sub(meta.rent_exempt_reserve),
+        ),
+        _ => bail!("Stake account is not in a valid state for splitting"),
+    };
+    if meta.authorized.staker != stake_authority {
+        bail!(
+            "{stake_authority} is not the stake authority ({})",
+            meta.authorized.staker
+        );
+    }
+
+    let parts = split_parts(total, shares)?;
+    if let Some(part) = parts.iter().find(|part| **part < minimum_delegation) {
+        bail!(
+            "Every part needs at least the minimum delegation of {} SOL, but one would only get \
+             {} SOL",
+            lamports_to_sol(minimum_delegation),
+            lamports_to_sol(*part)
+        );
+    }
+
+    let mut signers = vec![stake_authority_keypair];
+    let mut seed_base = None;
+    let new_accounts: Vec<(Pubkey, Option<String>)> = match destinations {
+        SplitDestinations::Generate => (1..parts.len())
+            .map(|_| {
+                let keypair = Keypair::new();
+                let pubkey = keypair.pubkey();
+                signers.push(keypair);
+                (pubkey, None)
+            })
+            .collect(),
+        SplitDestinations::Seeds {
+            base,
+            prefix,
+            start,
+        } => {
+            let base_keypair = read_keypair_from_path(base)?;
+            let base_pubkey = base_keypair.pubkey();
+            seed_base = Some(base_pubkey);
+            signers.push(base_keypair);
+            let derived = (start..)
+                .take(parts.len() - 1)
+                .map(|index| {
+                    let seed = format!("{prefix}{index}");
+                    derive_stake_address(&base_pubkey, &seed).map(|address| (address, Some(seed)))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            let addresses: Vec<Pubkey> = derived.iter().map(|(address, _)| *address).collect();
+            let chunks = addresses
+                .chunks(MAX_MULTIPLE_ACCOUNTS)
+                .map(|chunk| ctx.rpc().get_multiple_accounts(chunk));
+            let existing = futures::future::try_join_all(chunks).await?;
+            if let Some(((address, seed), _)) = derived
+                .iter()
+                .zip(existing.into_iter().flatten())
+                .find(|(_, account)| account.is_some())
+            {
+                bail!(
+                    "{address} (seed {}) is already in use. Pick another --seed-start, see `stake \
+                     derived-addresses`",
+                    seed.as_deref().unwrap_or_default()
+                );
+            }
+            derived
+        }
+    };
+
+    let payer = *ctx.pubkey();
+    let instructions: Vec<(usize, Vec<Instruction>)> = new_accounts
+        .iter()
+        .zip(&parts[1..])
+        .enumerate()
+        .map(|(i, ((address, seed), lamports))| {
+            let mut ixs = vec![solana_system_interface::instruction::transfer(
+                &payer,
+                address,
+                rent_exempt_reserve,
+            )];
+            ixs.extend(match (seed, &seed_base) {
+                (Some(seed), Some(base)) => instruction::split_with_seed(
+                    stake_pubkey,
+                    &stake_authority,
+                    *lamports,
+                    address,
+                    base,
+                    seed,
+                ),
+                _ => instruction::split(stake_pubkey, &stake_authority, *lamports, address),
+            });
+            (i, ixs)
+        })
+        .collect();
+
+    check_minimum_balance(ctx, &payer, rent_exempt_reserve * new_accounts.len() as u64).await?;
+
+    let (tx_args, prefix) = pin_batch_tx_args(ctx, compute_budget, &instructions).await?;
+    let batches = pack_instructions(instructions, &payer, &prefix)?;
+
+    Ok(SplitPartsPlan {
+        output: SplitPartsOutput {
+            stake_account: stake_pubkey.to_string(),
+            remaining_lamports: parts[0],
+            rent_exempt_reserve,
+            succeeded: 0,
+            failed: 0,
+            parts: new_accounts
+                .into_iter()
+                .zip(&parts[1..])
+                .map(|((address, seed), lamports)| SplitPartOutput {
+                    stake_account: address.to_string(),
+                    seed,
+                    lamports: *lamports,
+                    status: BulkStatus::Pending,
+                    signature: None,
+                    error: None,
+                })
+                .collect(),
+        },
+        batches,
+        signers,
+        tx_args,
+    })
+}
+
+/// Sends the transactions of the plan one after another, stopping at the
+/// first failure as the later parts would likely fail the same way.
+async fn process_split_parts(
+    ctx: &ScillaContext,
+    plan: SplitPartsPlan,
+) -> anyhow::Result<SplitPartsOutput> {
+    let SplitPartsPlan {
+        mut output,
+        batches,
+        signers,
+        tx_args,
+    } = plan;
+    let mut all_signers: Vec<&dyn Signer> = vec![ctx.keypair()];
+    all_signers.extend(signers.iter().map(|keypair| keypair as &dyn Signer));
+
+    for batch in batches {
+        let result = send_batch(ctx, &batch.instructions, &all_signers, &tx_args).await;
+        for i in &batch.items {
+            let part = &mut output.parts[*i];
+            part.signature = result.signature.clone();
+            part.error = result.error.clone();
+            part.status = match result.error {
+                Some(_) => BulkStatus::Failed,
+                None => BulkStatus::Succeeded,
+            };
+        }
+        if result.error.is_some() {
+            break;
+        }
+    }
+
+    let succeeded = |part: &&SplitPartOutput| part.status == BulkStatus::Succeeded;
+    output.succeeded = output.parts.iter().filter(succeeded).count();
+    output.failed = output.parts.len() - output.succeeded;
+    // Parts that weren't split off stay in the account
+    output.remaining_lamports += output
+        .parts
+        .iter()
+        .filter(|part| part.status != BulkStatus::Succeeded)
+        .map(|part| part.lamports)
+        .sum::<u64>();
+
+    Ok(output)
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuthorizeStakeOutput {
+    pub signature: String,
+    pub stake_account: String,
+    pub authority_type: String,
+    pub new_authority: String,
+}
+
+impl CommandOutput for AuthorizeStakeOutput {
+    fn print_table(&self) {
+        println!(
+            "{}\n{}\n{}\n{}",
+            style(format!("{} changed successfully!", self.authority_type))
+                .yellow()
+                .bold(),
+            style(format!("Stake Account: {}", self.stake_account)).yellow(),
+            style(format!(
+                "New {}: {}",
+                self.authority_type, self.new_authority
+            ))
+            .yellow(),
+            style(format!("Signature: {}", self.signature)).green()
+        );
+    }
+}
+
+/// Checks that `signer` may hand `authority_type` over to a new key, following
+/// the stake program's rules: either authority may replace the stake
+/// authority, only the withdraw authority may replace itself, and while a
+/// lockup is in force only together with the lockup custodian.
+fn check_stake_authorize(
+    meta: &Meta,
+    clock: &Clock,
+    authority_type: StakeAuthorityType,
+    signer: &Pubkey,
+    custodian: Option<&Pubkey>,
+) -> anyhow::Result<()> {
+    let Authorized { staker, withdrawer } = meta.authorized;
+
+    match authority_type {
+        StakeAuthorityType::Staker => {
+            if *signer != staker && *signer != withdrawer {
+                bail!(
+                    "{signer} is neither the stake authority ({staker}) nor the withdraw \
+                     authority ({withdrawer})"
+                );
+            }
+        }
+        StakeAuthorityType::Withdrawer => {
+            if *signer != withdrawer {
+                bail!("{signer} is not the withdraw authority ({withdrawer})");
+            }
+
+            let lockup = meta.lockup;
+            if lockup.is_in_force(clock, None) {
+                match custodian {
+                    None => bail!(
+                        "A lockup is in force until epoch {} / unix timestamp {}, so the lockup \
+                         custodian ({}) must co-sign to change the withdraw authority",
+                        lockup.epoch,
+                        lockup.unix_timestamp,
+                        lockup.custodian
+                    ),
+                    Some(custodian) if *custodian != lockup.custodian => bail!(
+                        "{custodian} is not the lockup custodian ({})",
+                        lockup.custodian
+                    ),
+                    Some(_) => {}
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn process_authorize_stake(
+    ctx: &ScillaContext,
+    stake_pubkey: &Pubkey,
+    authority_type: StakeAuthorityType,
+    authority_keypair_path: &PathBuf,
+    authority_seed: Option<(String, Pubkey)>,
+    new_authority: NewStakeAuthority,
+    custodian_keypair_path: Option<&PathBuf>,
+    tx_args: &TxArgs,
+) -> anyhow::Result<TxOutcome<AuthorizeStakeOutput>> {
+    let authority_keypair = read_keypair_from_path(authority_keypair_path)?;
+    let base_pubkey = authority_keypair.pubkey();
+    let authority_pubkey = match &authority_seed {
+        Some((seed, owner)) => Pubkey::create_with_seed(&base_pubkey, seed, owner)?,
+        None => base_pubkey,
+    };
+
+    let (new_authority_pubkey, new_authority_keypair) = match new_authority {
+        NewStakeAuthority::Address(pubkey) => (pubkey, None),
+        NewStakeAuthority::Signer(path) => {
+            let keypair = read_keypair_from_path(path)?;
+            (keypair.pubkey(), Some(keypair))
+        }
+    };
+
+    let mut custodian_keypair = custodian_keypair_path
+        .map(read_keypair_from_path)
+        .transpose()?;
+
+    // The account state can only be checked online, so skip it when signing offline
+    if !tx_args.sign_only {
+        let (accounts, cluster) = fetch_with_stake_cluster_state(ctx, &[*stake_pubkey]).await?;
+        let meta = stake_account_meta(stake_pubkey, accounts.first())?;
+
+        // Ask for the custodian only once it turns out to be needed
+        if authority_type == StakeAuthorityType::Withdrawer
+            && custodian_keypair.is_none()
+            && meta.lockup.is_in_force(&cluster.clock, None)
+        {
+            let path = suspend_spinner(|| {
+                prompt_keypair_path(
+                    &format!(
+                        "A lockup is in force. Enter Lockup Custodian ({}) Keypair Path: ",
+                        meta.lockup.custodian
+                    ),
+                    ctx,
+                )
+            });
+            custodian_keypair = Some(read_keypair_from_path(path)?);
+        }
+
+        check_stake_authorize(
+            &meta,
+            &cluster.clock,
+            authority_type,
+            &authority_pubkey,
+            custodian_keypair
+                .as_ref()
+                .map(|keypair| keypair.pubkey())
+                .as_ref(),
+        )?;
+
+        if new_authority_pubkey == authority_pubkey {
+            bail!("{new_authority_pubkey} already is the {authority_type}");
+        }
+    }
+
+    let custodian_pubkey = custodian_keypair.as_ref().map(|keypair| keypair.pubkey());
+    let stake_authorize = StakeAuthorize::from(authority_type);
+    let ix = match (authority_seed, new_authority_keypair.is_some()) {
+        (None, false) => instruction::authorize(
+            stake_pubkey,
+            &authority_pubkey,
+            &new_authority_pubkey,
+            stake_authorize,
+            custodian_pubkey.as_ref(),
+        ),
+        (None, true) => instruction::authorize_checked(
+            stake_pubkey,
+            &authority_pubkey,
+            &new_authority_pubkey,
+            stake_authorize,
+            custodian_pubkey.as_ref(),
+        ),
+        (Some((seed, owner)), false) => instruction::authorize_with_seed(
+            stake_pubkey,
+            &base_pubkey,
+            seed,
+            &owner,
+            &new_authority_pubkey,
+            stake_authorize,
+            custodian_pubkey.as_ref(),
+        ),
+        (Some((seed, owner)), true) => instruction::authorize_checked_with_seed(
+            stake_pubkey,
+            &base_pubkey,
+            seed,
+            &owner,
+            &new_authority_pubkey,
+            stake_authorize,
+            custodian_pubkey.as_ref(),
+        ),
+    };
+
+    let mut signers: Vec<&dyn Signer> = vec![ctx.keypair(), &authority_keypair];
+    if let Some(keypair) = &new_authority_keypair {
+        signers.push(keypair);
+    }
+    if let Some(keypair) = &custodian_keypair {
+        signers.push(keypair);
+    }
+
+    let signature = match build_and_send_tx(ctx, &[ix], &signers, tx_args).await? {
+        TxOutcome::Sent(signature) => signature,
+        TxOutcome::SignedOnly(signed) => return Ok(TxOutcome::SignedOnly(signed)),
+    };
+
+    Ok(TxOutcome::Sent(AuthorizeStakeOutput {
+        signature: signature.to_string(),
+        stake_account: stake_pubkey.to_string(),
+        authority_type: authority_type.to_string(),
+        new_authority: new_authority_pubkey.to_string(),
+    }))
+}
+
+#[derive(Debug, Serialize)]
+pub struct LockupStatusOutput {
+    pub stake_account: String,
+    pub epoch: u64,
+    pub unix_timestamp: i64,
+    pub custodian: String,
+    pub current_epoch: u64,
+    pub current_unix_timestamp: i64,
+    pub in_force: bool,
+    /// Epochs until the lockup epoch is reached
+    pub epochs_remaining: u64,
+    /// Seconds until the lockup date is reached
+    pub seconds_remaining: u64,
+    /// Whether the custodian has to co-sign withdrawals and withdraw
+    /// authority changes
+    pub custodian_required: bool,
+    /// Signer that may update the lockup
+    pub lockup_authority: String,
+}
+
+impl LockupStatusOutput {
+    fn new(stake_pubkey: &Pubkey, meta: &Meta, clock: &Clock) -> Self {
+        let lockup = meta.lockup;
+        let in_force = lockup.is_in_force(clock, None);
+        Self {
+            stake_account: stake_pubkey.to_string(),
+            epoch: lockup.epoch,
+            unix_timestamp: lockup.unix_timestamp,
+            custodian: lockup.custodian.to_string(),
+            current_epoch: clock.epoch,
+            current_unix_timestamp: clock.unix_timestamp,
+            in_force,
+            epochs_remaining: lockup.epoch.saturating_sub(clock.epoch),
+            seconds_remaining: lockup
+                .unix_timestamp
+                .saturating_sub(clock.unix_timestamp)
+                .max(0) as u64,
+            custodian_required: in_force,
+            lockup_authority: if in_force {
+                lockup.custodian
+            } else {
+                meta.authorized.withdrawer
+            }
+            .to_string(),
+        }
+    }
+}
+
+impl CommandOutput for LockupStatusOutput {
+    fn print_table(&self) {
+        let date = |timestamp: i64| {
+            chrono::DateTime::<chrono::Utc>::from_timestamp_secs(timestamp)
+                .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+                .unwrap_or_else(|| timestamp.to_string())
+        };
+        let yes_no = |value: bool| if value { "Yes" } else { "No" };
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_header(vec![
+                Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
+            ])
+            .add_row(vec![
+                Cell::new("Stake Account Pubkey"),
+                Cell::new(&self.stake_account),
+            ])
+            .add_row(vec![
+                Cell::new("In Force"),
+                Cell::new(if self.in_force {
+                    style("Yes").yellow().to_string()
+                } else {
+                    style("No").green().to_string()
+                }),
+            ])
+            .add_row(vec![
+                Cell::new("Lockup Epoch"),
+                Cell::new(format!(
+                    "{} (current {}, {} remaining)",
+                    self.epoch, self.current_epoch, self.epochs_remaining
+                )),
+            ])
+            .add_row(vec![
+                Cell::new("Lockup Date"),
+                Cell::new(format!(
+                    "{} ({} remaining)",
+                    date(self.unix_timestamp),
+                    format_duration(self.seconds_remaining)
+                )),
+            ])
+            .add_row(vec![Cell::new("Custodian"), Cell::new(&self.custodian)])
+            .add_row(vec![
+                Cell::new("Custodian Required to Withdraw"),
+                Cell::new(yes_no(self.custodian_required)),
+            ])
+            .add_row(vec![
+                Cell::new("Lockup Can Be Updated By"),
+                Cell::new(&self.lockup_authority),
+            ]);
+
+        println!("\n{}", style("STAKE LOCKUP").green().bold());
+        println!("{table}");
+    }
+}
+
+async fn fetch_lockup_status(
+    ctx: &ScillaContext,
+    stake_pubkey: &Pubkey,
+) -> anyhow::Result<LockupStatusOutput> {
+    let (accounts, cluster) = fetch_with_stake_cluster_state(ctx, &[*stake_pubkey]).await?;
+    let meta = stake_account_meta(stake_pubkey, accounts.first())?;
+
+    Ok(LockupStatusOutput::new(stake_pubkey, &meta, &cluster.clock))
+}
+
+/// Metadata of a fetched stake account, failing if the account is missing,
+/// not a stake account or not initialized
+fn stake_account_meta(
+    stake_pubkey: &Pubkey,
+    account: Option<&Option<Account>>,
+) -> anyhow::Result<Meta> {
+    let Some(Some(account)) = account else {
+        bail!("Failed to fetch stake account");
+    };
+    if account.owner != stake_program_id() {
+        bail!("Account {stake_pubkey} is not a stake account");
+    }
+
+    let stake_state: StakeStateV2 = bincode_deserialize(&account.data, "stake account data")?;
+    stake_state
+        .meta()
+        .ok_or_else(|| anyhow!("Stake account {stake_pubkey} is not initialized"))
+}
+
+#[derive(Debug, Serialize)]
+pub struct SetLockupOutput {
+    pub signature: String,
+    pub stake_account: String,
+    pub epoch: Option<u64>,
+    pub unix_timestamp: Option<i64>,
+    pub custodian: Option<String>,
+}
+
+impl CommandOutput for SetLockupOutput {
+    fn print_table(&self) {
+        println!(
+            "{}\n{}",
+            style("Stake lockup updated successfully!").yellow().bold(),
+            style(format!("Stake Account: {}", self.stake_account)).yellow(),
+        );
+        if let Some(epoch) = self.epoch {
+            println!("{}", style(format!("Lockup Epoch: {epoch}")).yellow());
+        }
+        if let Some(unix_timestamp) = self.unix_timestamp {
+            println!(
+                "{}",
+                style(format!("Lockup Unix Timestamp: {unix_timestamp}")).yellow()
+            );
+        }
+        if let Some(custodian) = &self.custodian {
+            println!(
+                "{}",
+                style(format!("Lockup Custodian: {custodian}")).yellow()
+            );
+        }
+        println!(
+            "{}",
+            style(format!("Signature: {}", self.signature)).green()
+        );
+    }
+}
+
+/// Checks that `signer` may update the lockup: the custodian while the lockup
+/// is in force, the withdraw authority otherwise.
+fn check_set_lockup(meta: &Meta, clock: &Clock, signer: &Pubkey) -> anyhow::Result<()> {
+    if meta.lockup.is_in_force(clock, None) {
+        if *signer != meta.lockup.custodian {
+            bail!(
+                "The lockup is in force, so only its custodian ({}) can update it",
+                meta.lockup.custodian
+            );
+        }
+    } else if *signer != meta.authorized.withdrawer {
+        bail!(
+            "The lockup is not in force, so only the withdraw authority ({}) can set it",
+            meta.authorized.withdrawer
+        );
+    }
+    Ok(())
+}
+
+async fn process_set_lockup(
+    ctx: &ScillaContext,
+    stake_pubkey: &Pubkey,
+    authority_keypair_path: &PathBuf,
+    mut lockup: LockupArgs,
+    new_custodian_keypair_path: Option<&PathBuf>,
+    tx_args: &TxArgs,
+) -> anyhow::Result<TxOutcome<SetLockupOutput>> {
+    let authority_keypair = read_keypair_from_path(authority_keypair_path)?;
+    let authority_pubkey = authority_keypair.pubkey();
+
+    let new_custodian_keypair = new_custodian_keypair_path
+        .map(read_keypair_from_path)
+        .transpose()?;
+    if let Some(keypair) = &new_custodian_keypair {
+        lockup.custodian = Some(keypair.pubkey());
+    }
+
+    if lockup == LockupArgs::default() {
+        bail!("Nothing to update. Set a new lockup epoch, date or custodian");
+    }
+
+    // The account state can only be checked online, so skip it when signing offline
+    if !tx_args.sign_only {
+        let (accounts, cluster) = fetch_with_stake_cluster_state(ctx, &[*stake_pubkey]).await?;
+        let meta = stake_account_meta(stake_pubkey, accounts.first())?;
+        check_set_lockup(&meta, &cluster.clock, &authority_pubkey)?;
+    }
+
+    let mut signers: Vec<&dyn Signer> = vec![ctx.keypair(), &authority_keypair];
+    let ix = match &new_custodian_keypair {
+        Some(keypair) => {
+            signers.push(keypair);
+            instruction::set_lockup_checked(stake_pubkey, &lockup, &authority_pubkey)
+        }
+        None => instruction::set_lockup(stake_pubkey, &lockup, &authority_pubkey),
+    };
+
+    let signature = match build_and_send_tx(ctx, &[ix], &signers, tx_args).await? {
+        TxOutcome::Sent(signature) => signature,
+        TxOutcome::SignedOnly(signed) => return Ok(TxOutcome::SignedOnly(signed)),
+    };
+
+    Ok(TxOutcome::Sent(SetLockupOutput {
+        signature: signature.to_string(),
+        stake_account: stake_pubkey.to_string(),
+        epoch: lockup.epoch,
+        unix_timestamp: lockup.unix_timestamp,
+        custodian: lockup.custodian.map(|custodian| custodian.to_string()),
+    }))
+}
+
+#[derive(Debug, Serialize)]
+pub struct DerivedAddressOutput {
+    pub seed: String,
+    pub address: String,
+    /// Whether an account exists at the address
+    pub in_use: bool,
+    pub lamports: u64,
+    pub state: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DerivedAddressesOutput {
+    pub base: String,
+    pub addresses: Vec<DerivedAddressOutput>,
+}
+
+impl CommandOutput for DerivedAddressesOutput {
+    fn print_table(&self) {
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL).set_header(vec![
+            Cell::new("Seed").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Address").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("State").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Balance (SOL)").add_attribute(comfy_table::Attribute::Bold),
+        ]);
+
+        for derived in &self.addresses {
+            let state = match (derived.in_use, derived.state) {
+                (false, _) => style("Unused").green().to_string(),
+                (true, Some(state)) => state.to_string(),
+                (true, None) => style("Not a stake account").yellow().to_string(),
+            };
+            table.add_row(vec![
+                Cell::new(&derived.seed),
+                Cell::new(&derived.address),
+                Cell::new(state),
+                Cell::new(if derived.in_use {
+                    lamports_to_sol(derived.lamports).to_string()
+                } else {
+                    "-".into()
+                }),
+            ]);
+        }
+
+        println!(
+            "\n{} {}",
+            style("STAKE ADDRESSES DERIVED FROM").green().bold(),
+            style(&self.base).green().bold()
+        );
+        println!("{table}");
+    }
+}
+
+/// Derives the stake account addresses for the seeds `{seed_prefix}{index}`
+/// and looks up which of them are already in use.
+async fn fetch_derived_stake_addresses(
+    ctx: &ScillaContext,
+    base: &Pubkey,
+    seed_prefix: &str,
+    start: u32,
+    count: u32,
+) -> anyhow::Result<DerivedAddressesOutput> {
+    let derived = (start..start.saturating_add(count))
+        .map(|index| {
+            let seed = format!("{seed_prefix}{index}");
+            derive_stake_address(base, &seed).map(|address| (seed, address))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let addresses: Vec<_> = derived.iter().map(|(_, address)| *address).collect();
+
+    let (accounts, (_, cluster)) = tokio::try_join!(
+        async {
+            let chunks = addresses
+                .chunks(MAX_MULTIPLE_ACCOUNTS)
+                .map(|chunk| ctx.rpc().get_multiple_accounts(chunk));
+            Ok(futures::future::try_join_all(chunks).await?)
+        },
+        fetch_with_stake_cluster_state(ctx, &[]),
+    )?;
+
+    let addresses = derived
+        .into_iter()
+        .zip(accounts.into_iter().flatten())
+        .map(|((seed, address), account)| {
+            let state = account
+                .as_ref()
+                .filter(|account| account.owner == stake_program_id())
+                .and_then(|account| {
+                    let stake_state: StakeStateV2 =
+                        bincode_deserialize(&account.data, "stake account data").ok()?;
+                    Some(
+                        StakeAccountOutput::new(&address, account, &stake_state, &cluster).status(),
+                    )
+                });
+            DerivedAddressOutput {
+                seed,
+                address: address.to_string(),
+                in_use: account.is_some(),
+                lamports: account
+                    .as_ref()
+                    .map(|account| account.lamports)
+                    .unwrap_or_default(),
+                state,
+            }
+        })
+        .collect();
+
+    Ok(DerivedAddressesOutput {
+        base: base.to_string(),
+        addresses,
+    })
+}
+
+/// Stake accounts to look up the rewards of
+pub enum RewardAccounts {
+    Stakes(Vec<Pubkey>),
+    /// Every stake account with this stake or withdraw authority
+    Authority(Pubkey),
+}
+
+impl RewardAccounts {
+    async fn fetch(self, ctx: &ScillaContext) -> anyhow::Result<Vec<Pubkey>> {
+        match self {
+            RewardAccounts::Stakes(stakes) => Ok(stakes),
+            RewardAccounts::Authority(authority) => {
+                let stakes: Vec<_> =
+                    fetch_stake_accounts_of_authority(ctx, &authority, AuthorityRole::Any)
+                        .await?
+                        .into_keys()
+                        .collect();
+                if stakes.is_empty() {
+                    bail!("No stake accounts found for authority {authority}");
+                }
+                Ok(stakes)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct StakeRewardsOutput {
+    pub start_epoch: Epoch,
+    pub end_epoch: Epoch,
+    pub stake_accounts: usize,
+    pub total_lamports: u64,
+    pub rewards: Vec<StakeReward>,
+    pub exported_to: Option<String>,
+}
+
+/// Writes `rows` to `path` as CSV with a header row, naming the file in any
+/// error.
+fn write_csv<T: Serialize>(path: &Path, rows: &[T]) -> anyhow::Result<()> {
+    let write = || -> anyhow::Result<()> {
+        let mut writer = csv::Writer::from_path(path)?;
+        for row in rows {
+            writer.serialize(row)?;
+        }
+        writer.flush()?;
+        Ok(())
+    };
+    write().map_err(|err| anyhow!("Failed to write {}: {err}", path.display()))
+}
+
+impl StakeRewardsOutput {
+    /// Writes the rewards to `path` as CSV
+    pub fn export(&mut self, path: &Path) -> anyhow::Result<()> {
+        write_csv(path, &self.rewards)?;
+        self.exported_to = Some(path.display().to_string());
+        Ok(())
+    }
+}
+
+impl CommandOutput for StakeRewardsOutput {
+    fn print_table(&self) {
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL).set_header(vec![
+            Cell::new("Epoch").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Stake Account").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Reward (SOL)").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Post Balance (SOL)").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Commission").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("APR").add_attribute(comfy_table::Attribute::Bold),
+        ]);
+
+        for reward in &self.rewards {
+            table.add_row(vec![
+                Cell::new(reward.epoch),
+                Cell::new(&reward.stake_account),
+                Cell::new(lamports_to_sol(reward.amount)),
+                Cell::new(lamports_to_sol(reward.post_balance)),
+                Cell::new(
+                    reward
+                        .commission
+                        .map(|commission| format!("{commission}%"))
+                        .unwrap_or_else(|| "-".into()),
+                ),
+                Cell::new(
+                    reward
+                        .apr
+                        .map(|apr| format!("{apr:.2}%"))
+                        .unwrap_or_else(|| "-".into()),
+                ),
+            ]);
+        }
+
+        println!(
+            "\n{} {}",
+            style("STAKE REWARDS FOR EPOCHS").green().bold(),
+            style(format!("{}-{}", self.start_epoch, self.end_epoch))
+                .green()
+                .bold()
+        );
+        if self.rewards.is_empty() {
+            println!(
+                "{}",
+                style(format!(
+                    "No rewards were credited to the {} stake account(s) in this range",
+                    self.stake_accounts
+                ))
+                .yellow()
+            );
+            return;
+        }
+        println!("{table}");
+
+        let mut account_totals: BTreeMap<&str, u64> = BTreeMap::new();
+        for reward in &self.rewards {
+            *account_totals.entry(&reward.stake_account).or_default() += reward.amount;
+        }
+        if account_totals.len() > 1 {
+            for (stake_account, total) in &account_totals {
+                println!(
+                    "{} {} SOL",
+                    style(format!("{stake_account}:")).cyan(),
+                    lamports_to_sol(*total)
+                );
+            }
+        }
+        println!(
+            "{} {} SOL",
+            style("Total Rewards:").cyan(),
+            style(lamports_to_sol(self.total_lamports)).green().bold()
+        );
+        if let Some(path) = &self.exported_to {
+            println!("{} {path}", style("Saved to:").cyan());
+        }
+    }
+}
+
+/// Looks up the inflation rewards of `accounts` for each completed epoch in
+/// `start_epoch..=end_epoch`, defaulting to the last `DEFAULT_REWARD_EPOCHS`
+/// completed epochs.
+async fn process_stake_rewards(
+    ctx: &ScillaContext,
+    accounts: RewardAccounts,
+    start_epoch: Option<Epoch>,
+    end_epoch: Option<Epoch>,
+) -> anyhow::Result<StakeRewardsOutput> {
+    let current_epoch = ctx.rpc().get_epoch_info().await?.epoch;
+    let Some(last_completed) = current_epoch.checked_sub(1) else {
+        bail!("No epoch has completed yet");
+    };
+
+    let end_epoch = end_epoch.unwrap_or(last_completed);
+    if end_epoch > last_completed {
+        bail!(
+            "Rewards for epoch {end_epoch} aren't paid out yet; the last completed epoch is \
+             {last_completed}"
+        );
+    }
+    let start_epoch =
+        start_epoch.unwrap_or_else(|| end_epoch.saturating_sub(DEFAULT_REWARD_EPOCHS - 1));
+    if start_epoch > end_epoch {
+        bail!("Start epoch {start_epoch} is after end epoch {end_epoch}");
+    }
+
+    let stake_accounts = accounts.fetch(ctx).await?;
+    let rewards = fetch_stake_rewards(ctx, &stake_accounts, start_epoch..=end_epoch).await?;
+
+    Ok(StakeRewardsOutput {
+        start_epoch,
+        end_epoch,
+        stake_accounts: stake_accounts.len(),
+        total_lamports: rewards.iter().map(|reward| reward.amount).sum(),
+        rewards,
+        exported_to: None,
+    })
+}
+
+/// One reward credit in an income ledger
+#[derive(Debug, Serialize)]
+pub struct RewardCredit {
+    /// When the reward was credited, in RFC 3339
+    pub timestamp: Option<String>,
+    pub unix_timestamp: Option<i64>,
+    pub epoch: Epoch,
+    pub stake_account: String,
+    pub amount_sol: String,
+    pub amount_lamports: u64,
+    pub post_balance_sol: String,
+    pub commission: Option<u8>,
+}
+
+impl From<&StakeReward> for RewardCredit {
+    fn from(reward: &StakeReward) -> Self {
+        RewardCredit {
+            timestamp: reward.credited_at.and_then(|timestamp| {
+                chrono::DateTime::<chrono::Utc>::from_timestamp_secs(timestamp)
+                    .map(|dt| dt.to_rfc3339())
+            }),
+            unix_timestamp: reward.credited_at,
+            epoch: reward.epoch,
+            stake_account: reward.stake_account.clone(),
+            amount_sol: format_sol(reward.amount),
+            amount_lamports: reward.amount,
+            post_balance_sol: format_sol(reward.post_balance),
+            commission: reward.commission,
+        }
+    }
+}
+
+/// Exact decimal SOL amount, so ledgers don't pick up float rounding
+fn format_sol(lamports: u64) -> String {
+    format!(
+        "{}.{:09}",
+        lamports / LAMPORTS_PER_SOL,
+        lamports % LAMPORTS_PER_SOL
+    )
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountIncomeOutput {
+    pub stake_account: String,
+    pub credits: usize,
+    pub total_lamports: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IncomeReportOutput {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub start_epoch: Option<Epoch>,
+    pub end_epoch: Option<Epoch>,
+    pub format: LedgerFormat,
+    pub path: String,
+    pub credits: usize,
+    pub total_lamports: u64,
+    pub accounts: Vec<AccountIncomeOutput>,
+}
+
+impl CommandOutput for IncomeReportOutput {
+    fn print_table(&self) {
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL).set_header(vec![
+            Cell::new("Stake Account").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Credits").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Income (SOL)").add_attribute(comfy_table::Attribute::Bold),
+        ]);
+        for account in &self.accounts {
+            table.add_row(vec![
+                Cell::new(&account.stake_account),
+                Cell::new(account.credits),
+                Cell::new(format_sol(account.total_lamports)),
+            ]);
+        }
+
+        println!("\n{}", style("STAKING INCOME REPORT").green().bold());
+        if let (Some(from), Some(to)) = (&self.from, &self.to) {
+            println!("{} {from} to {to}", style("Period:").cyan());
+        }
+        match (self.start_epoch, self.end_epoch) {
+            (Some(start), Some(end)) => {
+                println!("{} {start}-{end}", style("Reward Epochs:").cyan())
+            }
+            _ => println!(
+                "{}",
+                style("No epoch rewards were credited in this period").yellow()
+            ),
+        }
+        println!("{table}");
+        println!(
+            "{} {} credits, {} SOL",
+            style("Total Income:").cyan(),
+            self.credits,
+            style(format_sol(self.total_lamports)).green().bold()
+        );
+        println!(
+            "{} {} ({})",
+            style("Ledger:").cyan(),
+            self.path,
+            self.format
+        );
+    }
+}
+
+/// Collects the rewards credited to `accounts` in `period` and writes them to
+/// a ledger at `path`.
+async fn process_income_report(
+    ctx: &ScillaContext,
+    accounts: RewardAccounts,
+    period: IncomePeriod,
+    path: &Path,
+    format: LedgerFormat,
+) -> anyhow::Result<IncomeReportOutput> {
+    let (stake_accounts, epochs, dates) = match period {
+        IncomePeriod::Epochs(epochs) => {
+            let (stake_accounts, epoch_info) = tokio::try_join!(accounts.fetch(ctx), async {
+                Ok(ctx.rpc().get_epoch_info().await?)
+            })?;
+            if epochs.start() > epochs.end() {
+                bail!(
+                    "Start epoch {} is after end epoch {}",
+                    epochs.start(),
+                    epochs.end()
+                );
+            }
+            if *epochs.end() >= epoch_info.epoch {
+                bail!(
+                    "Rewards for epoch {} aren't paid out yet; the current epoch is {}",
+                    epochs.end(),
+                    epoch_info.epoch
+                );
+            }
+            (stake_accounts, Some(epochs), None)
+        }
+        IncomePeriod::Dates { from, to } => {
+            if from > to {
+                bail!("The period starts on {from}, after it ends on {to}");
+            }
+            let start = from.and_time(NaiveTime::MIN).and_utc().timestamp();
+            let end = to
+                .succ_opt()
+                .ok_or_else(|| anyhow!("Invalid end date {to}"))?
+                .and_time(NaiveTime::MIN)
+                .and_utc()
+                .timestamp();
+            let (stake_accounts, first_epoch, end_epoch) = tokio::try_join!(
+                accounts.fetch(ctx),
+                fetch_first_epoch_starting_from(ctx, start),
+                fetch_first_epoch_starting_from(ctx, end),
+            )?;
+            let epochs = epochs_credited_between(first_epoch, end_epoch);
+            (stake_accounts, epochs, Some((from, to)))
+        }
+    };
+
+    let rewards = match &epochs {
+        Some(epochs) => fetch_stake_rewards(ctx, &stake_accounts, epochs.clone()).await?,
+        None => Vec::new(),
+    };
+    let credits: Vec<RewardCredit> = rewards.iter().map(RewardCredit::from).collect();
+
+    match format {
+        LedgerFormat::Csv => write_csv(path, &credits)?,
+        LedgerFormat::Json => {
+            let ledger = serde_json::to_string_pretty(&credits)?;
+            fs::write(path, ledger)
+                .map_err(|err| anyhow!("Failed to write {}: {err}", path.display()))?;
+        }
+    }
+
+    let mut account_income: BTreeMap<Pubkey, (usize, u64)> = stake_accounts
+        .iter()
+        .map(|stake_account| (*stake_account, (0, 0)))
+        .collect();
+    for reward in &rewards {
+        if let Ok(stake_account) = reward.stake_account.parse()
+            && let Some((count, total)) = account_income.get_mut(&stake_account)
+        {
+            *count += 1;
+            *total += reward.amount;
+        }
+    }
+
+    Ok(IncomeReportOutput {
+        from: dates.map(|(from, _)| from.to_string()),
+        to: dates.map(|(_, to)| to.to_string()),
+        start_epoch: epochs.as_ref().map(|epochs| *epochs.start()),
+        end_epoch: epochs.as_ref().map(|epochs| *epochs.end()),
+        format,
+        path: path.display().to_string(),
+        credits: credits.len(),
+        total_lamports: rewards.iter().map(|reward| reward.amount).sum(),
+        accounts: account_income
+            .into_iter()
+            .map(
+                |(stake_account, (credits, total_lamports))| AccountIncomeOutput {
+                    stake_account: stake_account.to_string(),
+                    credits,
+                    total_lamports,
+                },
+            )
+            .collect(),
+    })
+}
+
+/// Estimated start of `epoch`, going by how far the current epoch has
+/// progressed at `now` and assuming every slot takes `DEFAULT_MS_PER_SLOT`.
+fn estimate_epoch_start(epoch_info: &EpochInfo, now: UnixTimestamp, epoch: Epoch) -> UnixTimestamp {
+    let slots_left_in_epoch = epoch_info.slots_in_epoch - epoch_info.slot_index;
+    let slots = match epoch.checked_sub(epoch_info.epoch + 1) {
+        Some(later_epochs) => slots_left_in_epoch + later_epochs * epoch_info.slots_in_epoch,
+        // The current epoch started `slot_index` slots ago
+        None => return now - (epoch_info.slot_index * DEFAULT_MS_PER_SLOT / 1_000) as i64,
+    };
+    now + (slots * DEFAULT_MS_PER_SLOT / 1_000) as i64
+}
+
+#[derive(Debug, Serialize)]
+pub struct StakeEtaEpochOutput {
+    pub epoch: Epoch,
+    /// Estimated start of the epoch
+    pub starts_at: UnixTimestamp,
+    pub effective: u64,
+    pub activating: u64,
+    pub deactivating: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StakeEtaOutput {
+    pub stake_account: Option<String>,
+    pub direction: StakeDirection,
+    /// Whether the projection assumes a deactivation that hasn't been
+    /// requested yet
+    pub hypothetical: bool,
+    pub stake: u64,
+    pub current_epoch: Epoch,
+    pub epoch_progress: f64,
+    /// Share of the cluster's effective stake that can warm up or cool down
+    /// per epoch
+    pub warmup_cooldown_rate: f64,
+    /// First epoch in which the stake is fully active or, when deactivating,
+    /// withdrawable
+    pub completion_epoch: Option<Epoch>,
+    pub completion_time: Option<UnixTimestamp>,
+    pub now: UnixTimestamp,
+    pub schedule: Vec<StakeEtaEpochOutput>,
+}
+
+impl CommandOutput for StakeEtaOutput {
+    fn print_table(&self) {
+        let date = |timestamp: i64| {
+            chrono::DateTime::<chrono::Utc>::from_timestamp_secs(timestamp)
+                .map(|dt| dt.format("%Y-%m-%d %H:%M UTC").to_string())
+                .unwrap_or_else(|| timestamp.to_string())
+        };
+        let percent = |lamports: u64| {
+            if self.stake == 0 {
+                0.0
+            } else {
+                lamports as f64 / self.stake as f64 * 100.0
+            }
+        };
+
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL).set_header(vec![
+            Cell::new("Epoch").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Starts (est.)").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Effective (SOL)").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Activating (SOL)").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Deactivating (SOL)").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Effective").add_attribute(comfy_table::Attribute::Bold),
+        ]);
+        for entry in &self.schedule {
+            let starts = if entry.epoch == self.current_epoch {
+                format!("current ({:.1}% done)", self.epoch_progress)
+            } else {
+                date(entry.starts_at)
+            };
+            table.add_row(vec![
+                Cell::new(entry.epoch),
+                Cell::new(starts),
+                Cell::new(lamports_to_sol(entry.effective)),
+                Cell::new(lamports_to_sol(entry.activating)),
+                Cell::new(lamports_to_sol(entry.deactivating)),
+                Cell::new(format!("{:.1}%", percent(entry.effective))),
+            ]);
+        }
+
+        let title = match &self.stake_account {
+            Some(stake_account) => format!("STAKE ETA FOR {stake_account}"),
+            None => format!("STAKE ETA FOR {} SOL", lamports_to_sol(self.stake)),
+        };
+        println!("\n{}", style(title).green().bold());
+        if self.hypothetical {
+            println!(
+                "{}",
+                style("The stake is fully active; projecting a deactivation requested now")
+                    .yellow()
+            );
+        }
+        println!("{table}");
+        println!(
+            "{} {:.0}% of the cluster's effective stake per epoch",
+            style("Warmup/Cooldown Rate:").cyan(),
+            self.warmup_cooldown_rate * 100.0
+        );
+
+        let outcome = match self.direction {
+            StakeDirection::Activate => "Fully active",
+            StakeDirection::Deactivate => "Withdrawable",
+        };
+        match (self.completion_epoch, self.completion_time) {
+            (Some(epoch), Some(time)) => println!(
+                "{} from epoch {epoch}, around {} (in {})",
+                style(format!("{outcome}:")).cyan(),
+                date(time),
+                format_duration(time.saturating_sub(self.now).max(0) as u64)
+            ),
+            _ => println!(
+                "{}",
+                style(format!(
+                    "{outcome} not reached within {MAX_PROJECTED_EPOCHS} epochs"
+                ))
+                .yellow()
+            ),
+        }
+        println!(
+            "{}",
+            style(format!(
+                "Times assume {DEFAULT_MS_PER_SLOT}ms slots and the cluster's recent warmup and \
+                 cooldown"
+            ))
+            .dim()
+        );
+    }
+}
+
+/// Projects how the stake of `target` warms up or cools down epoch by epoch
+/// and when it completes.
+async fn process_stake_eta(
+    ctx: &ScillaContext,
+    target: EtaTarget,
+) -> anyhow::Result<StakeEtaOutput> {
+    let pubkeys = match &target {
+        EtaTarget::Account(stake) => vec![*stake],
+        EtaTarget::Amount(..) => Vec::new(),
+    };
+    let ((accounts, cluster), epoch_info) =
+        tokio::try_join!(fetch_with_stake_cluster_state(ctx, &pubkeys), async {
+            Ok(ctx.rpc().get_epoch_info().await?)
+        },)?;
+    let current_epoch = cluster.clock.epoch;
+
+    let (stake_account, delegation, direction, hypothetical) = match target {
+        EtaTarget::Account(stake_pubkey) => {
+            let Some(Some(account)) = accounts.first() else {
+                bail!("Stake account {stake_pubkey} not found");
+            };
+            if account.owner != stake_program_id() {
+                bail!("Account is not owned by the stake program");
+            }
+            let stake_state: StakeStateV2 =
+                bincode_deserialize(&account.data, "stake account data")?;
+            let StakeStateV2::Stake(_, stake, _) = stake_state else {
+                bail!("Stake account is not delegated");
+            };
+            let mut delegation = stake.delegation;
+            let status = cluster.activation(&delegation);
+            let (direction, hypothetical) = match status {
+                StakeActivationStatus {
+                    effective: 0,
+                    activating: 0,
+                    ..
+                } => bail!("Stake account is fully deactivated and can be withdrawn"),
+                StakeActivationStatus { activating, .. } if activating > 0 => {
+                    (StakeDirection::Activate, false)
+                }
+                StakeActivationStatus { deactivating, .. } if deactivating > 0 => {
+                    (StakeDirection::Deactivate, false)
+                }
+                _ => {
+                    delegation.deactivation_epoch = current_epoch;
+                    (StakeDirection::Deactivate, true)
+                }
+            };
+            (
+                Some(stake_pubkey.to_string()),
+                delegation,
+                direction,
+                hypothetical,
+            )
+        }
+        EtaTarget::Amount(lamports, direction) => {
+            let delegation = match direction {
+                StakeDirection::Activate => {
+                    Delegation::new(&Pubkey::default(), lamports, current_epoch)
+                }
+                // Stake that has long been fully active
+                StakeDirection::Deactivate => Delegation {
+                    deactivation_epoch: current_epoch,
+                    ..Delegation::new(&Pubkey::default(), lamports, u64::MAX)
+                },
+            };
+            (None, delegation, direction, false)
+        }
+    };
+
+    let now = cluster.clock.unix_timestamp;
+    let projection = cluster.project_activation(&delegation);
+    let completion_epoch = cluster.estimate_completion_epoch(&delegation);
+
+    Ok(StakeEtaOutput {
+        stake_account,
+        direction,
+        hypothetical,
+        stake: delegation.stake,
+        current_epoch,
+        epoch_progress: epoch_info.slot_index as f64 / epoch_info.slots_in_epoch as f64 * 100.0,
+        warmup_cooldown_rate: warmup_cooldown_rate(
+            current_epoch,
+            cluster.new_rate_activation_epoch,
+        ),
+        completion_epoch,
+        completion_time: completion_epoch
+            .map(|epoch| estimate_epoch_start(&epoch_info, now, epoch)),
+        now,
+        schedule: projection
+            .into_iter()
+            .map(|(epoch, status)| StakeEtaEpochOutput {
+                epoch,
+                starts_at: estimate_epoch_start(&epoch_info, now, epoch),
+                effective: status.effective,
+                activating: status.activating,
+                deactivating: status.deactivating,
+            })
+            .collect(),
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct StakeHistoryEntryOutput {
+    pub epoch: u64,
+    pub effective: u64,
+    pub activating: u64,
+    pub deactivating: u64,
+    /// Percentage changes against the previous epoch, when it is in the
+    /// sysvar and non-zero
+    pub effective_change_pct: Option<f64>,
+    pub activating_change_pct: Option<f64>,
+    pub deactivating_change_pct: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StakeHistoryOutput {
+    pub page: usize,
+    pub total_pages: usize,
+    /// Epochs in the selected range across all pages
+    pub total_epochs: usize,
+    /// Newest epoch first
+    pub entries: Vec<StakeHistoryEntryOutput>,
+}
+
+impl CommandOutput for StakeHistoryOutput {
+    fn print_table(&self) {
+        if self.entries.is_empty() {
+            println!("{}", style("No stake history available").yellow());
+            return;
+        }
+
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL).set_header(vec![
+            Cell::new("Epoch").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Effective Stake").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Activating Stake").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Deactivating Stake").add_attribute(comfy_table::Attribute::Bold),
+        ]);
+
+        for entry in &self.entries {
+            // A shrinking effective stake is what stake flight looks like
+            let effective_change = match entry.effective_change_pct {
+                Some(pct) if pct < 0.0 => style(format!("{pct:+.2}%")).red().to_string(),
+                Some(pct) => style(format!("{pct:+.2}%")).green().to_string(),
+                None => "-".to_string(),
+            };
+            table.add_row(vec![
+                Cell::new(entry.epoch),
+                Cell::new(format!(
+                    "{} ({effective_change})",
+                    lamports_to_sol(entry.effective)
+                )),
+                Cell::new(format!(
+                    "{} ({})",
+                    lamports_to_sol(entry.activating),
+                    format_change_pct(entry.activating_change_pct)
+                )),
+                Cell::new(format!(
+                    "{} ({})",
+                    lamports_to_sol(entry.deactivating),
+                    format_change_pct(entry.deactivating_change_pct)
+                )),
+            ]);
+        }
+
+        println!("\n{}", style("CLUSTER STAKE HISTORY").green().bold());
+        println!("{}", table);
+
+        // Sparklines read left to right, so they run oldest to newest
+        let oldest_first = || self.entries.iter().rev();
+        let series = [
+            (
+                "Effective",
+                oldest_first()
+                    .map(|entry| entry.effective)
+                    .collect::<Vec<_>>(),
+            ),
+            (
+                "Activating",
+                oldest_first().map(|entry| entry.activating).collect(),
+            ),
+            (
+                "Deactivating",
+                oldest_first().map(|entry| entry.deactivating).collect(),
+            ),
+        ];
+        println!(
+            "\n{} {}",
+            style("TREND").green().bold(),
+            style(format!(
+                "(epochs {} to {})",
+                self.entries[self.entries.len() - 1].epoch,
+                self.entries[0].epoch
+            ))
+            .dim()
+        );
+        for (label, values) in series {
+            let min = values.iter().min().copied().unwrap_or_default();
+            let max = values.iter().max().copied().unwrap_or_default();
+            println!(
+                "{label:<13}{}  {} to {} SOL",
+                sparkline(&values),
+                lamports_to_sol(min),
+                lamports_to_sol(max)
+            );
+        }
+
+        println!(
+            "\n{}",
+            style(format!(
+                "Page {} of {} ({} epochs)",
+                self.page, self.total_pages, self.total_epochs
+            ))
+            .dim()
+        );
+    }
+}
+
+fn format_change_pct(change_pct: Option<f64>) -> String {
+    change_pct.map_or_else(|| "-".to_string(), |pct| format!("{pct:+.2}%"))
+}
+
+fn change_pct(previous: u64, current: u64) -> Option<f64> {
+    (previous > 0).then(|| (current as f64 - previous as f64) / previous as f64 * 100.0)
+}
+
+/// Draws `values` as a line of block characters scaled between their minimum
+/// and maximum.
+fn sparkline(values: &[u64]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let min = values.iter().min().copied().unwrap_or_default();
+    let max = values.iter().max().copied().unwrap_or_default();
+    values
+        .iter()
+        .map(|value| {
+            if max == min {
+                BARS[BARS.len() / 2]
+            } else {
+                let scaled =
+                    u128::from(value - min) * (BARS.len() as u128 - 1) / u128::from(max - min);
+                BARS[scaled as usize]
+            }
+        })
+        .collect()
+}
+
+async fn fetch_stake_history(ctx: &ScillaContext) -> anyhow::Result<StakeHistory> {
+    let stake_history_sysvar = Pubkey::from_str_const(STAKE_HISTORY_SYSVAR_ADDR);
+
+    let account = ctx.rpc().get_account(&stake_history_sysvar).await?;
+
+    bincode_deserialize_with_limit(account.data.len() as u64, &account.data, "stake history")
+}
+
+/// Selects one page of the epochs between `start_epoch` and `end_epoch`,
+/// newest first, comparing each epoch with the one before it.
+fn stake_history_page(
+    stake_history: &StakeHistory,
+    start_epoch: Option<Epoch>,
+    end_epoch: Option<Epoch>,
+    page: usize,
+    page_size: usize,
+) -> anyhow::Result<StakeHistoryOutput> {
+    if let (Some(start_epoch), Some(end_epoch)) = (start_epoch, end_epoch)
+        && start_epoch > end_epoch
+    {
+        bail!("Start epoch {start_epoch} is after end epoch {end_epoch}");
+    }
+
+    let in_range: Vec<&(Epoch, StakeHistoryEntry)> = stake_history
+        .iter()
+        .filter(|(epoch, _)| {
+            start_epoch.is_none_or(|start| *epoch >= start)
+                && end_epoch.is_none_or(|end| *epoch <= end)
+        })
+        .collect();
+    let total_epochs = in_range.len();
+    let total_pages = total_epochs.div_ceil(page_size).max(1);
+    if page > total_pages {
+        bail!("Page {page} is past the last page ({total_pages})");
+    }
+
+    let entries = in_range
+        .into_iter()
+        .skip((page - 1) * page_size)
+        .take(page_size)
+        .map(|(epoch, entry)| {
+            let previous = epoch
+                .checked_sub(1)
+                .and_then(|previous| stake_history.get(previous));
+            let change = |current: fn(&StakeHistoryEntry) -> u64| {
+                previous.and_then(|previous| change_pct(current(previous), current(entry)))
+            };
+
+            StakeHistoryEntryOutput {
+                epoch: *epoch,
+                effective: entry.effective,
+                activating: entry.activating,
+                deactivating: entry.deactivating,
+                effective_change_pct: change(|entry| entry.effective),
+                activating_change_pct: change(|entry| entry.activating),
+                deactivating_change_pct: change(|entry| entry.deactivating),
+            }
+        })
+        .collect();
+
+    Ok(StakeHistoryOutput {
+        page,
+        total_pages,
+        total_epochs,
+        entries,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_stake_interface::{stake_flags::StakeFlags, state::Stake},
+    };
+
+    fn cluster_at(epoch: Epoch, entry: StakeHistoryEntry) -> StakeClusterState {
+        let mut stake_history = StakeHistory::default();
+        for past in 0..epoch {
+            stake_history.add(past, entry.clone());
+        }
+        StakeClusterState {
+            stake_history,
+            clock: Clock {
+                epoch,
+                ..Clock::default()
+            },
+            new_rate_activation_epoch: Some(0),
+        }
+    }
+
+    #[test]
+    fn test_estimate_completion_epoch() {
+        let cluster = cluster_at(
+            10,
+            StakeHistoryEntry {
+                effective: 1_000_000 * LAMPORTS_PER_SOL,
+                activating: 0,
+                deactivating: 0,
+            },
+        );
+
+        let mut delegation = Delegation::new(&Pubkey::new_unique(), 1_000 * LAMPORTS_PER_SOL, 10);
+        // Small next to the cluster's 9% warmup allowance, so one epoch is enough
+        assert_eq!(cluster.estimate_completion_epoch(&delegation), Some(11));
+
+        // Half the cluster's stake warms up 90,000 SOL per epoch
+        delegation.stake = 500_000 * LAMPORTS_PER_SOL;
+        assert_eq!(cluster.estimate_completion_epoch(&delegation), Some(16));
+
+        // Bootstrap stake is fully active without any history
+        delegation.activation_epoch = u64::MAX;
+        delegation.deactivation_epoch = 10;
+        assert_eq!(cluster.estimate_completion_epoch(&delegation), Some(16));
+
+        delegation.deactivation_epoch = u64::MAX;
+        assert_eq!(cluster.estimate_completion_epoch(&delegation), None);
+    }
+
+    #[test]
+    fn test_project_activation() {
+        let cluster = cluster_at(
+            10,
+            StakeHistoryEntry {
+                effective: 1_000_000 * LAMPORTS_PER_SOL,
+                activating: 0,
+                deactivating: 0,
+            },
+        );
+        let delegation = Delegation::new(&Pubkey::new_unique(), 500_000 * LAMPORTS_PER_SOL, 10);
+
+        let projection = cluster.project_activation(&delegation);
+
+        // Starts in the current epoch and ends once fully active
+        assert_eq!(projection.first().map(|(epoch, _)| *epoch), Some(10));
+        assert_eq!(
+            projection.last().map(|(epoch, _)| *epoch),
+            cluster.estimate_completion_epoch(&delegation)
+        );
+        let effective: Vec<u64> = projection
+            .iter()
+            .map(|(_, status)| status.effective)
+            .collect();
+        assert!(effective.is_sorted());
+        assert_eq!(effective.last(), Some(&delegation.stake));
+    }
+
+    #[test]
+    fn test_estimate_epoch_start() {
+        let epoch_info = EpochInfo {
+            epoch: 100,
+            slot_index: 100_000,
+            slots_in_epoch: 432_000,
+            absolute_slot: 43_300_000,
+            block_height: 0,
+            transaction_count: None,
+        };
+        let now = 1_700_000_000;
+
+        // 400ms slots: the current epoch started 40,000s ago and the next
+        // starts in 332,000 slots
+        assert_eq!(estimate_epoch_start(&epoch_info, now, 100), now - 40_000);
+        assert_eq!(estimate_epoch_start(&epoch_info, now, 101), now + 132_800);
+        assert_eq!(
+            estimate_epoch_start(&epoch_info, now, 103),
+            now + 132_800 + 2 * 172_800
+        );
+    }
+
+    #[test]
+    fn test_validator_report() {
+        let vote_account = RpcVoteAccountInfo {
+            vote_pubkey: Pubkey::new_unique().to_string(),
+            node_pubkey: Pubkey::new_unique().to_string(),
+            activated_stake: 1_000 * LAMPORTS_PER_SOL,
+            commission: 5,
+            epoch_vote_account: true,
+            epoch_credits: (96..=100)
+                .map(|epoch| (epoch, epoch * 1_000, (epoch - 1) * 1_000 + 200))
+                .collect(),
+            last_vote: 999_990,
+            root_slot: 999_950,
+        };
+
+        let report = ValidatorReport::new(&vote_account, false, None, 1_000_000, 100);
+        assert!(!report.delinquent);
+        assert!(report.warnings.is_empty());
+        assert_eq!(report.vote_lag, 10);
+        assert_eq!(report.epoch_credits.len(), 5);
+        assert_eq!(report.epoch_credits[0].credits, 800);
+
+        let report = ValidatorReport::new(&vote_account, true, None, 1_000_000, 100);
+        assert!(report.delinquent);
+
+        let lagging = RpcVoteAccountInfo {
+            root_slot: 1_000_000 - DELINQUENT_VALIDATOR_SLOT_DISTANCE - 1,
+            ..vote_account.clone()
+        };
+        assert!(ValidatorReport::new(&lagging, false, None, 1_000_000, 100).delinquent);
+
+        let unstaked = RpcVoteAccountInfo {
+            activated_stake: 0,
+            root_slot: 0,
+            ..vote_account.clone()
+        };
+        assert!(!ValidatorReport::new(&unstaked, false, None, 1_000_000, 100).delinquent);
+
+        let greedy = RpcVoteAccountInfo {
+            commission: 100,
+            ..vote_account.clone()
+        };
+        let report = ValidatorReport::new(&greedy, false, None, 1_000_000, 100);
+        assert!(!report.delinquent);
+        assert_eq!(report.warnings.len(), 1);
+
+        let recent = RpcVoteAccountInfo {
+            epoch_credits: vec![(98, 500, 0), (99, 1_000, 500), (100, 1_500, 1_000)],
+            ..vote_account
+        };
+        let report = ValidatorReport::new(&recent, false, None, 1_000_000, 100);
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].starts_with("RECENTLY CREATED"));
+    }
+
+    #[test]
+    fn test_stake_history_page() -> anyhow::Result<()> {
+        let mut stake_history = StakeHistory::default();
+        for epoch in 0..25 {
+            stake_history.add(
+                epoch,
+                StakeHistoryEntry {
+                    effective: 1_000 + epoch * 10,
+                    activating: epoch % 2 * 100,
+                    deactivating: 50,
+                },
+            );
+        }
+
+        let history = stake_history_page(&stake_history, None, None, 1, 10)?;
+        assert_eq!((history.total_epochs, history.total_pages), (25, 3));
+        assert_eq!(history.entries.len(), 10);
+        assert_eq!(history.entries[0].epoch, 24);
+        assert_eq!(
+            history.entries[0].effective_change_pct,
+            Some(10.0 / 1_230.0 * 100.0)
+        );
+        assert_eq!(history.entries[0].activating_change_pct, Some(-100.0));
+        // No change can be measured from zero activating stake
+        assert_eq!(history.entries[1].activating_change_pct, None);
+        assert_eq!(history.entries[0].deactivating_change_pct, Some(0.0));
+
+        // The oldest epoch in the sysvar has nothing to compare against
+        let history = stake_history_page(&stake_history, None, None, 3, 10)?;
+        assert_eq!(history.entries.len(), 5);
+        assert_eq!(history.entries[4].epoch, 0);
+        assert_eq!(history.entries[4].effective_change_pct, None);
+
+        let history = stake_history_page(&stake_history, Some(5), Some(9), 1, 10)?;
+        assert_eq!(
+            history
+                .entries
+                .iter()
+                .map(|entry| entry.epoch)
+                .collect::<Vec<_>>(),
+            vec![9, 8, 7, 6, 5]
+        );
+        assert!(history.entries[4].effective_change_pct.is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stake_history_page_invalid() {
+        let mut stake_history = StakeHistory::default();
+        for epoch in 0..25 {
+            stake_history.add(epoch, StakeHistoryEntry::default());
+        }
+
+        let err = stake_history_page(&stake_history, None, None, 4, 10).unwrap_err();
+        assert_eq!(err.to_string(), "Page 4 is past the last page (3)");
+
+        // Narrowing the range shrinks the number of pages
+        let err = stake_history_page(&stake_history, Some(20), None, 2, 10).unwrap_err();
+        assert_eq!(err.to_string(), "Page 2 is past the last page (1)");
+
+        let err = stake_history_page(&stake_history, Some(9), Some(5), 1, 10).unwrap_err();
+        assert_eq!(err.to_string(), "Start epoch 9 is after end epoch 5");
+    }
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[0, 7, 14, 7]), "▁▄█▄");
+        assert_eq!(sparkline(&[5, 5]), "▅▅");
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn test_stake_voter_offset() -> anyhow::Result<()> {
+        let voter = Pubkey::new_unique();
+        let stake_state = StakeStateV2::Stake(
+            Meta::default(),
+            Stake {
+                delegation: Delegation::new(&voter, LAMPORTS_PER_SOL, 0),
+                credits_observed: 0,
+            },
+            StakeFlags::empty(),
+        );
+        let data = bincode::serialize(&stake_state)?;
+        assert_eq!(
+            &data[STAKE_VOTER_OFFSET..STAKE_VOTER_OFFSET + 32],
+            voter.as_ref()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_withdrawable_lamports() {
+        let cluster = cluster_at(
+            10,
+            StakeHistoryEntry {
+                effective: 1_000_000 * LAMPORTS_PER_SOL,
+                activating: 0,
+                deactivating: 5 * LAMPORTS_PER_SOL,
+            },
+        );
+        let meta = Meta {
+            rent_exempt_reserve: 2_282_880,
+            ..Meta::default()
+        };
+        let mut stake = Stake {
+            delegation: Delegation::new(&Pubkey::new_unique(), 5 * LAMPORTS_PER_SOL, u64::MAX),
+            credits_observed: 0,
+        };
+        let lamports = 6 * LAMPORTS_PER_SOL + meta.rent_exempt_reserve;
+
+        let delegated = StakeStateV2::Stake(meta, stake, StakeFlags::empty());
+        assert_eq!(
+            withdrawable_lamports(lamports, &delegated, &cluster),
+            LAMPORTS_PER_SOL
+        );
+
+        stake.delegation.deactivation_epoch = 5;
+        let deactivated = StakeStateV2::Stake(meta, stake, StakeFlags::empty());
+        assert_eq!(
+            withdrawable_lamports(lamports, &deactivated, &cluster),
+            lamports
+        );
+    }
+
+    #[test]
+    fn test_authority_offsets() {
+        let staker = Pubkey::new_unique();
+        let withdrawer = Pubkey::new_unique();
+        let state = StakeStateV2::Initialized(Meta {
+            authorized: Authorized { staker, withdrawer },
+            ..Meta::default()
+        });
+        let data = bincode::serialize(&state).unwrap();
+
+        assert_eq!(
+            &data[STAKE_STAKER_OFFSET..STAKE_STAKER_OFFSET + 32],
+            staker.as_ref()
+        );
+        assert_eq!(
+            &data[STAKE_WITHDRAWER_OFFSET..STAKE_WITHDRAWER_OFFSET + 32],
+            withdrawer.as_ref()
+        );
+    }
+
+    #[test]
+    fn test_check_stake_authorize() {
+        let staker = Pubkey::new_unique();
+        let withdrawer = Pubkey::new_unique();
+        let custodian = Pubkey::new_unique();
+        let clock = Clock {
+            epoch: 10,
+            ..Clock::default()
+        };
+        let mut meta = Meta {
+            authorized: Authorized { staker, withdrawer },
+            ..Meta::default()
+        };
+
+        let check = |meta: &Meta, authority_type, signer: &Pubkey, custodian: Option<&Pubkey>| {
+            check_stake_authorize(meta, &clock, authority_type, signer, custodian)
+        };
+
+        // Either authority may replace the stake authority
+        assert!(check(&meta, StakeAuthorityType::Staker, &staker, None).is_ok());
+        assert!(check(&meta, StakeAuthorityType::Staker, &withdrawer, None).is_ok());
+        assert!(check(&meta, StakeAuthorityType::Staker, &custodian, None).is_err());
+
+        // Only the withdraw authority may replace itself
+        assert!(check(&meta, StakeAuthorityType::Withdrawer, &withdrawer, None).is_ok());
+        assert!(check(&meta, StakeAuthorityType::Withdrawer, &staker, None).is_err());
+
+        // While a lockup is in force the custodian must co-sign
+        meta.lockup = Lockup {
+            epoch: 20,
+            unix_timestamp: 0,
+            custodian,
+        };
+        assert!(check(&meta, StakeAuthorityType::Withdrawer, &withdrawer, None).is_err());
+        assert!(
+            check(
+                &meta,
+                StakeAuthorityType::Withdrawer,
+                &withdrawer,
+                Some(&staker)
+            )
+            .is_err()
+        );
+        assert!(
+            check(
+                &meta,
+                StakeAuthorityType::Withdrawer,
+                &withdrawer,
+                Some(&custodian)
+            )
+            .is_ok()
+        );
+        assert!(check(&meta, StakeAuthorityType::Staker, &staker, None).is_ok());
+    }
+
+    #[test]
+    fn test_lockup_status() {
+        let withdrawer = Pubkey::new_unique();
+        let custodian = Pubkey::new_unique();
+        let clock = Clock {
+            epoch: 10,
+            unix_timestamp: 1_000,
+            ..Clock::default()
+        };
+        let mut meta = Meta {
+            authorized: Authorized::auto(&withdrawer),
+            lockup: Lockup {
+                epoch: 12,
+                unix_timestamp: 500,
+                custodian,
+            },
+            ..Meta::default()
+        };
+
+        let status = LockupStatusOutput::new(&Pubkey::new_unique(), &meta, &clock);
+        assert!(status.in_force);
+        assert!(status.custodian_required);
+        assert_eq!(status.epochs_remaining, 2);
+        assert_eq!(status.seconds_remaining, 0);
+        assert_eq!(status.lockup_authority, custodian.to_string());
+
+        // Only the custodian may update a lockup in force
+        assert!(check_set_lockup(&meta, &clock, &custodian).is_ok());
+        assert!(check_set_lockup(&meta, &clock, &withdrawer).is_err());
+
+        meta.lockup.epoch = 10;
+        let status = LockupStatusOutput::new(&Pubkey::new_unique(), &meta, &clock);
+        assert!(!status.in_force);
+        assert_eq!(status.lockup_authority, withdrawer.to_string());
+        assert!(check_set_lockup(&meta, &clock, &withdrawer).is_ok());
+        assert!(check_set_lockup(&meta, &clock, &custodian).is_err());
+    }
+
+    #[test]
+    fn test_check_move_stake() {
+        let cluster = cluster_at(
+            10,
+            StakeHistoryEntry {
+                effective: 1_000_000 * LAMPORTS_PER_SOL,
+                activating: 0,
+                deactivating: 0,
+            },
+        );
+        let authority = Pubkey::new_unique();
+        let vote = Pubkey::new_unique();
+        let meta = Meta {
+            rent_exempt_reserve: 2_282_880,
+            authorized: Authorized::auto(&authority),
+            ..Meta::default()
+        };
+        let active = |vote: &Pubkey, stake: u64| {
+            StakeStateV2::Stake(
+                meta,
+                Stake {
+                    delegation: Delegation::new(vote, stake, u64::MAX),
+                    credits_observed: 0,
+                },
+                StakeFlags::empty(),
+            )
+        };
+        let balance = |stake: u64| stake + meta.rent_exempt_reserve + LAMPORTS_PER_SOL;
+        let minimum = LAMPORTS_PER_SOL;
+
+        let source = active(&vote, 5 * LAMPORTS_PER_SOL);
+        let same_vote = active(&vote, 5 * LAMPORTS_PER_SOL);
+        let other_vote = active(&Pubkey::new_unique(), 5 * LAMPORTS_PER_SOL);
+        let initialized = StakeStateV2::Initialized(meta);
+        let check = |operation, destination: &StakeStateV2, signer: &Pubkey, lamports| {
+            check_move_stake(
+                operation,
+                (&source, balance(5 * LAMPORTS_PER_SOL)),
+                (destination, meta.rent_exempt_reserve),
+                signer,
+                lamports,
+                minimum,
+                &cluster,
+            )
+        };
+
+        // Stake moves only to the same validator or into an inactive account
+        assert!(
+            check(
+                MoveOperation::Stake,
+                &same_vote,
+                &authority,
+                LAMPORTS_PER_SOL
+            )
+            .is_ok()
+        );
+        assert!(
+            check(
+                MoveOperation::Stake,
+                &initialized,
+                &authority,
+                LAMPORTS_PER_SOL
+            )
+            .is_ok()
+        );
+        assert!(
+            check(
+                MoveOperation::Stake,
+                &other_vote,
+                &authority,
+                LAMPORTS_PER_SOL
+            )
+            .is_err()
+        );
+        // Neither side may drop below the minimum delegation
+        assert!(
+            check(
+                MoveOperation::Stake,
+                &same_vote,
+                &authority,
+                9 * LAMPORTS_PER_SOL / 2
+            )
+            .is_err()
+        );
+        assert!(
+            check(
+                MoveOperation::Stake,
+                &initialized,
+                &authority,
+                LAMPORTS_PER_SOL / 2
+            )
+            .is_err()
+        );
+        assert!(
+            check(
+                MoveOperation::Stake,
+                &same_vote,
+                &authority,
+                5 * LAMPORTS_PER_SOL
+            )
+            .is_ok()
+        );
+        assert!(
+            check(
+                MoveOperation::Stake,
+                &same_vote,
+                &Pubkey::new_unique(),
+                LAMPORTS_PER_SOL
+            )
+            .is_err()
+        );
+
+        // Only the balance that is neither delegated nor rent can move
+        assert!(
+            check(
+                MoveOperation::Lamports,
+                &other_vote,
+                &authority,
+                LAMPORTS_PER_SOL
+            )
+            .is_ok()
+        );
+        assert!(
+            check(
+                MoveOperation::Lamports,
+                &other_vote,
+                &authority,
+                LAMPORTS_PER_SOL + 1
+            )
+            .is_err()
+        );
+
+        // Accounts with different authorities can't exchange stake
+        let foreign = StakeStateV2::Initialized(Meta {
+            authorized: Authorized::auto(&Pubkey::new_unique()),
+            ..meta
+        });
+        assert!(check(MoveOperation::Lamports, &foreign, &authority, 1).is_err());
+    }
+
+    #[test]
+    fn test_check_bulk_stake_account() {
+        let cluster = cluster_at(
+            10,
+            StakeHistoryEntry {
+                effective: 1_000_000 * LAMPORTS_PER_SOL,
+                activating: 0,
+                deactivating: LAMPORTS_PER_SOL,
+            },
+        );
+        let authority = Pubkey::new_unique();
+        let meta = Meta {
+            rent_exempt_reserve: 2_282_880,
+            authorized: Authorized::auto(&authority),
+            ..Meta::default()
+        };
+        let delegated = |deactivation_epoch| {
+            let mut delegation = Delegation::new(&Pubkey::new_unique(), LAMPORTS_PER_SOL, u64::MAX);
+            delegation.deactivation_epoch = deactivation_epoch;
+            StakeStateV2::Stake(
+                meta,
+                Stake {
+                    delegation,
+                    credits_observed: 0,
+                },
+                StakeFlags::empty(),
+            )
+        };
+        let lamports = LAMPORTS_PER_SOL + meta.rent_exempt_reserve;
+        let active = delegated(u64::MAX);
+        // Bootstrap stake deactivated in an earlier epoch is fully inactive
+        let inactive = delegated(5);
+        let withdraw = BulkOperation::Withdraw {
+            recipient: Pubkey::new_unique(),
+        };
+        let check = |operation, state: &StakeStateV2, signer: &Pubkey| {
+            check_bulk_stake_account(operation, state, lamports, signer, &cluster)
+        };
+
+        assert_eq!(
+            check(BulkOperation::Deactivate, &active, &authority).unwrap(),
+            LAMPORTS_PER_SOL
+        );
+        assert!(check(BulkOperation::Deactivate, &inactive, &authority).is_err());
+        assert!(
+            check(
+                BulkOperation::Deactivate,
+                &StakeStateV2::Initialized(meta),
+                &authority
+            )
+            .is_err()
+        );
+        assert!(check(BulkOperation::Deactivate, &active, &Pubkey::new_unique()).is_err());
+
+        assert_eq!(check(withdraw, &inactive, &authority).unwrap(), lamports);
+        assert_eq!(
+            check(withdraw, &StakeStateV2::Initialized(meta), &authority).unwrap(),
+            lamports
+        );
+        assert!(check(withdraw, &active, &authority).is_err());
+        assert!(check(withdraw, &inactive, &Pubkey::new_unique()).is_err());
+
+        // A lockup in force needs the custodian, which bulk withdrawals don't
+        // sign with
+        let mut locked = meta;
+        locked.lockup.epoch = 20;
+        assert!(check(withdraw, &StakeStateV2::Initialized(locked), &authority).is_err());
+    }
+
+    #[test]
+    fn test_plan_merges() {
+        let cluster = cluster_at(
+            10,
+            StakeHistoryEntry {
+                effective: 1_000_000 * LAMPORTS_PER_SOL,
+                activating: LAMPORTS_PER_SOL,
+                deactivating: 0,
+            },
+        );
+        let authority = Pubkey::new_unique();
+        let meta = Meta {
+            rent_exempt_reserve: 2_282_880,
+            authorized: Authorized::auto(&authority),
+            ..Meta::default()
+        };
+        let delegated = |meta: Meta, vote: &Pubkey, activation_epoch| {
+            StakeStateV2::Stake(
+                meta,
+                Stake {
+                    delegation: Delegation::new(vote, LAMPORTS_PER_SOL, activation_epoch),
+                    credits_observed: 0,
+                },
+                StakeFlags::empty(),
+            )
+        };
+        let (vote_a, vote_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut locked = meta;
+        locked.lockup.epoch = 20;
+        let mut deactivating = delegated(meta, &vote_a, u64::MAX);
+        if let StakeStateV2::Stake(_, stake, _) = &mut deactivating {
+            stake.delegation.deactivation_epoch = 10;
+        }
+
+        let account = |lamports, state| (Pubkey::new_unique(), lamports, state);
+        let accounts = vec![
+            // Fully active with the same validator: merged into the largest
+            account(3, delegated(meta, &vote_a, u64::MAX)),
+            account(5, delegated(meta, &vote_a, u64::MAX)),
+            account(4, delegated(meta, &vote_a, u64::MAX)),
+            // Only active account with this validator
+            account(1, delegated(meta, &vote_b, u64::MAX)),
+            // Activating this epoch
+            account(1, delegated(meta, &vote_b, 10)),
+            account(2, delegated(meta, &vote_b, 10)),
+            // Inactive, but the lockup of one is in force
+            account(1, StakeStateV2::Initialized(meta)),
+            account(1, StakeStateV2::Initialized(locked)),
+            // Cooling down
+            account(1, deactivating),
+        ];
+
+        let (groups, unmergeable) = plan_merges(&accounts, &cluster);
+
+        assert_eq!(groups.len(), 2);
+        let active = groups
+            .iter()
+            .find(|group| group.state == MergeState::Active)
+            .unwrap();
+        assert_eq!(active.destination, accounts[1].0);
+        assert_eq!(active.voter, Some(vote_a));
+        assert_eq!(active.sources, vec![(accounts[2].0, 4), (accounts[0].0, 3)]);
+        let activating = groups
+            .iter()
+            .find(|group| group.state == MergeState::Activating)
+            .unwrap();
+        assert_eq!(activating.destination, accounts[5].0);
+        assert_eq!(activating.sources, vec![(accounts[4].0, 1)]);
+
+        assert_eq!(unmergeable.len(), 1);
+        assert_eq!(unmergeable[0].0, accounts[8].0);
+    }
+
+    #[test]
+    fn test_split_parts() -> anyhow::Result<()> {
+        // The remainder of an uneven split stays in the account
+        assert_eq!(split_parts(10, &SplitShares::Equal(3))?, vec![4, 3, 3]);
+        assert_eq!(
+            split_parts(
+                1_000 * LAMPORTS_PER_SOL,
+                &SplitShares::Percentages(vec![50.0, 30.0, 20.0])
+            )?,
+            vec![
+                500 * LAMPORTS_PER_SOL,
+                300 * LAMPORTS_PER_SOL,
+                200 * LAMPORTS_PER_SOL
+            ]
+        );
+        assert_eq!(
+            split_parts(100, &SplitShares::Percentages(vec![33.4, 33.3, 33.3]))?,
+            vec![34, 33, 33]
+        );
+
+        assert!(split_parts(10, &SplitShares::Equal(1)).is_err());
+        assert!(split_parts(10, &SplitShares::Percentages(vec![100.0])).is_err());
+        assert!(split_parts(10, &SplitShares::Percentages(vec![60.0, 30.0])).is_err());
+        assert!(split_parts(10, &SplitShares::Percentages(vec![110.0, -10.0])).is_err());
+        assert_eq!(
+            "50, 30%,20".parse::<Percentages>()?,
+            Percentages(vec![50.0, 30.0, 20.0])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_derive_stake_address() -> anyhow::Result<()> {
+        let base = Pubkey::new_unique();
+
+        assert_eq!(
+            derive_stake_address(&base, "stake-0")?,
+            Pubkey::create_with_seed(&base, "stake-0", &stake_program_id())?
+        );
+        assert_ne!(
+            derive_stake_address(&base, "stake-0")?,
+            derive_stake_address(&base, "stake-1")?
+        );
+        // Seeds are limited to 32 bytes
+        assert!(derive_stake_address(&base, &"s".repeat(33)).is_err());
+
+        Ok(())
+    }
 }
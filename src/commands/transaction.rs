@@ -2,35 +2,111 @@ use {
     crate::{
         commands::CommandFlow,
         context::ScillaContext,
-        misc::helpers::{bincode_deserialize, decode_base58, decode_base64},
-        prompt::{prompt_input_data, prompt_select_data},
-        ui::show_spinner,
+        error::ScillaResult,
+        misc::{
+            helpers::{TxOutcome, read_keypair_from_path},
+            signatures::{
+                SignedTransactionOutput, SignerSignature, add_signature, decode_transaction,
+                format_signers, merge_signatures, missing_signers, sign_transaction,
+            },
+            simulation::preview_and_confirm,
+        },
+        output::CommandOutput,
+        prompt::{prompt_input_data, prompt_keypair_path, prompt_select_data},
+        ui::{print_error, report_error, show_spinner},
     },
+    anyhow::bail,
+    clap::{Args, Subcommand},
     comfy_table::{Cell, Table, presets::UTF8_FULL},
     console::style,
+    inquire::Confirm,
+    serde::Serialize,
     solana_rpc_client_api::config::RpcTransactionConfig,
     solana_signature::Signature,
     solana_transaction::versioned::VersionedTransaction,
-    solana_transaction_status::{EncodedTransaction, UiMessage, UiTransactionEncoding},
-    std::fmt,
+    solana_transaction_status::{
+        EncodedTransaction, TransactionConfirmationStatus, UiMessage, UiTransactionEncoding,
+    },
+    std::{fmt, path::PathBuf},
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Subcommand)]
 pub enum TransactionCommand {
-    CheckConfirmation,
-    FetchStatus,
-    FetchTransaction,
-    SendTransaction,
+    /// Check if a transaction landed
+    CheckConfirmation(SignatureArgs),
+    /// Fetch the status of a transaction
+    FetchStatus(SignatureArgs),
+    /// Fetch full transaction data
+    FetchTransaction(SignatureArgs),
+    /// Send an encoded VersionedTransaction
+    SendTransaction(SendTransactionArgs),
+    /// Add a local signature to a partially signed transaction
+    SignTransaction(SignTransactionArgs),
+    #[command(skip)]
     GoBack,
 }
 
+#[derive(Debug, Clone, Default, Args)]
+pub struct SignatureArgs {
+    /// Transaction signature
+    #[arg(long)]
+    pub signature: Option<Signature>,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct SendTransactionArgs {
+    /// Encoding of the transaction (base64 or base58)
+    #[arg(long, value_parser = parse_transaction_encoding)]
+    pub encoding: Option<UiTransactionEncoding>,
+    /// Encoded transaction
+    #[arg(long)]
+    pub transaction: Option<String>,
+    /// Partially signed copy of the same transaction to merge signatures from
+    /// (repeatable)
+    #[arg(long)]
+    pub merge: Vec<String>,
+    /// Signature collected from another signer, as PUBKEY=SIGNATURE
+    /// (repeatable)
+    #[arg(long)]
+    pub signer: Vec<SignerSignature>,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct SignTransactionArgs {
+    /// Encoding of the transaction (base64 or base58)
+    #[arg(long, value_parser = parse_transaction_encoding)]
+    pub encoding: Option<UiTransactionEncoding>,
+    /// Encoded, partially signed transaction
+    #[arg(long)]
+    pub transaction: Option<String>,
+    /// Keypair path to sign with (repeatable, defaults to the configured
+    /// keypair)
+    #[arg(long)]
+    pub keypair: Vec<PathBuf>,
+    /// File to save the signed transaction to
+    #[arg(long)]
+    pub export: Option<PathBuf>,
+    /// Submit the transaction once every signature is present
+    #[arg(long)]
+    pub send: bool,
+}
+
+fn parse_transaction_encoding(s: &str) -> anyhow::Result<UiTransactionEncoding> {
+    match s.to_ascii_lowercase().as_str() {
+        "base64" => Ok(UiTransactionEncoding::Base64),
+        "base58" => Ok(UiTransactionEncoding::Base58),
+        _ => bail!("Unsupported encoding {s}. Use base64 or base58"),
+    }
+}
+
 impl TransactionCommand {
     pub fn spinner_msg(&self) -> &'static str {
         match self {
-            Self::CheckConfirmation => "Checking transaction confirmation…",
-            Self::FetchStatus => "Fetching transaction status…",
-            Self::FetchTransaction => "Fetching full transaction data…",
-            Self::SendTransaction => "Sending transaction…",
+            Self::CheckConfirmation(_) => "Checking transaction confirmation…",
+            Self::FetchStatus(_) => "Fetching transaction status…",
+            Self::FetchTransaction(_) => "Fetching full transaction data…",
+            Self::SendTransaction(_) => "Sending transaction…",
+            Self::SignTransaction(_) => "Signing transaction…",
             Self::GoBack => "Going back…",
         }
     }
@@ -39,102 +115,272 @@ impl TransactionCommand {
 impl fmt::Display for TransactionCommand {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_str(match self {
-            Self::CheckConfirmation => "Check Transaction Confirmation",
-            Self::FetchStatus => "Fetch Transaction Status",
-            Self::FetchTransaction => "Fetch Transaction",
-            Self::SendTransaction => "Send Transaction",
+            Self::CheckConfirmation(_) => "Check Transaction Confirmation",
+            Self::FetchStatus(_) => "Fetch Transaction Status",
+            Self::FetchTransaction(_) => "Fetch Transaction",
+            Self::SendTransaction(_) => "Send Transaction",
+            Self::SignTransaction(_) => "Sign Transaction",
             Self::GoBack => "Go back",
         })
     }
 }
 
 impl TransactionCommand {
-    pub async fn process_command(&self, ctx: &ScillaContext) -> CommandFlow<()> {
+    pub async fn process_command(&self, ctx: &ScillaContext) -> ScillaResult<()> {
         match self {
-            TransactionCommand::CheckConfirmation => {
-                let signature: Signature = prompt_input_data("Enter transaction signature:");
-                show_spinner(
+            TransactionCommand::CheckConfirmation(args) => {
+                let signature: Signature = args
+                    .signature
+                    .unwrap_or_else(|| prompt_input_data("Enter transaction signature:"));
+                let confirmation = show_spinner(
                     self.spinner_msg(),
                     process_check_confirmation(ctx, &signature),
                 )
-                .await;
+                .await?;
+                ctx.output_format().render(&confirmation)?;
             }
-            TransactionCommand::FetchStatus => {
-                let signature: Signature = prompt_input_data("Enter transaction signature:");
-                show_spinner(
+            TransactionCommand::FetchStatus(args) => {
+                let signature: Signature = args
+                    .signature
+                    .unwrap_or_else(|| prompt_input_data("Enter transaction signature:"));
+                let status = show_spinner(
                     self.spinner_msg(),
                     process_fetch_transaction_status(ctx, &signature),
                 )
-                .await;
+                .await?;
+                ctx.output_format().render(&status)?;
             }
-            TransactionCommand::FetchTransaction => {
-                let signature: Signature = prompt_input_data("Enter transaction signature:");
-                show_spinner(
+            TransactionCommand::FetchTransaction(args) => {
+                let signature: Signature = args
+                    .signature
+                    .unwrap_or_else(|| prompt_input_data("Enter transaction signature:"));
+                let transaction = show_spinner(
                     self.spinner_msg(),
                     process_fetch_transaction(ctx, &signature),
                 )
-                .await;
+                .await?;
+                ctx.output_format().render(&transaction)?;
             }
-            TransactionCommand::SendTransaction => {
-                println!(
-                    "{}",
-                    style("Note: Only VersionedTransaction format is supported")
-                        .yellow()
-                        .dim()
-                );
-
-                let encoding = prompt_select_data(
-                    "Select encoding format:",
-                    vec![UiTransactionEncoding::Base64, UiTransactionEncoding::Base58],
-                );
-
-                let encoded_tx: String = prompt_input_data("Enter encoded transaction:");
-
-                show_spinner(
-                    self.spinner_msg(),
-                    process_send_transaction(ctx, encoding, &encoded_tx),
-                )
-                .await;
+            TransactionCommand::SendTransaction(args) => {
+                if args.transaction.is_none() {
+                    println!(
+                        "{}",
+                        style("Note: Only VersionedTransaction format is supported")
+                            .yellow()
+                            .dim()
+                    );
+                }
+
+                let encoding = args.encoding.unwrap_or_else(|| {
+                    prompt_select_data(
+                        "Select encoding format:",
+                        vec![UiTransactionEncoding::Base64, UiTransactionEncoding::Base58],
+                    )
+                });
+
+                let encoded_tx: String = args
+                    .transaction
+                    .clone()
+                    .unwrap_or_else(|| prompt_input_data("Enter encoded transaction:"));
+
+                let mut tx =
+                    decode_with_signatures(encoding, &encoded_tx, args).map_err(report_error)?;
+
+                // Interactively collect the signatures produced offline
+                if args.transaction.is_none() {
+                    while !missing_signers(&tx).is_empty() {
+                        let missing = missing_signers(&tx);
+                        println!(
+                            "{} {}",
+                            style("Missing signatures from:").yellow(),
+                            format_signers(&missing)
+                        );
+                        let input: String = prompt_input_data(
+                            "Enter signature as PUBKEY=SIGNATURE (leave empty to stop):",
+                        );
+                        if input.trim().is_empty() {
+                            break;
+                        }
+                        let added = input.parse::<SignerSignature>().and_then(|signer| {
+                            add_signature(&mut tx, &signer.pubkey, signer.signature)
+                        });
+                        if let Err(err) = added {
+                            print_error(err.to_string());
+                        }
+                    }
+                }
+
+                let sent =
+                    show_spinner(self.spinner_msg(), process_send_transaction(ctx, &tx)).await?;
+                ctx.output_format().render(&sent)?;
             }
-            TransactionCommand::GoBack => return CommandFlow::GoBack,
+            TransactionCommand::SignTransaction(args) => {
+                let encoding = args.encoding.unwrap_or_else(|| {
+                    prompt_select_data(
+                        "Select encoding format:",
+                        vec![UiTransactionEncoding::Base64, UiTransactionEncoding::Base58],
+                    )
+                });
+                let encoded_tx: String = args
+                    .transaction
+                    .clone()
+                    .unwrap_or_else(|| prompt_input_data("Enter encoded transaction:"));
+
+                let mut tx = decode_transaction(encoding, &encoded_tx).map_err(report_error)?;
+
+                let keypair_paths = if args.keypair.is_empty() {
+                    if args.transaction.is_none() {
+                        println!(
+                            "{} {}",
+                            style("Missing signatures from:").yellow(),
+                            format_signers(&missing_signers(&tx))
+                        );
+                        vec![prompt_keypair_path("Enter Keypair Path to sign with:", ctx)]
+                    } else {
+                        vec![ctx.keypair_path().clone()]
+                    }
+                } else {
+                    args.keypair.clone()
+                };
+
+                sign_with_keypairs(&mut tx, &keypair_paths).map_err(report_error)?;
+
+                let send = missing_signers(&tx).is_empty()
+                    && (args.send
+                        || (args.transaction.is_none()
+                            && Confirm::new(
+                                "All signatures collected. Send the transaction now?",
+                            )
+                            .with_default(false)
+                            .prompt()
+                            .unwrap_or(false)));
+
+                let outcome = if send {
+                    let sent = show_spinner(self.spinner_msg(), process_send_transaction(ctx, &tx))
+                        .await?;
+                    TxOutcome::Sent(sent)
+                } else {
+                    let mut signed = SignedTransactionOutput::new(&tx)?;
+                    if let Some(path) = &args.export {
+                        signed.export(path).map_err(report_error)?;
+                    }
+                    TxOutcome::SignedOnly(signed)
+                };
+                ctx.output_format().render(&outcome)?;
+            }
+            TransactionCommand::GoBack => return Ok(CommandFlow::GoBack),
         }
 
-        CommandFlow::Process(())
+        Ok(CommandFlow::Process(()))
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConfirmationOutput {
+    pub signature: String,
+    pub confirmed: bool,
+}
+
+impl CommandOutput for ConfirmationOutput {
+    fn print_table(&self) {
+        let status_styled = if self.confirmed {
+            style("Confirmed").green()
+        } else {
+            style("Not Confirmed").yellow()
+        };
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_header(vec![
+                Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
+            ])
+            .add_row(vec![Cell::new("Signature"), Cell::new(&self.signature)])
+            .add_row(vec![Cell::new("Status"), Cell::new(status_styled)]);
+
+        println!("\n{}", style("TRANSACTION CONFIRMATION").green().bold());
+        println!("{}", table);
     }
 }
 
 async fn process_check_confirmation(
     ctx: &ScillaContext,
     signature: &Signature,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<ConfirmationOutput> {
     let confirmed = ctx.rpc().confirm_transaction(signature).await?;
 
-    let status_styled = if confirmed {
-        style("Confirmed").green()
-    } else {
-        style("Not Confirmed").yellow()
-    };
+    Ok(ConfirmationOutput {
+        signature: signature.to_string(),
+        confirmed,
+    })
+}
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
-        ])
-        .add_row(vec![Cell::new("Signature"), Cell::new(signature)])
-        .add_row(vec![Cell::new("Status"), Cell::new(status_styled)]);
+#[derive(Debug, Serialize)]
+pub struct TransactionStatusOutput {
+    pub signature: String,
+    pub slot: u64,
+    /// `None` once the transaction is finalized
+    pub confirmations: Option<usize>,
+    pub confirmation_status: Option<TransactionConfirmationStatus>,
+    pub err: Option<String>,
+}
 
-    println!("\n{}", style("TRANSACTION CONFIRMATION").green().bold());
-    println!("{}", table);
+impl CommandOutput for TransactionStatusOutput {
+    fn print_table(&self) {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_header(vec![
+                Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
+            ])
+            .add_row(vec![Cell::new("Signature"), Cell::new(&self.signature)])
+            .add_row(vec![Cell::new("Slot"), Cell::new(self.slot)]);
+
+        if let Some(confirmations) = self.confirmations {
+            table.add_row(vec![Cell::new("Confirmations"), Cell::new(confirmations)]);
+        } else {
+            table.add_row(vec![
+                Cell::new("Confirmations"),
+                Cell::new(style("Finalized").green()),
+            ]);
+        }
 
-    Ok(())
+        if let Some(confirmation_status) = &self.confirmation_status {
+            table.add_row(vec![
+                Cell::new("Confirmation Status"),
+                Cell::new(match confirmation_status {
+                    TransactionConfirmationStatus::Processed => {
+                        style("Processed").yellow().to_string()
+                    }
+                    TransactionConfirmationStatus::Confirmed => {
+                        style("Confirmed").cyan().to_string()
+                    }
+                    TransactionConfirmationStatus::Finalized => {
+                        style("Finalized").green().to_string()
+                    }
+                }),
+            ]);
+        }
+
+        table.add_row(vec![
+            Cell::new("Status"),
+            Cell::new(match &self.err {
+                None => style("Success").green().to_string(),
+                Some(err) => style(format!("Error: {err}")).red().to_string(),
+            }),
+        ]);
+
+        println!("\n{}", style("TRANSACTION STATUS").green().bold());
+        println!("{}", table);
+    }
 }
 
 async fn process_fetch_transaction_status(
     ctx: &ScillaContext,
     signature: &Signature,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<TransactionStatusOutput> {
     let status = ctx
         .rpc()
         .get_signature_statuses_with_history(&[*signature])
@@ -144,63 +390,139 @@ async fn process_fetch_transaction_status(
         anyhow::bail!("Transaction not found");
     };
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
-        ])
-        .add_row(vec![Cell::new("Signature"), Cell::new(signature)])
-        .add_row(vec![Cell::new("Slot"), Cell::new(tx_status.slot)]);
-
-    if let Some(confirmations) = tx_status.confirmations {
-        table.add_row(vec![Cell::new("Confirmations"), Cell::new(confirmations)]);
-    } else {
-        table.add_row(vec![
-            Cell::new("Confirmations"),
-            Cell::new(style("Finalized").green()),
-        ]);
-    }
+    Ok(TransactionStatusOutput {
+        signature: signature.to_string(),
+        slot: tx_status.slot,
+        confirmations: tx_status.confirmations,
+        confirmation_status: tx_status.confirmation_status.clone(),
+        err: tx_status.err.as_ref().map(|err| format!("{err:?}")),
+    })
+}
 
-    if let Some(confirmation_status) = &tx_status.confirmation_status {
-        table.add_row(vec![
-            Cell::new("Confirmation Status"),
-            Cell::new(match confirmation_status {
-                solana_transaction_status::TransactionConfirmationStatus::Processed => {
-                    style("Processed").yellow().to_string()
-                }
-                solana_transaction_status::TransactionConfirmationStatus::Confirmed => {
-                    style("Confirmed").cyan().to_string()
-                }
-                solana_transaction_status::TransactionConfirmationStatus::Finalized => {
-                    style("Finalized").green().to_string()
-                }
-            }),
-        ]);
-    }
+#[derive(Debug, Serialize)]
+pub struct AccountKeyOutput {
+    pub pubkey: String,
+    /// Only known for parsed messages
+    pub signer: Option<bool>,
+    /// Only known for parsed messages
+    pub writable: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TransactionMessageOutput {
+    pub parsed: bool,
+    pub recent_blockhash: String,
+    pub account_keys: Vec<AccountKeyOutput>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TransactionDetailsOutput {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub fee: Option<u64>,
+    pub err: Option<String>,
+    pub message: TransactionMessageOutput,
+}
+
+impl CommandOutput for TransactionDetailsOutput {
+    fn print_table(&self) {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_header(vec![
+                Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
+            ])
+            .add_row(vec![Cell::new("Signature"), Cell::new(&self.signature)])
+            .add_row(vec![Cell::new("Slot"), Cell::new(self.slot)]);
+
+        if let Some(block_time) = self.block_time {
+            table.add_row(vec![Cell::new("Block Time"), Cell::new(block_time)]);
+        }
+
+        if let Some(fee) = self.fee {
+            table.add_row(vec![Cell::new("Fee (lamports)"), Cell::new(fee)]);
+            table.add_row(vec![
+                Cell::new("Status"),
+                Cell::new(match &self.err {
+                    None => style("Success").green().to_string(),
+                    Some(err) => style(format!("Error: {err}")).red().to_string(),
+                }),
+            ]);
+        }
+
+        println!("\n{}", style("TRANSACTION DETAILS").green().bold());
+        println!("{}", table);
 
-    table.add_row(vec![
-        Cell::new("Status"),
-        Cell::new(if tx_status.err.is_none() {
-            style("Success").green().to_string()
+        let message = &self.message;
+        if message.parsed {
+            println!("\n{}", style("TRANSACTION MESSAGE").cyan().bold());
         } else {
-            style(format!("Error: {:?}", tx_status.err))
-                .red()
-                .to_string()
-        }),
-    ]);
+            println!("\n{}", style("TRANSACTION MESSAGE (Raw)").cyan().bold());
+        }
 
-    println!("\n{}", style("TRANSACTION STATUS").green().bold());
-    println!("{}", table);
+        let mut msg_table = Table::new();
+        msg_table
+            .load_preset(UTF8_FULL)
+            .set_header(vec![
+                Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
+            ])
+            .add_row(vec![
+                Cell::new("Account Keys"),
+                Cell::new(message.account_keys.len()),
+            ])
+            .add_row(vec![
+                Cell::new("Recent Blockhash"),
+                Cell::new(&message.recent_blockhash),
+            ]);
+
+        println!("{}", msg_table);
+
+        if message.account_keys.is_empty() {
+            return;
+        }
 
-    Ok(())
+        println!("\n{}", style("ACCOUNT KEYS").cyan().bold());
+        if message.parsed {
+            let mut accounts_table = Table::new();
+            accounts_table.load_preset(UTF8_FULL).set_header(vec![
+                Cell::new("Index").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Pubkey").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Signer").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Writable").add_attribute(comfy_table::Attribute::Bold),
+            ]);
+
+            for (idx, account) in message.account_keys.iter().enumerate() {
+                accounts_table.add_row(vec![
+                    Cell::new(idx),
+                    Cell::new(&account.pubkey),
+                    Cell::new(if account.signer == Some(true) {
+                        "✓"
+                    } else {
+                        ""
+                    }),
+                    Cell::new(if account.writable == Some(true) {
+                        "✓"
+                    } else {
+                        ""
+                    }),
+                ]);
+            }
+            println!("{}", accounts_table);
+        } else {
+            for (idx, key) in message.account_keys.iter().enumerate() {
+                println!("  {}. {}", idx, key.pubkey);
+            }
+        }
+    }
 }
 
 async fn process_fetch_transaction(
     ctx: &ScillaContext,
     signature: &Signature,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<TransactionDetailsOutput> {
     let tx = ctx
         .rpc()
         .get_transaction_with_config(
@@ -213,136 +535,113 @@ async fn process_fetch_transaction(
         )
         .await?;
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
-        ])
-        .add_row(vec![Cell::new("Signature"), Cell::new(signature)])
-        .add_row(vec![Cell::new("Slot"), Cell::new(tx.slot)]);
-
-    if let Some(block_time) = tx.block_time {
-        table.add_row(vec![Cell::new("Block Time"), Cell::new(block_time)]);
-    }
-
-    if let Some(meta) = &tx.transaction.meta {
-        table.add_row(vec![Cell::new("Fee (lamports)"), Cell::new(meta.fee)]);
-        table.add_row(vec![
-            Cell::new("Status"),
-            Cell::new(if meta.err.is_none() {
-                style("Success").green().to_string()
-            } else {
-                style(format!("Error: {:?}", meta.err)).red().to_string()
-            }),
-        ]);
-    }
-
-    println!("\n{}", style("TRANSACTION DETAILS").green().bold());
-    println!("{}", table);
-
     let EncodedTransaction::Json(ui_tx) = &tx.transaction.transaction else {
         anyhow::bail!("Transaction encoding is not JSON");
     };
 
-    match &ui_tx.message {
-        UiMessage::Parsed(parsed_msg) => {
-            println!("\n{}", style("TRANSACTION MESSAGE").cyan().bold());
-
-            let mut msg_table = Table::new();
-            msg_table
-                .load_preset(UTF8_FULL)
-                .set_header(vec![
-                    Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
-                    Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
-                ])
-                .add_row(vec![
-                    Cell::new("Account Keys"),
-                    Cell::new(parsed_msg.account_keys.len()),
-                ])
-                .add_row(vec![
-                    Cell::new("Recent Blockhash"),
-                    Cell::new(&parsed_msg.recent_blockhash),
-                ]);
-
-            println!("{}", msg_table);
-
-            if !parsed_msg.account_keys.is_empty() {
-                println!("\n{}", style("ACCOUNT KEYS").cyan().bold());
-                let mut accounts_table = Table::new();
-                accounts_table.load_preset(UTF8_FULL).set_header(vec![
-                    Cell::new("Index").add_attribute(comfy_table::Attribute::Bold),
-                    Cell::new("Pubkey").add_attribute(comfy_table::Attribute::Bold),
-                    Cell::new("Signer").add_attribute(comfy_table::Attribute::Bold),
-                    Cell::new("Writable").add_attribute(comfy_table::Attribute::Bold),
-                ]);
-
-                for (idx, account) in parsed_msg.account_keys.iter().enumerate() {
-                    accounts_table.add_row(vec![
-                        Cell::new(idx),
-                        Cell::new(&account.pubkey),
-                        Cell::new(if account.signer { "✓" } else { "" }),
-                        Cell::new(if account.writable { "✓" } else { "" }),
-                    ]);
-                }
-                println!("{}", accounts_table);
-            }
-        }
-        UiMessage::Raw(raw_msg) => {
-            println!("\n{}", style("TRANSACTION MESSAGE (Raw)").cyan().bold());
+    let message = match &ui_tx.message {
+        UiMessage::Parsed(parsed_msg) => TransactionMessageOutput {
+            parsed: true,
+            recent_blockhash: parsed_msg.recent_blockhash.clone(),
+            account_keys: parsed_msg
+                .account_keys
+                .iter()
+                .map(|account| AccountKeyOutput {
+                    pubkey: account.pubkey.clone(),
+                    signer: Some(account.signer),
+                    writable: Some(account.writable),
+                })
+                .collect(),
+        },
+        UiMessage::Raw(raw_msg) => TransactionMessageOutput {
+            parsed: false,
+            recent_blockhash: raw_msg.recent_blockhash.clone(),
+            account_keys: raw_msg
+                .account_keys
+                .iter()
+                .map(|key| AccountKeyOutput {
+                    pubkey: key.clone(),
+                    signer: None,
+                    writable: None,
+                })
+                .collect(),
+        },
+    };
 
-            let mut msg_table = Table::new();
-            msg_table
-                .load_preset(UTF8_FULL)
-                .set_header(vec![
-                    Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
-                    Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
-                ])
-                .add_row(vec![
-                    Cell::new("Account Keys"),
-                    Cell::new(raw_msg.account_keys.len()),
-                ])
-                .add_row(vec![
-                    Cell::new("Recent Blockhash"),
-                    Cell::new(&raw_msg.recent_blockhash),
-                ]);
+    let meta = tx.transaction.meta.as_ref();
+
+    Ok(TransactionDetailsOutput {
+        signature: signature.to_string(),
+        slot: tx.slot,
+        block_time: tx.block_time,
+        fee: meta.map(|meta| meta.fee),
+        err: meta
+            .and_then(|meta| meta.err.as_ref())
+            .map(|err| format!("{err:?}")),
+        message,
+    })
+}
 
-            println!("{}", msg_table);
+#[derive(Debug, Serialize)]
+pub struct SendTransactionOutput {
+    pub signature: String,
+}
 
-            if !raw_msg.account_keys.is_empty() {
-                println!("\n{}", style("ACCOUNT KEYS").cyan().bold());
-                for (idx, key) in raw_msg.account_keys.iter().enumerate() {
-                    println!("  {}. {}", idx, key);
-                }
-            }
-        }
+impl CommandOutput for SendTransactionOutput {
+    fn print_table(&self) {
+        println!(
+            "{} {}",
+            style("Transaction sent successfully!").green().bold(),
+            style(&self.signature).cyan()
+        );
     }
+}
 
+fn sign_with_keypairs(
+    tx: &mut VersionedTransaction,
+    keypair_paths: &[PathBuf],
+) -> anyhow::Result<()> {
+    for path in keypair_paths {
+        let keypair = read_keypair_from_path(path)?;
+        sign_transaction(tx, &keypair)?;
+    }
     Ok(())
 }
 
-async fn process_send_transaction(
-    ctx: &ScillaContext,
+/// Decodes the transaction and adds the signatures passed on the command
+/// line.
+fn decode_with_signatures(
     encoding: UiTransactionEncoding,
     encoded_tx: &str,
-) -> anyhow::Result<()> {
-    let tx_bytes = match encoding {
-        UiTransactionEncoding::Base64 => decode_base64(encoded_tx)?,
-        UiTransactionEncoding::Base58 => decode_base58(encoded_tx)?,
-        _ => unreachable!("The available encoding options are Base64 and Base58"),
-    };
+    args: &SendTransactionArgs,
+) -> anyhow::Result<VersionedTransaction> {
+    let mut tx = decode_transaction(encoding, encoded_tx)?;
+    for encoded in &args.merge {
+        merge_signatures(&mut tx, &decode_transaction(encoding, encoded)?)?;
+    }
+    for signer in &args.signer {
+        add_signature(&mut tx, &signer.pubkey, signer.signature)?;
+    }
+    Ok(tx)
+}
 
-    let tx: VersionedTransaction =
-        bincode_deserialize(&tx_bytes, "encoded transaction to VersionedTransaction")?;
+async fn process_send_transaction(
+    ctx: &ScillaContext,
+    tx: &VersionedTransaction,
+) -> anyhow::Result<SendTransactionOutput> {
+    let missing = missing_signers(tx);
+    if !missing.is_empty() {
+        bail!(
+            "Transaction is missing signatures from: {}",
+            format_signers(&missing)
+        );
+    }
 
-    let signature = ctx.rpc().send_transaction(&tx).await?;
+    preview_and_confirm(ctx, tx).await?;
 
-    println!(
-        "{} {}",
-        style("Transaction sent successfully!").green().bold(),
-        style(signature).cyan()
-    );
+    let signature = ctx.rpc().send_transaction(tx).await?;
 
-    Ok(())
+    Ok(SendTransactionOutput {
+        signature: signature.to_string(),
+    })
 }
@@ -2,16 +2,20 @@ use {
     crate::{
         ScillaContext,
         commands::CommandFlow,
+        error::ScillaResult,
         misc::helpers::{
-            Commission, SolAmount, build_and_send_tx, fetch_account_with_epoch, lamports_to_sol,
-            read_keypair_from_path,
+            Commission, SolAmount, TxArgs, TxOutcome, build_and_send_tx, fetch_account_with_epoch,
+            lamports_to_sol, read_keypair_from_path,
         },
+        output::CommandOutput,
         prompt::{prompt_input_data, prompt_keypair_path},
         ui::show_spinner,
     },
     anyhow::{anyhow, bail},
+    clap::{Args, Subcommand},
     comfy_table::{Cell, Table, presets::UTF8_FULL},
     console::style,
+    serde::Serialize,
     solana_keypair::Signer,
     solana_pubkey::Pubkey,
     solana_rpc_client_api::config::RpcGetVoteAccountsConfig,
@@ -23,24 +27,103 @@ use {
 };
 
 /// Commands related to validator/vote account operations
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Subcommand)]
 pub enum VoteCommand {
-    CreateVoteAccount,
-    AuthorizeVoter,
-    WithdrawFromVoteAccount,
-    ShowVoteAccount,
-    CloseVoteAccount,
+    /// Initialize a new vote account
+    CreateVoteAccount(CreateVoteAccountArgs),
+    /// Change the authorized voter
+    AuthorizeVoter(AuthorizeVoterArgs),
+    /// Withdraw SOL from a vote account
+    WithdrawFromVoteAccount(WithdrawFromVoteAccountArgs),
+    /// Display vote account info
+    ShowVoteAccount(ShowVoteAccountArgs),
+    /// Withdraw the full balance and close a vote account
+    CloseVoteAccount(CloseVoteAccountArgs),
+    #[command(skip)]
     GoBack,
 }
 
+#[derive(Debug, Clone, Default, Args)]
+pub struct CreateVoteAccountArgs {
+    /// Vote account keypair path
+    #[arg(long)]
+    pub vote_account: Option<PathBuf>,
+    /// Validator identity keypair path
+    #[arg(long)]
+    pub identity: Option<PathBuf>,
+    /// Authorized withdrawer keypair path
+    #[arg(long)]
+    pub withdrawer: Option<PathBuf>,
+    /// Commission percentage (0-100)
+    #[arg(long)]
+    pub commission: Option<Commission>,
+    #[command(flatten)]
+    pub tx: TxArgs,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct AuthorizeVoterArgs {
+    /// Vote account address
+    #[arg(long)]
+    pub vote_account: Option<Pubkey>,
+    /// Current authorized voter or withdrawer keypair path
+    #[arg(long)]
+    pub authorized: Option<PathBuf>,
+    /// New authorized voter address
+    #[arg(long)]
+    pub new_authorized: Option<Pubkey>,
+    #[command(flatten)]
+    pub tx: TxArgs,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct WithdrawFromVoteAccountArgs {
+    /// Vote account address
+    #[arg(long)]
+    pub vote_account: Option<Pubkey>,
+    /// Authorized withdrawer keypair path
+    #[arg(long)]
+    pub withdrawer: Option<PathBuf>,
+    /// Recipient address
+    #[arg(long)]
+    pub recipient: Option<Pubkey>,
+    /// Amount to withdraw in SOL
+    #[arg(long)]
+    pub amount: Option<SolAmount>,
+    #[command(flatten)]
+    pub tx: TxArgs,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct ShowVoteAccountArgs {
+    /// Vote account address
+    #[arg(long)]
+    pub vote_account: Option<Pubkey>,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct CloseVoteAccountArgs {
+    /// Vote account address
+    #[arg(long)]
+    pub vote_account: Option<Pubkey>,
+    /// Withdraw authority keypair path
+    #[arg(long)]
+    pub withdrawer: Option<PathBuf>,
+    /// Destination address for the remaining balance
+    #[arg(long)]
+    pub destination: Option<Pubkey>,
+    #[command(flatten)]
+    pub tx: TxArgs,
+}
+
 impl VoteCommand {
     pub fn spinner_msg(&self) -> &'static str {
         match self {
-            VoteCommand::CreateVoteAccount => "Creating vote account…",
-            VoteCommand::AuthorizeVoter => "Authorizing voter…",
-            VoteCommand::WithdrawFromVoteAccount => "Withdrawing SOL from vote account…",
-            VoteCommand::ShowVoteAccount => "Fetching vote account details…",
-            VoteCommand::CloseVoteAccount => "Closing vote account…",
+            VoteCommand::CreateVoteAccount(_) => "Creating vote account…",
+            VoteCommand::AuthorizeVoter(_) => "Authorizing voter…",
+            VoteCommand::WithdrawFromVoteAccount(_) => "Withdrawing SOL from vote account…",
+            VoteCommand::ShowVoteAccount(_) => "Fetching vote account details…",
+            VoteCommand::CloseVoteAccount(_) => "Closing vote account…",
             VoteCommand::GoBack => "Going back…",
         }
     }
@@ -49,11 +132,11 @@ impl VoteCommand {
 impl fmt::Display for VoteCommand {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let text = match self {
-            VoteCommand::CreateVoteAccount => "Create vote account",
-            VoteCommand::AuthorizeVoter => "Authorize voter",
-            VoteCommand::WithdrawFromVoteAccount => "Withdraw from vote account",
-            VoteCommand::ShowVoteAccount => "Show vote account",
-            VoteCommand::CloseVoteAccount => "Close vote account",
+            VoteCommand::CreateVoteAccount(_) => "Create vote account",
+            VoteCommand::AuthorizeVoter(_) => "Authorize voter",
+            VoteCommand::WithdrawFromVoteAccount(_) => "Withdraw from vote account",
+            VoteCommand::ShowVoteAccount(_) => "Show vote account",
+            VoteCommand::CloseVoteAccount(_) => "Close vote account",
             VoteCommand::GoBack => "Go back",
         };
         write!(f, "{text}")
@@ -61,19 +144,25 @@ impl fmt::Display for VoteCommand {
 }
 
 impl VoteCommand {
-    pub async fn process_command(&self, ctx: &ScillaContext) -> CommandFlow<()> {
+    pub async fn process_command(&self, ctx: &ScillaContext) -> ScillaResult<()> {
         match self {
-            VoteCommand::CreateVoteAccount => {
-                let vote_account_keypair_path =
-                    prompt_keypair_path("Enter Vote Account Keypair Path:", ctx);
-                let identity_keypair_path =
-                    prompt_keypair_path("Enter Identity Keypair Path:", ctx);
-                let withdraw_keypair_path =
-                    prompt_keypair_path("Enter Withdraw Keypair Path:", ctx);
-                let commission: Commission =
-                    prompt_input_data("Enter Commission 0-100 (default 0):");
-
-                show_spinner(
+            VoteCommand::CreateVoteAccount(args) => {
+                let vote_account_keypair_path = args.vote_account.clone().unwrap_or_else(|| {
+                    prompt_keypair_path("Enter Vote Account Keypair Path:", ctx)
+                });
+                let identity_keypair_path = args
+                    .identity
+                    .clone()
+                    .unwrap_or_else(|| prompt_keypair_path("Enter Identity Keypair Path:", ctx));
+                let withdraw_keypair_path = args
+                    .withdrawer
+                    .clone()
+                    .unwrap_or_else(|| prompt_keypair_path("Enter Withdraw Keypair Path:", ctx));
+                let commission: Commission = args
+                    .commission
+                    .unwrap_or_else(|| prompt_input_data("Enter Commission 0-100 (default 0):"));
+
+                let created = show_spinner(
                     self.spinner_msg(),
                     process_create_vote_account(
                         ctx,
@@ -81,37 +170,54 @@ impl VoteCommand {
                         &identity_keypair_path,
                         &withdraw_keypair_path,
                         commission.value(),
+                        &args.tx,
                     ),
                 )
-                .await;
+                .await?;
+                ctx.output_format().render(&created)?;
             }
-            VoteCommand::AuthorizeVoter => {
-                let vote_account_pubkey: Pubkey = prompt_input_data("Enter Vote Account Address:");
-                let authorized_keypair_path =
-                    prompt_keypair_path("Enter Authorized Keypair Path:", ctx);
-                let new_authorized_pubkey: Pubkey =
-                    prompt_input_data("Enter New Authorized Address:");
-
-                show_spinner(
+            VoteCommand::AuthorizeVoter(args) => {
+                let vote_account_pubkey: Pubkey = args
+                    .vote_account
+                    .unwrap_or_else(|| prompt_input_data("Enter Vote Account Address:"));
+                let authorized_keypair_path = args
+                    .authorized
+                    .clone()
+                    .unwrap_or_else(|| prompt_keypair_path("Enter Authorized Keypair Path:", ctx));
+                let new_authorized_pubkey: Pubkey = args
+                    .new_authorized
+                    .unwrap_or_else(|| prompt_input_data("Enter New Authorized Address:"));
+
+                let authorized = show_spinner(
                     self.spinner_msg(),
                     process_authorize_voter(
                         ctx,
                         &vote_account_pubkey,
                         &authorized_keypair_path,
                         &new_authorized_pubkey,
+                        &args.tx,
                     ),
                 )
-                .await;
+                .await?;
+                ctx.output_format().render(&authorized)?;
             }
-            VoteCommand::WithdrawFromVoteAccount => {
-                let vote_account_pubkey: Pubkey = prompt_input_data("Enter Vote Account Address:");
+            VoteCommand::WithdrawFromVoteAccount(args) => {
+                let vote_account_pubkey: Pubkey = args
+                    .vote_account
+                    .unwrap_or_else(|| prompt_input_data("Enter Vote Account Address:"));
                 let authorized_withdrawer_keypair_path =
-                    prompt_keypair_path("Enter Authorized Withdraw Keypair Path:", ctx);
-                let recipient_address: Pubkey = prompt_input_data("Enter Recipient Address:");
-
-                let amount: SolAmount = prompt_input_data("Enter withdraw amount in SOL:");
-
-                show_spinner(
+                    args.withdrawer.clone().unwrap_or_else(|| {
+                        prompt_keypair_path("Enter Authorized Withdraw Keypair Path:", ctx)
+                    });
+                let recipient_address: Pubkey = args
+                    .recipient
+                    .unwrap_or_else(|| prompt_input_data("Enter Recipient Address:"));
+
+                let amount: SolAmount = args
+                    .amount
+                    .unwrap_or_else(|| prompt_input_data("Enter withdraw amount in SOL:"));
+
+                let withdrawn = show_spinner(
                     self.spinner_msg(),
                     process_sol_withdraw_from_vote_account(
                         ctx,
@@ -119,39 +225,73 @@ impl VoteCommand {
                         &authorized_withdrawer_keypair_path,
                         &recipient_address,
                         amount.to_lamports(),
+                        &args.tx,
                     ),
                 )
-                .await;
+                .await?;
+                ctx.output_format().render(&withdrawn)?;
             }
-            VoteCommand::ShowVoteAccount => {
-                let vote_account_pubkey: Pubkey = prompt_input_data("Enter Vote Account Address:");
-                show_spinner(
+            VoteCommand::ShowVoteAccount(args) => {
+                let vote_account_pubkey: Pubkey = args
+                    .vote_account
+                    .unwrap_or_else(|| prompt_input_data("Enter Vote Account Address:"));
+                let vote_account = show_spinner(
                     self.spinner_msg(),
                     process_fetch_vote_account(ctx, &vote_account_pubkey),
                 )
-                .await;
+                .await?;
+                ctx.output_format().render(&vote_account)?;
             }
-            VoteCommand::CloseVoteAccount => {
-                let vote_account_pubkey: Pubkey = prompt_input_data("Enter Vote Account Address:");
+            VoteCommand::CloseVoteAccount(args) => {
+                let vote_account_pubkey: Pubkey = args
+                    .vote_account
+                    .unwrap_or_else(|| prompt_input_data("Enter Vote Account Address:"));
                 let withdraw_authority_keypair_path =
-                    prompt_keypair_path("Enter Withdraw Authority Keypair Path:", ctx);
-                let destination_pubkey: Pubkey = prompt_input_data("Enter Destination Address:");
-
-                show_spinner(
+                    args.withdrawer.clone().unwrap_or_else(|| {
+                        prompt_keypair_path("Enter Withdraw Authority Keypair Path:", ctx)
+                    });
+                let destination_pubkey: Pubkey = args
+                    .destination
+                    .unwrap_or_else(|| prompt_input_data("Enter Destination Address:"));
+
+                let closed = show_spinner(
                     self.spinner_msg(),
                     close_vote_account(
                         ctx,
                         &vote_account_pubkey,
                         &withdraw_authority_keypair_path,
                         &destination_pubkey,
+                        &args.tx,
                     ),
                 )
-                .await;
+                .await?;
+                ctx.output_format().render(&closed)?;
             }
-            VoteCommand::GoBack => return CommandFlow::GoBack,
+            VoteCommand::GoBack => return Ok(CommandFlow::GoBack),
         }
 
-        CommandFlow::Process(())
+        Ok(CommandFlow::Process(()))
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateVoteAccountOutput {
+    pub signature: String,
+    pub vote_account: String,
+}
+
+impl CommandOutput for CreateVoteAccountOutput {
+    fn print_table(&self) {
+        println!(
+            "{} {}",
+            style("Vote account created successfully!").green().bold(),
+            style(format!("Signature: {}", self.signature)).cyan()
+        );
+        println!(
+            "{} {}",
+            style("Vote account address:").green(),
+            style(&self.vote_account).cyan()
+        );
     }
 }
 
@@ -161,7 +301,15 @@ async fn process_create_vote_account(
     identity_keypair_path: &PathBuf,
     withdraw_keypair_path: &PathBuf,
     commission: u8,
-) -> anyhow::Result<()> {
+    tx_args: &TxArgs,
+) -> anyhow::Result<TxOutcome<CreateVoteAccountOutput>> {
+    if tx_args.sign_only {
+        bail!(
+            "Creating a vote account needs the rent-exempt minimum from RPC, so it can't be \
+             signed with --sign-only"
+        );
+    }
+
     let vote_account_keypair = read_keypair_from_path(vote_account_keypair_path)?;
     let identity_keypair = read_keypair_from_path(identity_keypair_path)?;
     let withdraw_keypair = read_keypair_from_path(withdraw_keypair_path)?;
@@ -212,25 +360,39 @@ async fn process_create_vote_account(
         CreateVoteAccountConfig::default(),
     );
 
-    let signature = build_and_send_tx(
+    let signature = match build_and_send_tx(
         ctx,
         &instructions,
         &[ctx.keypair(), &vote_account_keypair, &identity_keypair],
+        tx_args,
     )
-    .await?;
+    .await?
+    {
+        TxOutcome::Sent(signature) => signature,
+        TxOutcome::SignedOnly(signed) => return Ok(TxOutcome::SignedOnly(signed)),
+    };
 
-    println!(
-        "{} {}",
-        style("Vote account created successfully!").green().bold(),
-        style(format!("Signature: {signature}")).cyan()
-    );
-    println!(
-        "{} {}",
-        style("Vote account address:").green(),
-        style(vote_account_pubkey).cyan()
-    );
+    Ok(TxOutcome::Sent(CreateVoteAccountOutput {
+        signature: signature.to_string(),
+        vote_account: vote_account_pubkey.to_string(),
+    }))
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuthorizeVoterOutput {
+    pub signature: String,
+    pub vote_account: String,
+    pub new_authorized_voter: String,
+}
 
-    Ok(())
+impl CommandOutput for AuthorizeVoterOutput {
+    fn print_table(&self) {
+        println!(
+            "{} {}",
+            style("Signature:").green().bold(),
+            style(&self.signature).cyan()
+        );
+    }
 }
 
 async fn process_authorize_voter(
@@ -238,7 +400,8 @@ async fn process_authorize_voter(
     vote_account_pubkey: &Pubkey,
     authorized_keypair_path: &PathBuf,
     new_authorized_pubkey: &Pubkey,
-) -> anyhow::Result<()> {
+    tx_args: &TxArgs,
+) -> anyhow::Result<TxOutcome<AuthorizeVoterOutput>> {
     let authorized = read_keypair_from_path(authorized_keypair_path)?;
     let authorized_pubkey = authorized.pubkey();
 
@@ -276,15 +439,35 @@ async fn process_authorize_voter(
         VoteAuthorize::Voter,
     );
 
-    let signature = build_and_send_tx(ctx, &[vote_ix], &[ctx.keypair(), &authorized]).await?;
+    let signature =
+        match build_and_send_tx(ctx, &[vote_ix], &[ctx.keypair(), &authorized], tx_args).await? {
+            TxOutcome::Sent(signature) => signature,
+            TxOutcome::SignedOnly(signed) => return Ok(TxOutcome::SignedOnly(signed)),
+        };
 
-    println!(
-        "{} {}",
-        style("Signature:").green().bold(),
-        style(signature).cyan()
-    );
+    Ok(TxOutcome::Sent(AuthorizeVoterOutput {
+        signature: signature.to_string(),
+        vote_account: vote_account_pubkey.to_string(),
+        new_authorized_voter: new_authorized_pubkey.to_string(),
+    }))
+}
+
+#[derive(Debug, Serialize)]
+pub struct VoteWithdrawOutput {
+    pub signature: String,
+    pub vote_account: String,
+    pub recipient: String,
+    pub lamports: u64,
+}
 
-    Ok(())
+impl CommandOutput for VoteWithdrawOutput {
+    fn print_table(&self) {
+        println!(
+            "{} {}",
+            style("Signature:").green().bold(),
+            style(&self.signature).cyan()
+        );
+    }
 }
 
 async fn process_sol_withdraw_from_vote_account(
@@ -293,29 +476,33 @@ async fn process_sol_withdraw_from_vote_account(
     authorized_withdrawer_keypair_path: &PathBuf,
     recipient_address: &Pubkey,
     amount: u64,
-) -> anyhow::Result<()> {
+    tx_args: &TxArgs,
+) -> anyhow::Result<TxOutcome<VoteWithdrawOutput>> {
     let authorized_withdrawer = read_keypair_from_path(authorized_withdrawer_keypair_path)?;
     let withdrawer_pubkey = authorized_withdrawer.pubkey();
 
-    let vote_account = ctx
-        .rpc()
-        .get_account(vote_account_pubkey)
-        .await
-        .map_err(|_| anyhow!("{vote_account_pubkey} account does not exist"))?;
+    // The account state can only be checked online, so skip it when signing offline
+    if !tx_args.sign_only {
+        let vote_account = ctx
+            .rpc()
+            .get_account(vote_account_pubkey)
+            .await
+            .map_err(|_| anyhow!("{vote_account_pubkey} account does not exist"))?;
 
-    if vote_account.owner != solana_vote_interface::program::id() {
-        bail!("{vote_account_pubkey} is not a vote account");
-    }
+        if vote_account.owner != solana_vote_interface::program::id() {
+            bail!("{vote_account_pubkey} is not a vote account");
+        }
 
-    let vote_state = VoteStateV4::deserialize(&vote_account.data, vote_account_pubkey)
-        .map_err(|_| anyhow!("Account data could not be deserialized to vote state"))?;
+        let vote_state = VoteStateV4::deserialize(&vote_account.data, vote_account_pubkey)
+            .map_err(|_| anyhow!("Account data could not be deserialized to vote state"))?;
 
-    if withdrawer_pubkey != vote_state.authorized_withdrawer {
-        bail!(
-            "Keypair {} is not the authorized withdrawer ({})",
-            withdrawer_pubkey,
-            vote_state.authorized_withdrawer
-        );
+        if withdrawer_pubkey != vote_state.authorized_withdrawer {
+            bail!(
+                "Keypair {} is not the authorized withdrawer ({})",
+                withdrawer_pubkey,
+                vote_state.authorized_withdrawer
+            );
+        }
     }
 
     let withdraw_ix = withdraw(
@@ -325,20 +512,42 @@ async fn process_sol_withdraw_from_vote_account(
         recipient_address,
     );
 
-    let signature = build_and_send_tx(
+    let signature = match build_and_send_tx(
         ctx,
         &[withdraw_ix],
         &[ctx.keypair(), &authorized_withdrawer],
+        tx_args,
     )
-    .await?;
+    .await?
+    {
+        TxOutcome::Sent(signature) => signature,
+        TxOutcome::SignedOnly(signed) => return Ok(TxOutcome::SignedOnly(signed)),
+    };
 
-    println!(
-        "{} {}",
-        style("Signature:").green().bold(),
-        style(signature).cyan()
-    );
+    Ok(TxOutcome::Sent(VoteWithdrawOutput {
+        signature: signature.to_string(),
+        vote_account: vote_account_pubkey.to_string(),
+        recipient: recipient_address.to_string(),
+        lamports: amount,
+    }))
+}
 
-    Ok(())
+#[derive(Debug, Serialize)]
+pub struct CloseVoteAccountOutput {
+    pub signature: String,
+    pub vote_account: String,
+    pub destination: String,
+    pub lamports: u64,
+}
+
+impl CommandOutput for CloseVoteAccountOutput {
+    fn print_table(&self) {
+        println!(
+            "{} {}",
+            style("Vote account closed! Signature:").green().bold(),
+            style(&self.signature).cyan()
+        );
+    }
 }
 
 async fn close_vote_account(
@@ -346,7 +555,15 @@ async fn close_vote_account(
     vote_account_pubkey: &Pubkey,
     withdraw_authority_keypair_path: &PathBuf,
     destination_pubkey: &Pubkey,
-) -> anyhow::Result<()> {
+    tx_args: &TxArgs,
+) -> anyhow::Result<TxOutcome<CloseVoteAccountOutput>> {
+    if tx_args.sign_only {
+        bail!(
+            "Closing a vote account withdraws its current balance, which needs RPC access. Use \
+             withdraw-from-vote-account with an explicit amount and --sign-only instead"
+        );
+    }
+
     let withdraw_authority = read_keypair_from_path(withdraw_authority_keypair_path)?;
     let vote_account_status = ctx
         .rpc()
@@ -382,22 +599,100 @@ async fn close_vote_account(
         destination_pubkey,
     );
 
-    let signature =
-        build_and_send_tx(ctx, &[withdraw_ix], &[ctx.keypair(), &withdraw_authority]).await?;
+    let signature = match build_and_send_tx(
+        ctx,
+        &[withdraw_ix],
+        &[ctx.keypair(), &withdraw_authority],
+        tx_args,
+    )
+    .await?
+    {
+        TxOutcome::Sent(signature) => signature,
+        TxOutcome::SignedOnly(signed) => return Ok(TxOutcome::SignedOnly(signed)),
+    };
 
-    println!(
-        "{} {}",
-        style("Vote account closed! Signature:").green().bold(),
-        style(signature).cyan()
-    );
+    Ok(TxOutcome::Sent(CloseVoteAccountOutput {
+        signature: signature.to_string(),
+        vote_account: vote_account_pubkey.to_string(),
+        destination: destination_pubkey.to_string(),
+        lamports: current_balance,
+    }))
+}
 
-    Ok(())
+#[derive(Debug, Serialize)]
+pub struct VoteAccountOutput {
+    pub address: String,
+    pub lamports: u64,
+    pub validator_identity: String,
+    pub vote_authority: String,
+    pub withdraw_authority: String,
+    pub credits: u64,
+    pub commission: u16,
+    pub root_slot: Option<u64>,
+    pub recent_timestamp: i64,
+    pub recent_timestamp_slot: u64,
+}
+
+impl CommandOutput for VoteAccountOutput {
+    fn print_table(&self) {
+        let balance_sol = lamports_to_sol(self.lamports);
+
+        let root_slot = match self.root_slot {
+            Some(slot) => slot.to_string(),
+            None => "~".to_string(),
+        };
+
+        let timestamp = chrono::DateTime::from_timestamp(self.recent_timestamp, 0)
+            .expect("Solana timestamp should always be in valid range")
+            .format("%Y-%m-%dT%H:%M:%SZ")
+            .to_string();
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_header(vec![
+                Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
+            ])
+            .add_row(vec![
+                Cell::new("Account Balance"),
+                Cell::new(format!("{balance_sol} SOL")),
+            ])
+            .add_row(vec![
+                Cell::new("Validator Identity"),
+                Cell::new(&self.validator_identity),
+            ])
+            .add_row(vec![
+                Cell::new("Vote Authority"),
+                Cell::new(&self.vote_authority),
+            ])
+            .add_row(vec![
+                Cell::new("Withdraw Authority"),
+                Cell::new(&self.withdraw_authority),
+            ])
+            .add_row(vec![Cell::new("Credits"), Cell::new(self.credits)])
+            .add_row(vec![
+                Cell::new("Commission"),
+                Cell::new(format!("{}%", self.commission)),
+            ])
+            .add_row(vec![Cell::new("Root Slot"), Cell::new(root_slot)])
+            .add_row(vec![
+                Cell::new("Recent Timestamp"),
+                Cell::new(format!(
+                    "{} from slot {}",
+                    timestamp, self.recent_timestamp_slot
+                )),
+            ]);
+
+        println!("\n{}", style("VOTE ACCOUNT INFORMATION").green().bold());
+        println!("{table}");
+    }
 }
 
 async fn process_fetch_vote_account(
     ctx: &ScillaContext,
     vote_account_pubkey: &Pubkey,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<VoteAccountOutput> {
     let vote_account = ctx
         .rpc()
         .get_account(vote_account_pubkey)
@@ -411,63 +706,22 @@ async fn process_fetch_vote_account(
     let vote_state = VoteStateV4::deserialize(&vote_account.data, vote_account_pubkey)
         .map_err(|_| anyhow!("Account data could not be deserialized to vote state"))?;
 
-    let balance_sol = lamports_to_sol(vote_account.lamports);
-
-    let root_slot = match vote_state.root_slot {
-        Some(slot) => slot.to_string(),
-        None => "~".to_string(),
-    };
-
-    let timestamp = chrono::DateTime::from_timestamp(vote_state.last_timestamp.timestamp, 0)
-        .expect("Solana timestamp should always be in valid range")
-        .format("%Y-%m-%dT%H:%M:%SZ")
-        .to_string();
-
     let vote_authority = vote_state
         .authorized_voters
         .last()
         .map(|(_, v)| v.to_string())
         .unwrap_or_else(|| vote_state.node_pubkey.to_string());
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
-        ])
-        .add_row(vec![
-            Cell::new("Account Balance"),
-            Cell::new(format!("{balance_sol} SOL")),
-        ])
-        .add_row(vec![
-            Cell::new("Validator Identity"),
-            Cell::new(vote_state.node_pubkey),
-        ])
-        .add_row(vec![Cell::new("Vote Authority"), Cell::new(vote_authority)])
-        .add_row(vec![
-            Cell::new("Withdraw Authority"),
-            Cell::new(vote_state.authorized_withdrawer),
-        ])
-        .add_row(vec![Cell::new("Credits"), Cell::new(vote_state.credits())])
-        .add_row(vec![
-            Cell::new("Commission"),
-            Cell::new(format!(
-                "{}%",
-                vote_state.inflation_rewards_commission_bps / 100
-            )),
-        ])
-        .add_row(vec![Cell::new("Root Slot"), Cell::new(root_slot)])
-        .add_row(vec![
-            Cell::new("Recent Timestamp"),
-            Cell::new(format!(
-                "{} from slot {}",
-                timestamp, vote_state.last_timestamp.slot
-            )),
-        ]);
-
-    println!("\n{}", style("VOTE ACCOUNT INFORMATION").green().bold());
-    println!("{table}");
-
-    Ok(())
+    Ok(VoteAccountOutput {
+        address: vote_account_pubkey.to_string(),
+        lamports: vote_account.lamports,
+        validator_identity: vote_state.node_pubkey.to_string(),
+        vote_authority,
+        withdraw_authority: vote_state.authorized_withdrawer.to_string(),
+        credits: vote_state.credits(),
+        commission: vote_state.inflation_rewards_commission_bps / 100,
+        root_slot: vote_state.root_slot,
+        recent_timestamp: vote_state.last_timestamp.timestamp,
+        recent_timestamp_slot: vote_state.last_timestamp.slot,
+    })
 }
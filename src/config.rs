@@ -3,6 +3,7 @@ use {
         commands::config::generate_config,
         constants::{DEFAULT_KEYPAIR_PATH, DEVNET_RPC, SCILLA_CONFIG_RELATIVE_PATH},
         error::ScillaError,
+        misc::compute_budget::ComputeUnitPrice,
     },
     console::style,
     serde::{Deserialize, Serialize},
@@ -42,6 +43,12 @@ pub struct ScillaConfig {
     pub commitment_level: CommitmentLevel,
     #[serde(deserialize_with = "deserialize_path_with_tilde")]
     pub keypair_path: PathBuf,
+    /// Compute unit limit requested by sent transactions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compute_unit_limit: Option<u32>,
+    /// Compute unit price paid by sent transactions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compute_unit_price: Option<ComputeUnitPrice>,
 }
 
 impl Default for ScillaConfig {
@@ -54,6 +61,8 @@ impl Default for ScillaConfig {
             rpc_url: DEVNET_RPC.to_string(),
             commitment_level: CommitmentLevel::Confirmed,
             keypair_path: default_keypair_path,
+            compute_unit_limit: None,
+            compute_unit_price: None,
         }
     }
 }
@@ -87,7 +96,7 @@ impl ScillaConfig {
             );
         }
 
-        println!(
+        eprintln!(
             "{}",
             style(format!("Using Scilla config path : {scilla_config_path:?}")).dim()
         );
@@ -150,5 +159,30 @@ commitment-level = "confirmed"
         assert_eq!(config.rpc_url, "https://api.mainnet-beta.solana.com");
         assert_eq!(config.commitment_level, CommitmentLevel::Confirmed);
         assert_eq!(config.keypair_path, home.join("my/key.json"));
+        assert_eq!(config.compute_unit_price, None);
+    }
+
+    #[test]
+    fn test_load_from_path_with_compute_budget() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let config_path = temp_dir.path().join("config.toml");
+
+        fs::write(
+            &config_path,
+            r#"
+rpc-url = "https://api.mainnet-beta.solana.com"
+keypair-path = "/tmp/key.json"
+commitment-level = "confirmed"
+compute-unit-limit = 200000
+compute-unit-price = "auto:75"
+"#,
+        )
+        .expect("Failed to write file");
+
+        let config = ScillaConfig::load_from_path(&config_path)
+            .expect("Valid config should load successfully");
+
+        assert_eq!(config.compute_unit_limit, Some(200_000));
+        assert_eq!(config.compute_unit_price, Some(ComputeUnitPrice::Auto(75)));
     }
 }
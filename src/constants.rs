@@ -12,8 +12,50 @@ pub const MAINNET_RPC: &str = "https://api.mainnet-beta.solana.com";
 
 pub const TESTNET_RPC: &str = "https://api.testnet.solana.com";
 
-pub const DEFAULT_EPOCH_LIMIT: usize = 10;
+pub const DEFAULT_HISTORY_PAGE_SIZE: usize = 10;
+
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 4;
 
 pub const STAKE_HISTORY_SYSVAR_ADDR: &str = "SysvarStakeHistory1111111111111111111111111";
 
+/// Feature gate that lowers the stake warmup/cooldown rate from 25% to 9%
+pub const REDUCE_STAKE_WARMUP_COOLDOWN_FEATURE: &str =
+    "GwtDQBghCTBgmX2cpEGNPxTEBUTQRaDMGTr5qychdGMj";
+
+/// How far ahead stake activation or deactivation is projected
+pub const MAX_PROJECTED_EPOCHS: u64 = 100;
+
 pub const MEMO_PROGRAM_ID: &str = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr";
+
+/// Most accounts a single `getMultipleAccounts` request may ask for
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// Most signatures a single `getSignatureStatuses` request may ask for
+pub const MAX_SIGNATURE_STATUSES: usize = 256;
+
+/// Items of a multi-transaction operation whose accounts are used to look up
+/// recent prioritization fees, which the RPC caps at 128 accounts per request
+pub const PRIORITIZATION_FEE_SAMPLE: usize = 64;
+
+/// Offsets of `Meta.authorized.staker` and `Meta.authorized.withdrawer` in
+/// serialized `StakeStateV2` data, after the enum tag and the rent exempt
+/// reserve
+pub const STAKE_STAKER_OFFSET: usize = 12;
+pub const STAKE_WITHDRAWER_OFFSET: usize = 44;
+
+/// Offset of `Stake.delegation.voter_pubkey` in serialized `StakeStateV2`
+/// data, after `Meta`
+pub const STAKE_VOTER_OFFSET: usize = 124;
+
+/// Seeds listed when deriving stake account addresses
+pub const DEFAULT_DERIVED_ADDRESS_COUNT: u32 = 10;
+
+pub const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+
+/// Completed epochs covered by the reward history when no range is given
+pub const DEFAULT_REWARD_EPOCHS: u64 = 10;
+
+/// Validators with vote credits in fewer of the most recent epochs than this
+/// are flagged as recently created. The RPC reports credits for at most 5
+/// epochs.
+pub const NEW_VALIDATOR_EPOCHS: u64 = 5;
@@ -1,5 +1,5 @@
 use {
-    crate::config::ScillaConfig,
+    crate::{config::ScillaConfig, misc::compute_budget::ComputeUnitPrice, output::OutputFormat},
     anyhow::anyhow,
     solana_commitment_config::CommitmentConfig,
     solana_keypair::{EncodableKey, Keypair, Signer},
@@ -13,6 +13,11 @@ pub struct ScillaContext {
     keypair: Keypair,
     pubkey: Pubkey,
     keypair_path: PathBuf,
+    compute_unit_limit: Option<u32>,
+    compute_unit_price: Option<ComputeUnitPrice>,
+    output_format: OutputFormat,
+    skip_confirmation: bool,
+    interactive: bool,
 }
 
 impl ScillaContext {
@@ -32,8 +37,54 @@ impl ScillaContext {
         &self.keypair_path
     }
 
+    /// Default compute unit limit for sent transactions
+    pub fn compute_unit_limit(&self) -> Option<u32> {
+        self.compute_unit_limit
+    }
+
+    /// Default compute unit price for sent transactions
+    pub fn compute_unit_price(&self) -> Option<ComputeUnitPrice> {
+        self.compute_unit_price
+    }
+
+    pub fn output_format(&self) -> OutputFormat {
+        self.output_format
+    }
+
+    pub fn set_output_format(&mut self, output_format: OutputFormat) {
+        self.output_format = output_format;
+    }
+
+    /// Whether transactions are sent without asking for confirmation after
+    /// the simulation preview
+    pub fn skip_confirmation(&self) -> bool {
+        self.skip_confirmation
+    }
+
+    pub fn set_skip_confirmation(&mut self, skip_confirmation: bool) {
+        self.skip_confirmation = skip_confirmation;
+    }
+
+    /// Whether commands are picked through the prompts rather than passed as
+    /// a subcommand. Missing arguments are prompted for either way, but
+    /// optional values and follow-up choices are only asked for here, so a
+    /// subcommand never stops on a question it has no argument for.
+    pub fn is_interactive(&self) -> bool {
+        self.interactive
+    }
+
+    pub fn set_interactive(&mut self, interactive: bool) {
+        self.interactive = interactive;
+    }
+
     pub fn reload(&mut self, new_config: ScillaConfig) -> anyhow::Result<()> {
+        let output_format = self.output_format;
+        let skip_confirmation = self.skip_confirmation;
+        let interactive = self.interactive;
         *self = ScillaContext::try_from(new_config)?;
+        self.output_format = output_format;
+        self.skip_confirmation = skip_confirmation;
+        self.interactive = interactive;
         Ok(())
     }
 }
@@ -64,6 +115,11 @@ impl TryFrom<ScillaConfig> for ScillaContext {
             keypair,
             pubkey,
             keypair_path: config.keypair_path,
+            compute_unit_limit: config.compute_unit_limit,
+            compute_unit_price: config.compute_unit_price,
+            output_format: OutputFormat::default(),
+            skip_confirmation: false,
+            interactive: false,
         })
     }
 }
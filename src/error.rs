@@ -2,6 +2,16 @@ use {crate::commands::CommandFlow, thiserror::Error};
 
 pub type ScillaResult<T> = anyhow::Result<CommandFlow<T>>;
 
+/// An error a command has already shown to the user, so it isn't printed
+/// again when the command exits.
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct ReportedError(#[from] pub anyhow::Error);
+
+pub fn is_reported(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| cause.is::<ReportedError>())
+}
+
 #[derive(Debug, Error)]
 pub enum ScillaError {
     #[error("Scilla ScillaConfig path doesnt exists")]
@@ -13,3 +23,19 @@ pub enum ScillaError {
     #[error("Anyhow err")]
     Anyhow(#[from] anyhow::Error),
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, anyhow::anyhow};
+
+    #[test]
+    fn test_is_reported() {
+        let err = anyhow!("Account not found");
+        assert!(!is_reported(&err));
+
+        let err = anyhow::Error::from(ReportedError(err));
+        assert!(is_reported(&err));
+        assert_eq!(err.to_string(), "Account not found");
+        assert!(is_reported(&err.context("Failed to load")));
+    }
+}
@@ -1,22 +1,48 @@
 use {
     crate::{
-        commands::CommandFlow, config::ScillaConfig, context::ScillaContext, error::ScillaResult,
+        cli::Cli,
+        commands::CommandFlow,
+        config::ScillaConfig,
+        context::ScillaContext,
+        error::{ScillaResult, is_reported},
         prompt::prompt_for_command,
+        ui::print_error,
     },
+    clap::Parser,
     console::style,
+    std::process::exit,
 };
 
+pub mod cli;
 pub mod commands;
 pub mod config;
 pub mod constants;
 pub mod context;
 pub mod error;
 pub mod misc;
+pub mod output;
 pub mod prompt;
 pub mod ui;
 
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> ScillaResult<()> {
+    let cli = Cli::parse();
+
+    if let Some(command) = cli.command {
+        let config = ScillaConfig::load()?;
+        let mut ctx = ScillaContext::try_from(config)?;
+        ctx.set_output_format(cli.output);
+        ctx.set_skip_confirmation(cli.yes);
+
+        return match command.process_command(&mut ctx).await {
+            Ok(flow) => Ok(flow),
+            Err(err) => {
+                print_unreported(&err);
+                exit(1)
+            }
+        };
+    }
+
     println!(
         "{}",
         style("⚡ Scilla — Hacking Through the Solana Matrix")
@@ -26,6 +52,9 @@ async fn main() -> ScillaResult<()> {
 
     let config = ScillaConfig::load()?;
     let mut ctx = ScillaContext::try_from(config)?;
+    ctx.set_output_format(cli.output);
+    ctx.set_skip_confirmation(cli.yes);
+    ctx.set_interactive(true);
 
     loop {
         let command = prompt_for_command()?;
@@ -33,11 +62,20 @@ async fn main() -> ScillaResult<()> {
         let res = command.process_command(&mut ctx).await;
 
         match res {
-            CommandFlow::Process(_) => continue,
-            CommandFlow::GoBack => continue,
-            CommandFlow::Exit => break,
+            Err(err) => print_unreported(&err),
+            Ok(CommandFlow::Process(_)) => continue,
+            Ok(CommandFlow::GoBack) => continue,
+            Ok(CommandFlow::Exit) => break,
         }
     }
 
     Ok(CommandFlow::Exit)
 }
+
+/// Prints an error unless the command already showed it, e.g. through its
+/// spinner.
+fn print_unreported(err: &anyhow::Error) {
+    if !is_reported(err) {
+        print_error(format!("Error : {err}"));
+    }
+}
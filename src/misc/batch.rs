@@ -0,0 +1,157 @@
+use {
+    crate::{
+        ScillaContext,
+        constants::PRIORITIZATION_FEE_SAMPLE,
+        misc::{
+            compute_budget::{
+                ComputeBudgetArgs, ComputeUnitPrice, compute_budget_instructions,
+                resolve_compute_unit_price,
+            },
+            helpers::TxArgs,
+        },
+    },
+    anyhow::{Context, bail},
+    solana_instruction::Instruction,
+    solana_message::Message,
+    solana_pubkey::Pubkey,
+    solana_transaction::Transaction,
+};
+
+/// Largest serialized transaction the cluster accepts
+pub const PACKET_DATA_SIZE: usize = 1232;
+
+/// Serialized size of an unsigned transaction carrying `instructions`
+pub fn transaction_size(instructions: &[Instruction], payer: &Pubkey) -> anyhow::Result<usize> {
+    let tx = Transaction::new_unsigned(Message::new(instructions, Some(payer)));
+    let size = bincode::serialized_size(&tx).context("Failed to serialize transaction")?;
+    Ok(size as usize)
+}
+
+/// Items whose instructions are sent together in one transaction.
+#[derive(Debug, Clone)]
+pub struct Batch<T> {
+    pub items: Vec<T>,
+    pub instructions: Vec<Instruction>,
+}
+
+/// Packs the instructions of each item into as few transactions as the size
+/// limit allows, keeping the instructions of an item together and in order.
+/// `prefix` (e.g. compute budget instructions) is counted against every
+/// transaction but not included in the batches.
+pub fn pack_instructions<T>(
+    items: impl IntoIterator<Item = (T, Vec<Instruction>)>,
+    payer: &Pubkey,
+    prefix: &[Instruction],
+) -> anyhow::Result<Vec<Batch<T>>> {
+    let fits = |instructions: &[Instruction]| -> anyhow::Result<bool> {
+        let all: Vec<_> = prefix.iter().chain(instructions).cloned().collect();
+        Ok(transaction_size(&all, payer)? <= PACKET_DATA_SIZE)
+    };
+
+    let mut batches = Vec::new();
+    let mut current = Batch {
+        items: Vec::new(),
+        instructions: Vec::new(),
+    };
+
+    for (item, instructions) in items {
+        let mut candidate = current.instructions.clone();
+        candidate.extend_from_slice(&instructions);
+        if fits(&candidate)? {
+            current.items.push(item);
+            current.instructions = candidate;
+            continue;
+        }
+
+        if !fits(&instructions)? {
+            bail!("An entry is too large to fit into a single transaction");
+        }
+        if !current.items.is_empty() {
+            batches.push(current);
+        }
+        current = Batch {
+            items: vec![item],
+            instructions,
+        };
+    }
+
+    if !current.items.is_empty() {
+        batches.push(current);
+    }
+
+    Ok(batches)
+}
+
+/// Resolves the compute unit price once for all the transactions of a
+/// multi-transaction operation, so every batch pays the same fee and `auto`
+/// isn't looked up again per batch. Returns the transaction options with the
+/// price fixed and the compute budget instructions to pass to
+/// [`pack_instructions`] as its prefix.
+pub async fn pin_batch_tx_args<T>(
+    ctx: &ScillaContext,
+    compute_budget: &ComputeBudgetArgs,
+    instructions: &[(T, Vec<Instruction>)],
+) -> anyhow::Result<(TxArgs, Vec<Instruction>)> {
+    // Only a sample of the items is used, as the RPC caps the accounts per
+    // prioritization fee query
+    let sample: Vec<Instruction> = instructions
+        .iter()
+        .take(PRIORITIZATION_FEE_SAMPLE)
+        .flat_map(|(_, ixs)| ixs.iter().cloned())
+        .collect();
+    let micro_lamports =
+        resolve_compute_unit_price(ctx, compute_budget, ctx.pubkey(), &sample).await?;
+
+    let mut tx_args = TxArgs {
+        compute_budget: compute_budget.clone(),
+        ..Default::default()
+    };
+    tx_args.compute_budget.compute_unit_price = Some(ComputeUnitPrice::Fixed(micro_lamports));
+    let prefix =
+        compute_budget_instructions(ctx, &tx_args.compute_budget, ctx.pubkey(), &[]).await?;
+
+    Ok((tx_args, prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_system_interface::instruction::transfer};
+
+    #[test]
+    fn test_pack_instructions() -> anyhow::Result<()> {
+        let payer = Pubkey::new_unique();
+        let items: Vec<_> = (0..50)
+            .map(|i| (i, vec![transfer(&payer, &Pubkey::new_unique(), 1)]))
+            .collect();
+
+        let batches = pack_instructions(items, &payer, &[])?;
+
+        assert!(batches.len() > 1);
+        assert_eq!(
+            batches
+                .iter()
+                .flat_map(|batch| &batch.items)
+                .copied()
+                .collect::<Vec<_>>(),
+            (0..50).collect::<Vec<_>>()
+        );
+        for batch in &batches {
+            assert_eq!(batch.items.len(), batch.instructions.len());
+            assert!(transaction_size(&batch.instructions, &payer)? <= PACKET_DATA_SIZE);
+        }
+        // A transfer only adds a new account and a small instruction, so far
+        // more than one fits into a transaction
+        assert!(batches[0].items.len() > 10);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pack_instructions_rejects_oversized_entry() {
+        let payer = Pubkey::new_unique();
+        let oversized =
+            Instruction::new_with_bytes(Pubkey::new_unique(), &[0; PACKET_DATA_SIZE], vec![]);
+
+        assert!(pack_instructions([((), vec![oversized])], &payer, &[]).is_err());
+    }
+}
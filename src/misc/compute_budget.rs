@@ -0,0 +1,243 @@
+use {
+    crate::ScillaContext,
+    anyhow::{anyhow, bail},
+    clap::Args,
+    serde::{Deserialize, Serialize},
+    solana_instruction::Instruction,
+    solana_pubkey::Pubkey,
+    std::{collections::BTreeSet, fmt, str::FromStr},
+};
+
+/// Upper bound on compute units a single transaction may request
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Percentile used when `auto` is given without one
+pub const DEFAULT_PRIORITY_FEE_PERCENTILE: u8 = 50;
+
+// ComputeBudgetInstruction discriminants (borsh enum tags)
+const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+const SET_COMPUTE_UNIT_PRICE: u8 = 3;
+
+/// Price paid per compute unit, in micro-lamports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum ComputeUnitPrice {
+    /// Fixed price in micro-lamports
+    Fixed(u64),
+    /// Percentile of the recent prioritization fees paid for the accounts the
+    /// transaction writes to
+    Auto(u8),
+}
+
+impl FromStr for ComputeUnitPrice {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let Some(rest) = s.strip_prefix("auto") else {
+            return s.parse().map(ComputeUnitPrice::Fixed).map_err(|_| {
+                anyhow!(
+                    "Invalid compute unit price: {s}. Use micro-lamports, `auto` or \
+                     `auto:<percentile>`"
+                )
+            });
+        };
+
+        let percentile = match rest.strip_prefix(':') {
+            Some(p) => p
+                .parse::<u8>()
+                .map_err(|_| anyhow!("Invalid priority fee percentile: {p}"))?,
+            None if rest.is_empty() => DEFAULT_PRIORITY_FEE_PERCENTILE,
+            None => bail!("Invalid compute unit price: {s}"),
+        };
+        if !(1..=100).contains(&percentile) {
+            bail!("Priority fee percentile must be between 1 and 100, got {percentile}");
+        }
+        Ok(ComputeUnitPrice::Auto(percentile))
+    }
+}
+
+impl fmt::Display for ComputeUnitPrice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComputeUnitPrice::Fixed(micro_lamports) => write!(f, "{micro_lamports}"),
+            ComputeUnitPrice::Auto(percentile) => write!(f, "auto:{percentile}"),
+        }
+    }
+}
+
+impl TryFrom<String> for ComputeUnitPrice {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<ComputeUnitPrice> for String {
+    fn from(price: ComputeUnitPrice) -> Self {
+        price.to_string()
+    }
+}
+
+/// Per-transaction overrides of the compute budget defaults from the config.
+#[derive(Debug, Clone, Default, Args)]
+pub struct ComputeBudgetArgs {
+    /// Compute unit limit to request for the transaction
+    #[arg(long, value_parser = parse_compute_unit_limit)]
+    pub compute_unit_limit: Option<u32>,
+    /// Compute unit price in micro-lamports, or `auto[:PERCENTILE]` to derive
+    /// it from recent prioritization fees
+    #[arg(long)]
+    pub compute_unit_price: Option<ComputeUnitPrice>,
+}
+
+pub fn parse_compute_unit_limit(s: &str) -> anyhow::Result<u32> {
+    let limit: u32 = s
+        .trim()
+        .parse()
+        .map_err(|_| anyhow!("Invalid compute unit limit: {s}"))?;
+    if limit == 0 || limit > MAX_COMPUTE_UNIT_LIMIT {
+        bail!("Compute unit limit must be between 1 and {MAX_COMPUTE_UNIT_LIMIT}, got {limit}");
+    }
+    Ok(limit)
+}
+
+pub fn set_compute_unit_limit(units: u32) -> Instruction {
+    let mut data = vec![SET_COMPUTE_UNIT_LIMIT];
+    data.extend_from_slice(&units.to_le_bytes());
+    Instruction::new_with_bytes(solana_sdk_ids::compute_budget::id(), &data, vec![])
+}
+
+pub fn set_compute_unit_price(micro_lamports: u64) -> Instruction {
+    let mut data = vec![SET_COMPUTE_UNIT_PRICE];
+    data.extend_from_slice(&micro_lamports.to_le_bytes());
+    Instruction::new_with_bytes(solana_sdk_ids::compute_budget::id(), &data, vec![])
+}
+
+/// Builds the ComputeBudget instructions to prepend to `instructions`, using
+/// the overrides in `args` and falling back to the config defaults.
+pub async fn compute_budget_instructions(
+    ctx: &ScillaContext,
+    args: &ComputeBudgetArgs,
+    fee_payer: &Pubkey,
+    instructions: &[Instruction],
+) -> anyhow::Result<Vec<Instruction>> {
+    let mut budget_ixs = Vec::new();
+
+    if let Some(units) = args.compute_unit_limit.or(ctx.compute_unit_limit()) {
+        budget_ixs.push(set_compute_unit_limit(units));
+    }
+
+    let micro_lamports = resolve_compute_unit_price(ctx, args, fee_payer, instructions).await?;
+    if micro_lamports > 0 {
+        budget_ixs.push(set_compute_unit_price(micro_lamports));
+    }
+
+    Ok(budget_ixs)
+}
+
+/// Compute unit price in micro-lamports the transaction would pay, looking up
+/// recent prioritization fees for `auto` over the accounts it writes to,
+/// including `fee_payer`.
+pub async fn resolve_compute_unit_price(
+    ctx: &ScillaContext,
+    args: &ComputeBudgetArgs,
+    fee_payer: &Pubkey,
+    instructions: &[Instruction],
+) -> anyhow::Result<u64> {
+    let micro_lamports = match args.compute_unit_price.or(ctx.compute_unit_price()) {
+        Some(ComputeUnitPrice::Fixed(micro_lamports)) => micro_lamports,
+        Some(ComputeUnitPrice::Auto(percentile)) => {
+            let writable: BTreeSet<Pubkey> = std::iter::once(*fee_payer)
+                .chain(
+                    instructions
+                        .iter()
+                        .flat_map(|ix| &ix.accounts)
+                        .filter(|meta| meta.is_writable)
+                        .map(|meta| meta.pubkey),
+                )
+                .collect();
+            let writable: Vec<Pubkey> = writable.into_iter().collect();
+
+            let fees: Vec<u64> = ctx
+                .rpc()
+                .get_recent_prioritization_fees(&writable)
+                .await?
+                .into_iter()
+                .map(|fee| fee.prioritization_fee)
+                .collect();
+            fee_at_percentile(fees, percentile)
+        }
+        None => 0,
+    };
+
+    Ok(micro_lamports)
+}
+
+/// Nearest-rank percentile of `fees`, or 0 when no fees were reported
+fn fee_at_percentile(mut fees: Vec<u64>, percentile: u8) -> u64 {
+    if fees.is_empty() {
+        return 0;
+    }
+    fees.sort_unstable();
+    let rank = (percentile as usize * fees.len()).div_ceil(100).max(1);
+    fees[rank - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_compute_unit_price() {
+        assert_eq!(
+            "1000".parse::<ComputeUnitPrice>().unwrap(),
+            ComputeUnitPrice::Fixed(1000)
+        );
+        assert_eq!(
+            "auto".parse::<ComputeUnitPrice>().unwrap(),
+            ComputeUnitPrice::Auto(DEFAULT_PRIORITY_FEE_PERCENTILE)
+        );
+        assert_eq!(
+            "auto:75".parse::<ComputeUnitPrice>().unwrap(),
+            ComputeUnitPrice::Auto(75)
+        );
+        assert!("auto:0".parse::<ComputeUnitPrice>().is_err());
+        assert!("auto75".parse::<ComputeUnitPrice>().is_err());
+        assert!("cheap".parse::<ComputeUnitPrice>().is_err());
+    }
+
+    #[test]
+    fn test_fee_at_percentile() {
+        let fees = vec![50, 10, 40, 20, 30];
+        assert_eq!(fee_at_percentile(fees.clone(), 1), 10);
+        assert_eq!(fee_at_percentile(fees.clone(), 50), 30);
+        assert_eq!(fee_at_percentile(fees.clone(), 100), 50);
+        assert_eq!(fee_at_percentile(vec![], 75), 0);
+    }
+
+    /// Locks the hand-built encoding to the borsh layout of
+    /// `ComputeBudgetInstruction`: a one byte variant tag followed by the
+    /// little-endian value, with no accounts.
+    #[test]
+    fn test_compute_budget_instruction_data() {
+        let limit = set_compute_unit_limit(200_000);
+        assert_eq!(limit.program_id, solana_sdk_ids::compute_budget::id());
+        assert!(limit.accounts.is_empty());
+        assert_eq!(limit.data, [2, 0x40, 0x0d, 0x03, 0x00]);
+        assert_eq!(
+            set_compute_unit_limit(MAX_COMPUTE_UNIT_LIMIT).data,
+            [2, 0xc0, 0x5c, 0x15, 0x00]
+        );
+
+        let price = set_compute_unit_price(1);
+        assert_eq!(price.program_id, solana_sdk_ids::compute_budget::id());
+        assert!(price.accounts.is_empty());
+        assert_eq!(price.data, [3, 1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            set_compute_unit_price(u64::MAX).data,
+            [3, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]
+        );
+    }
+}
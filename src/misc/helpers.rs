@@ -1,16 +1,37 @@
 use {
-    crate::{ScillaContext, constants::LAMPORTS_PER_SOL},
+    crate::{
+        ScillaContext,
+        constants::{LAMPORTS_PER_SOL, MEMO_PROGRAM_ID},
+        misc::{
+            compute_budget::{ComputeBudgetArgs, ComputeUnitPrice, compute_budget_instructions},
+            signatures::{SignedTransactionOutput, format_signers, missing_signers},
+            simulation::preview_and_confirm,
+        },
+        output::CommandOutput,
+    },
     anyhow::{Context, anyhow, bail},
     base64::Engine,
     bincode::Options,
+    clap::Args,
+    serde::Serialize,
     solana_account::Account,
     solana_epoch_info::EpochInfo,
-    solana_instruction::Instruction,
+    solana_instruction::{AccountMeta, Instruction},
     solana_keypair::{EncodableKey, Keypair, Signature, Signer},
-    solana_message::Message,
+    solana_message::{Hash, Message},
+    solana_nonce::{
+        state::{Data as NonceData, State},
+        versions::Versions,
+    },
     solana_pubkey::Pubkey,
-    solana_transaction::Transaction,
-    std::{path::Path, str::FromStr},
+    solana_sdk_ids::system_program,
+    solana_system_interface::instruction::advance_nonce_account,
+    solana_transaction::{Transaction, versioned::VersionedTransaction},
+    std::{
+        collections::HashSet,
+        path::{Path, PathBuf},
+        str::FromStr,
+    },
     tokio::try_join,
 };
 
@@ -107,25 +128,217 @@ pub fn lamports_to_sol(lamports: u64) -> f64 {
     lamports as f64 / LAMPORTS_PER_SOL as f64
 }
 
+/// Formats a duration as days, hours and minutes, e.g. `2d 3h 15m`
+pub fn format_duration(seconds: u64) -> String {
+    let days = seconds / 86_400;
+    let hours = seconds % 86_400 / 3_600;
+    let minutes = seconds % 3_600 / 60;
+    match (days, hours) {
+        (0, 0) => format!("{minutes}m"),
+        (0, _) => format!("{hours}h {minutes}m"),
+        _ => format!("{days}d {hours}h {minutes}m"),
+    }
+}
+
 pub fn read_keypair_from_path<P: AsRef<Path>>(path: P) -> anyhow::Result<Keypair> {
     let path = path.as_ref();
     Keypair::read_from_file(path)
         .map_err(|e| anyhow!("Failed to read keypair from {}: {}", path.display(), e))
 }
 
+/// Options shared by every command that sends a transaction.
+#[derive(Debug, Clone, Default, Args)]
+#[command(next_help_heading = "Transaction Options")]
+pub struct TxArgs {
+    #[command(flatten)]
+    pub compute_budget: ComputeBudgetArgs,
+    /// Durable nonce account to use instead of a recent blockhash
+    #[arg(long)]
+    pub nonce: Option<Pubkey>,
+    /// Nonce authority keypair path (defaults to the fee payer)
+    #[arg(long, requires = "nonce")]
+    pub nonce_authority: Option<PathBuf>,
+    /// Sign the transaction with the available keypairs without sending it
+    #[arg(long, requires = "blockhash")]
+    pub sign_only: bool,
+    /// Blockhash to sign with, or the stored nonce when used with --nonce
+    #[arg(long)]
+    pub blockhash: Option<Hash>,
+    /// Fee payer address, when the fee payer signs on another machine
+    #[arg(long)]
+    pub fee_payer: Option<Pubkey>,
+    /// File to save the signed transaction to (with --sign-only)
+    #[arg(long, requires = "sign_only")]
+    pub export: Option<PathBuf>,
+}
+
+/// Result of a command that builds a transaction: either it was sent, or it
+/// was only signed for submission later.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum TxOutcome<T> {
+    Sent(T),
+    SignedOnly(SignedTransactionOutput),
+}
+
+impl<T: CommandOutput> CommandOutput for TxOutcome<T> {
+    fn print_table(&self) {
+        match self {
+            TxOutcome::Sent(output) => output.print_table(),
+            TxOutcome::SignedOnly(signed) => signed.print_table(),
+        }
+    }
+}
+
 pub async fn build_and_send_tx(
     ctx: &ScillaContext,
     instruction: &[Instruction],
     signers: &[&dyn Signer],
-) -> anyhow::Result<Signature> {
-    let recent_blockhash = ctx.rpc().get_latest_blockhash().await?;
-    let message = Message::new(instruction, Some(ctx.pubkey()));
+    tx_args: &TxArgs,
+) -> anyhow::Result<TxOutcome<Signature>> {
+    let tx = build_tx(ctx, instruction, signers, tx_args).await?;
+    send_tx(ctx, &tx, tx_args).await
+}
+
+/// Builds the transaction for `instruction` as [`build_and_send_tx`] would
+/// send it, signed with whichever of `signers` it requires.
+pub async fn build_tx(
+    ctx: &ScillaContext,
+    instruction: &[Instruction],
+    signers: &[&dyn Signer],
+    tx_args: &TxArgs,
+) -> anyhow::Result<VersionedTransaction> {
+    let nonce_authority = tx_args
+        .nonce_authority
+        .as_ref()
+        .map(read_keypair_from_path)
+        .transpose()?;
+    let mut signers = signers.to_vec();
+
+    // The advance instruction has to come first for the runtime to accept
+    // the nonce in place of a recent blockhash
+    let (recent_blockhash, mut instructions) = match &tx_args.nonce {
+        Some(nonce_pubkey) => {
+            let authority: &dyn Signer = match &nonce_authority {
+                Some(keypair) => {
+                    signers.push(keypair);
+                    keypair
+                }
+                None => ctx.keypair(),
+            };
+            let nonce = match tx_args.blockhash {
+                Some(nonce) => nonce,
+                None => {
+                    let nonce_account = ctx.rpc().get_account(nonce_pubkey).await?;
+                    let nonce_data = decode_nonce_data(&nonce_account)?;
+                    if nonce_data.authority != authority.pubkey() {
+                        bail!(
+                            "{} is not the authority of nonce account {nonce_pubkey}. Authority: \
+                             {}",
+                            authority.pubkey(),
+                            nonce_data.authority
+                        );
+                    }
+                    nonce_data.blockhash()
+                }
+            };
+
+            (
+                nonce,
+                vec![advance_nonce_account(nonce_pubkey, &authority.pubkey())],
+            )
+        }
+        None => match tx_args.blockhash {
+            Some(blockhash) => (blockhash, Vec::new()),
+            None => (ctx.rpc().get_latest_blockhash().await?, Vec::new()),
+        },
+    };
+    // An `auto` price is looked up over RPC, which offline signing can't use
+    if tx_args.sign_only
+        && let Some(ComputeUnitPrice::Auto(_)) = tx_args
+            .compute_budget
+            .compute_unit_price
+            .or(ctx.compute_unit_price())
+    {
+        bail!(
+            "An `auto` compute unit price can't be resolved offline. Pass a fixed \
+             --compute-unit-price with --sign-only"
+        );
+    }
+    let fee_payer = tx_args.fee_payer.unwrap_or(*ctx.pubkey());
+    instructions.extend(
+        compute_budget_instructions(ctx, &tx_args.compute_budget, &fee_payer, instruction).await?,
+    );
+    instructions.extend_from_slice(instruction);
+
+    let message = Message::new(&instructions, Some(&fee_payer));
+
+    // Only sign with the keypairs the message asks for; any other required
+    // signer has to add its signature separately
+    let required = message.signer_keys();
+    let mut seen = HashSet::new();
+    signers.retain(|signer| {
+        let pubkey = signer.pubkey();
+        required.contains(&&pubkey) && seen.insert(pubkey)
+    });
+
     let mut tx = Transaction::new_unsigned(message);
-    tx.try_sign(&signers.to_vec(), recent_blockhash)?;
+    tx.try_partial_sign(&signers, recent_blockhash)?;
 
-    let signature = ctx.rpc().send_and_confirm_transaction(&tx).await?;
+    Ok(VersionedTransaction::from(tx))
+}
+
+/// Submits a built transaction after the preview, or only returns it signed
+/// with `--sign-only`.
+pub async fn send_tx(
+    ctx: &ScillaContext,
+    tx: &VersionedTransaction,
+    tx_args: &TxArgs,
+) -> anyhow::Result<TxOutcome<Signature>> {
+    if tx_args.sign_only {
+        let mut signed = SignedTransactionOutput::new(tx)?;
+        if let Some(path) = &tx_args.export {
+            signed.export(path)?;
+        }
+        return Ok(TxOutcome::SignedOnly(signed));
+    }
+
+    let missing = missing_signers(tx);
+    if !missing.is_empty() {
+        bail!(
+            "Missing signatures from: {}. Use --sign-only to collect them first",
+            format_signers(&missing)
+        );
+    }
 
-    Ok(signature)
+    preview_and_confirm(ctx, tx).await?;
+
+    let signature = ctx.rpc().send_and_confirm_transaction(tx).await?;
+
+    Ok(TxOutcome::Sent(signature))
+}
+
+/// SPL memo instruction, signed by `signer` so the memo is attributed to it.
+pub fn memo_instruction(memo: &str, signer: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        Pubkey::from_str_const(MEMO_PROGRAM_ID),
+        memo.as_bytes(),
+        vec![AccountMeta::new_readonly(*signer, true)],
+    )
+}
+
+/// Decodes an initialized durable nonce account.
+pub fn decode_nonce_data(account: &Account) -> anyhow::Result<NonceData> {
+    if account.owner != system_program::id() {
+        bail!("Account is not owned by the system program");
+    }
+
+    let versions = bincode_deserialize::<Versions>(&account.data, "nonce account data")?;
+    let State::Initialized(data) = versions.state() else {
+        bail!("This account is not an initialized nonce account");
+    };
+
+    Ok(data.clone())
 }
 
 /// Fetches account data and current epoch info in parallel.
@@ -204,10 +417,21 @@ pub fn decode_base58(encoded: &str) -> anyhow::Result<Vec<u8>> {
 #[cfg(test)]
 mod tests {
     use {
-        super::*, crate::constants::MEMO_PROGRAM_ID, solana_message::VersionedMessage,
-        solana_transaction::versioned::VersionedTransaction,
+        super::*, crate::config::ScillaConfig, solana_message::VersionedMessage,
+        solana_nonce::state::DurableNonce, solana_transaction::versioned::VersionedTransaction,
+        tempfile::TempDir,
     };
 
+    fn nonce_account(state: State) -> Account {
+        Account {
+            lamports: 1_447_680,
+            data: bincode::serialize(&Versions::new(state)).unwrap(),
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
     #[test]
     fn test_lamports_to_sol_exact_one_sol() {
         assert_eq!(lamports_to_sol(1_000_000_000), 1.0);
@@ -220,6 +444,17 @@ mod tests {
         assert!(result > 0.0, "Should handle u64::MAX without panic");
         assert!(result < f64::INFINITY, "Should not overflow to infinity");
     }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(59), "0m");
+        assert_eq!(format_duration(3_660), "1h 1m");
+        assert_eq!(
+            format_duration(2 * 86_400 + 3 * 3_600 + 15 * 60),
+            "2d 3h 15m"
+        );
+    }
+
     #[test]
     fn test_decode_base64_memo_transaction() -> anyhow::Result<()> {
         // Fixture: Real memo transaction from Solana devnet
@@ -274,4 +509,84 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_decode_nonce_data() {
+        let authority = Pubkey::new_unique();
+        let blockhash = Hash::new_unique();
+        let durable_nonce = DurableNonce::from_blockhash(&blockhash);
+        let account = nonce_account(State::new_initialized(&authority, durable_nonce, 5_000));
+
+        let data = decode_nonce_data(&account).unwrap();
+        assert_eq!(data.authority, authority);
+        assert_eq!(data.get_lamports_per_signature(), 5_000);
+        // Transactions sign with the durable nonce, not the blockhash it was
+        // derived from
+        assert_eq!(data.blockhash(), *durable_nonce.as_hash());
+        assert_ne!(data.blockhash(), blockhash);
+
+        assert!(decode_nonce_data(&nonce_account(State::Uninitialized)).is_err());
+        let not_system_owned = Account {
+            owner: Pubkey::new_unique(),
+            ..account.clone()
+        };
+        assert!(decode_nonce_data(&not_system_owned).is_err());
+        let truncated = Account {
+            data: account.data[..10].to_vec(),
+            ..account
+        };
+        assert!(decode_nonce_data(&truncated).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_build_tx_advances_nonce_first() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let keypair_path = temp_dir.path().join("id.json");
+        Keypair::new().write_to_file(&keypair_path).unwrap();
+        let ctx = ScillaContext::try_from(ScillaConfig {
+            keypair_path,
+            ..ScillaConfig::default()
+        })
+        .unwrap();
+
+        let nonce_pubkey = Pubkey::new_unique();
+        let nonce_value = Hash::new_unique();
+        let tx_args = TxArgs {
+            compute_budget: ComputeBudgetArgs {
+                compute_unit_limit: Some(200_000),
+                compute_unit_price: Some(ComputeUnitPrice::Fixed(1)),
+            },
+            nonce: Some(nonce_pubkey),
+            sign_only: true,
+            blockhash: Some(nonce_value),
+            ..TxArgs::default()
+        };
+        let memo = memo_instruction("durable", ctx.pubkey());
+
+        let tx = build_tx(&ctx, &[memo], &[ctx.keypair()], &tx_args)
+            .await
+            .unwrap();
+
+        assert_eq!(tx.message.recent_blockhash(), &nonce_value);
+        let keys = tx.message.static_account_keys();
+        let programs: Vec<Pubkey> = tx
+            .message
+            .instructions()
+            .iter()
+            .map(|ix| keys[ix.program_id_index as usize])
+            .collect();
+        assert_eq!(
+            programs,
+            [
+                system_program::id(),
+                solana_sdk_ids::compute_budget::id(),
+                solana_sdk_ids::compute_budget::id(),
+                Pubkey::from_str(MEMO_PROGRAM_ID).unwrap(),
+            ]
+        );
+        assert_eq!(
+            tx.message.instructions()[0].data,
+            advance_nonce_account(&nonce_pubkey, ctx.pubkey()).data
+        );
+    }
 }
@@ -1 +1,6 @@
+pub mod batch;
+pub mod compute_budget;
 pub mod helpers;
+pub mod rewards;
+pub mod signatures;
+pub mod simulation;
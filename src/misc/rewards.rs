@@ -0,0 +1,209 @@
+use {
+    crate::{
+        constants::{DEFAULT_BATCH_CONCURRENCY, MAX_MULTIPLE_ACCOUNTS, SECONDS_PER_YEAR},
+        context::ScillaContext,
+    },
+    anyhow::{Context, anyhow},
+    futures::{StreamExt, TryStreamExt, stream},
+    serde::Serialize,
+    solana_clock::{Epoch, Slot, UnixTimestamp},
+    solana_pubkey::Pubkey,
+    std::{collections::BTreeMap, ops::RangeInclusive},
+};
+
+/// Inflation reward credited to a stake account for an epoch
+#[derive(Debug, Clone, Serialize)]
+pub struct StakeReward {
+    pub stake_account: String,
+    pub epoch: Epoch,
+    /// Slot in which the reward was credited, early in the following epoch
+    pub effective_slot: Slot,
+    pub amount: u64,
+    pub post_balance: u64,
+    /// Commission of the vote account when the reward was credited
+    pub commission: Option<u8>,
+    /// Annualized return of the epoch in percent, when the epoch length is
+    /// known
+    pub apr: Option<f64>,
+    /// Block time at which the following epoch started, i.e. around when the
+    /// reward was credited
+    pub credited_at: Option<UnixTimestamp>,
+}
+
+/// Fetches the inflation rewards of `stake_accounts` for each epoch in
+/// `epochs`, ordered by epoch. Epochs in which an account earned nothing are
+/// left out.
+pub async fn fetch_stake_rewards(
+    ctx: &ScillaContext,
+    stake_accounts: &[Pubkey],
+    epochs: RangeInclusive<Epoch>,
+) -> anyhow::Result<Vec<StakeReward>> {
+    let requests: Vec<(Epoch, &[Pubkey])> = epochs
+        .clone()
+        .flat_map(|epoch| {
+            stake_accounts
+                .chunks(MAX_MULTIPLE_ACCOUNTS)
+                .map(move |chunk| (epoch, chunk))
+        })
+        .collect();
+
+    let responses: Vec<_> = stream::iter(requests)
+        .map(|(epoch, chunk)| async move {
+            let rewards = ctx.rpc().get_inflation_reward(chunk, Some(epoch)).await?;
+            anyhow::Ok(chunk.iter().zip(rewards).collect::<Vec<_>>())
+        })
+        .buffered(DEFAULT_BATCH_CONCURRENCY)
+        .try_collect()
+        .await?;
+
+    // An epoch lasts until the next one starts
+    let start_times =
+        fetch_epoch_start_times(ctx, *epochs.start()..=epochs.end().saturating_add(1)).await?;
+    let epoch_seconds = |epoch: Epoch| {
+        let start = start_times.get(&epoch)?;
+        let end = start_times.get(&(epoch + 1))?;
+        Some(end - start).filter(|seconds| *seconds > 0)
+    };
+
+    Ok(responses
+        .into_iter()
+        .flatten()
+        .filter_map(|(stake_account, reward)| {
+            let reward = reward?;
+            let apr = epoch_seconds(reward.epoch)
+                .and_then(|seconds| annualized_return(reward.amount, reward.post_balance, seconds));
+            Some(StakeReward {
+                stake_account: stake_account.to_string(),
+                epoch: reward.epoch,
+                effective_slot: reward.effective_slot,
+                amount: reward.amount,
+                post_balance: reward.post_balance,
+                commission: reward.commission,
+                apr,
+                credited_at: start_times.get(&(reward.epoch + 1)).copied(),
+            })
+        })
+        .collect())
+}
+
+/// Annualized return in percent of a reward of `amount` that brought the
+/// balance to `post_balance` over an epoch of `epoch_seconds`
+pub fn annualized_return(amount: u64, post_balance: u64, epoch_seconds: i64) -> Option<f64> {
+    let pre_balance = post_balance
+        .checked_sub(amount)
+        .filter(|balance| *balance > 0)?;
+    let epochs_per_year = SECONDS_PER_YEAR as f64 / epoch_seconds as f64;
+    Some(amount as f64 / pre_balance as f64 * epochs_per_year * 100.0)
+}
+
+/// Block time of the first block of each epoch in `epochs`. Epochs whose
+/// first block isn't known to the RPC node (e.g. not yet started) are left
+/// out.
+pub async fn fetch_epoch_start_times(
+    ctx: &ScillaContext,
+    epochs: RangeInclusive<Epoch>,
+) -> anyhow::Result<BTreeMap<Epoch, UnixTimestamp>> {
+    let (epoch_schedule, current_slot) =
+        tokio::try_join!(ctx.rpc().get_epoch_schedule(), ctx.rpc().get_slot())?;
+
+    let start_times: Vec<_> = stream::iter(epochs)
+        .map(|epoch| {
+            let epoch_schedule = &epoch_schedule;
+            async move {
+                let first_slot = epoch_schedule.get_first_slot_in_epoch(epoch);
+                if first_slot > current_slot {
+                    return anyhow::Ok(None);
+                }
+                let start_time = fetch_first_block_time(ctx, first_slot).await?;
+                anyhow::Ok(start_time.map(|start_time| (epoch, start_time)))
+            }
+        })
+        .buffered(DEFAULT_BATCH_CONCURRENCY)
+        .try_collect()
+        .await?;
+
+    Ok(start_times.into_iter().flatten().collect())
+}
+
+/// Finds the first epoch that started at or after `timestamp`, or the epoch
+/// after the current one if none has yet, by bisecting over the epoch start
+/// times.
+pub async fn fetch_first_epoch_starting_from(
+    ctx: &ScillaContext,
+    timestamp: UnixTimestamp,
+) -> anyhow::Result<Epoch> {
+    let (epoch_schedule, epoch_info) =
+        tokio::try_join!(ctx.rpc().get_epoch_schedule(), ctx.rpc().get_epoch_info())?;
+
+    let (mut low, mut high) = (0, epoch_info.epoch + 1);
+    while low < high {
+        let epoch = low + (high - low) / 2;
+        let first_slot = epoch_schedule.get_first_slot_in_epoch(epoch);
+        let start_time = fetch_first_block_time(ctx, first_slot)
+            .await
+            .with_context(|| format!("Failed to look up when epoch {epoch} started"))?
+            .ok_or_else(|| anyhow!("No block found in epoch {epoch}"))?;
+        if start_time >= timestamp {
+            high = epoch;
+        } else {
+            low = epoch + 1;
+        }
+    }
+
+    Ok(low)
+}
+
+/// Epochs whose rewards were credited between the start of `first_epoch` and
+/// the start of `end_epoch`. The rewards for an epoch are credited as the
+/// next one begins.
+pub fn epochs_credited_between(
+    first_epoch: Epoch,
+    end_epoch: Epoch,
+) -> Option<RangeInclusive<Epoch>> {
+    let start = first_epoch.saturating_sub(1);
+    let end = end_epoch.checked_sub(2)?;
+    (start <= end).then_some(start..=end)
+}
+
+/// Block time of the first block at or after `first_slot`, which may itself
+/// have been skipped
+async fn fetch_first_block_time(
+    ctx: &ScillaContext,
+    first_slot: Slot,
+) -> anyhow::Result<Option<UnixTimestamp>> {
+    let blocks = ctx.rpc().get_blocks_with_limit(first_slot, 1).await?;
+    match blocks.first() {
+        Some(slot) => Ok(Some(ctx.rpc().get_block_time(*slot).await?)),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_annualized_return() {
+        // 0.1% per two-day epoch, compounded simply over a year
+        let epoch_seconds = 2 * 86_400;
+        let apr = annualized_return(1_000, 1_001_000, epoch_seconds).unwrap();
+        assert!((apr - 0.1 * 365.0 / 2.0).abs() < 1e-9);
+
+        // No balance before the reward
+        assert_eq!(annualized_return(1_000, 1_000, epoch_seconds), None);
+        assert_eq!(annualized_return(1_000, 500, epoch_seconds), None);
+    }
+
+    #[test]
+    fn test_epochs_credited_between() {
+        // Epochs 10 to 14 started in the period, so the rewards of epochs 9 to
+        // 13 were credited in it
+        assert_eq!(epochs_credited_between(10, 15), Some(9..=13));
+        // Rewards for epoch 0 are credited as epoch 1 starts
+        assert_eq!(epochs_credited_between(0, 2), Some(0..=0));
+        assert_eq!(epochs_credited_between(1, 2), Some(0..=0));
+        // No epoch started in the period
+        assert_eq!(epochs_credited_between(10, 10), None);
+        assert_eq!(epochs_credited_between(0, 1), None);
+    }
+}
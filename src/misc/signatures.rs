@@ -0,0 +1,336 @@
+use {
+    crate::{
+        misc::helpers::{bincode_deserialize, decode_base58, decode_base64},
+        output::CommandOutput,
+    },
+    anyhow::{Context, anyhow, bail},
+    base64::Engine,
+    comfy_table::{Attribute, Cell, Table, presets::UTF8_FULL},
+    console::style,
+    serde::Serialize,
+    solana_keypair::Signer,
+    solana_pubkey::Pubkey,
+    solana_signature::Signature,
+    solana_transaction::versioned::VersionedTransaction,
+    solana_transaction_status::UiTransactionEncoding,
+    std::{fs, path::Path, str::FromStr},
+};
+
+/// A `PUBKEY=SIGNATURE` pair produced by a signer on another machine.
+#[derive(Debug, Clone, Copy)]
+pub struct SignerSignature {
+    pub pubkey: Pubkey,
+    pub signature: Signature,
+}
+
+impl FromStr for SignerSignature {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (pubkey, signature) = s
+            .trim()
+            .split_once('=')
+            .ok_or_else(|| anyhow!("Invalid signer {s}. Expected PUBKEY=SIGNATURE"))?;
+
+        Ok(SignerSignature {
+            pubkey: pubkey
+                .parse()
+                .map_err(|_| anyhow!("Invalid signer pubkey: {pubkey}"))?,
+            signature: signature
+                .parse()
+                .map_err(|_| anyhow!("Invalid signature: {signature}"))?,
+        })
+    }
+}
+
+pub fn decode_transaction(
+    encoding: UiTransactionEncoding,
+    encoded_tx: &str,
+) -> anyhow::Result<VersionedTransaction> {
+    let tx_bytes = match encoding {
+        UiTransactionEncoding::Base64 => decode_base64(encoded_tx)?,
+        UiTransactionEncoding::Base58 => decode_base58(encoded_tx)?,
+        _ => bail!("The available encoding options are Base64 and Base58"),
+    };
+
+    bincode_deserialize(&tx_bytes, "encoded transaction to VersionedTransaction")
+}
+
+pub fn encode_transaction(tx: &VersionedTransaction) -> anyhow::Result<String> {
+    let tx_bytes = bincode::serialize(tx).context("Failed to serialize transaction")?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(tx_bytes))
+}
+
+/// Accounts whose signatures the transaction requires, in signature order
+pub fn required_signers(tx: &VersionedTransaction) -> &[Pubkey] {
+    let num_required = tx.message.header().num_required_signatures as usize;
+    let keys = tx.message.static_account_keys();
+    &keys[..num_required.min(keys.len())]
+}
+
+/// Required signers whose signature slot is still empty
+pub fn missing_signers(tx: &VersionedTransaction) -> Vec<Pubkey> {
+    required_signers(tx)
+        .iter()
+        .zip(&tx.signatures)
+        .filter(|(_, signature)| **signature == Signature::default())
+        .map(|(pubkey, _)| *pubkey)
+        .collect()
+}
+
+/// Comma separated list of signers for messages
+pub fn format_signers(signers: &[Pubkey]) -> String {
+    signers
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Signs the transaction message with `signer` and stores the signature in
+/// its slot.
+pub fn sign_transaction(tx: &mut VersionedTransaction, signer: &dyn Signer) -> anyhow::Result<()> {
+    let pubkey = signer.pubkey();
+    if !required_signers(tx).contains(&pubkey) {
+        bail!(
+            "{pubkey} is not a required signer of this transaction. Required signers: {}",
+            format_signers(required_signers(tx))
+        );
+    }
+
+    let signature = signer.try_sign_message(&tx.message.serialize())?;
+    add_signature(tx, &pubkey, signature)
+}
+
+/// Verifies `signature` against the transaction message and stores it in
+/// the slot of `pubkey`.
+pub fn add_signature(
+    tx: &mut VersionedTransaction,
+    pubkey: &Pubkey,
+    signature: Signature,
+) -> anyhow::Result<()> {
+    let position = required_signers(tx)
+        .iter()
+        .position(|signer| signer == pubkey)
+        .ok_or_else(|| anyhow!("{pubkey} is not a required signer of this transaction"))?;
+
+    if !signature.verify(pubkey.as_ref(), &tx.message.serialize()) {
+        bail!("Signature {signature} from {pubkey} does not match this transaction");
+    }
+
+    if tx.signatures.len() < required_signers(tx).len() {
+        tx.signatures
+            .resize(required_signers(tx).len(), Signature::default());
+    }
+    tx.signatures[position] = signature;
+
+    Ok(())
+}
+
+/// Copies every signature present in `other` into `tx`. Both must carry the
+/// same message.
+pub fn merge_signatures(
+    tx: &mut VersionedTransaction,
+    other: &VersionedTransaction,
+) -> anyhow::Result<()> {
+    if tx.message != other.message {
+        bail!("Cannot merge signatures of transactions with different messages");
+    }
+
+    let signed: Vec<_> = required_signers(other)
+        .iter()
+        .zip(&other.signatures)
+        .filter(|(_, signature)| **signature != Signature::default())
+        .map(|(pubkey, signature)| (*pubkey, *signature))
+        .collect();
+
+    for (pubkey, signature) in signed {
+        add_signature(tx, &pubkey, signature)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+pub struct SignerStatusOutput {
+    pub pubkey: String,
+    /// `None` while the signer hasn't signed yet
+    pub signature: Option<String>,
+}
+
+/// A signed (possibly only partially) transaction ready to be passed on to
+/// the remaining signers or submitted.
+#[derive(Debug, Serialize)]
+pub struct SignedTransactionOutput {
+    pub blockhash: String,
+    pub signers: Vec<SignerStatusOutput>,
+    pub complete: bool,
+    /// Base64 encoded `VersionedTransaction`
+    pub transaction: String,
+    pub exported_to: Option<String>,
+}
+
+impl SignedTransactionOutput {
+    pub fn new(tx: &VersionedTransaction) -> anyhow::Result<Self> {
+        let signers: Vec<_> = required_signers(tx)
+            .iter()
+            .enumerate()
+            .map(|(i, pubkey)| SignerStatusOutput {
+                pubkey: pubkey.to_string(),
+                signature: tx
+                    .signatures
+                    .get(i)
+                    .filter(|signature| **signature != Signature::default())
+                    .map(ToString::to_string),
+            })
+            .collect();
+
+        Ok(SignedTransactionOutput {
+            blockhash: tx.message.recent_blockhash().to_string(),
+            complete: signers.iter().all(|signer| signer.signature.is_some()),
+            signers,
+            transaction: encode_transaction(tx)?,
+            exported_to: None,
+        })
+    }
+
+    /// Writes the encoded transaction to `path`
+    pub fn export(&mut self, path: &Path) -> anyhow::Result<()> {
+        fs::write(path, &self.transaction)
+            .with_context(|| format!("Failed to write transaction to {}", path.display()))?;
+        self.exported_to = Some(path.display().to_string());
+        Ok(())
+    }
+}
+
+impl CommandOutput for SignedTransactionOutput {
+    fn print_table(&self) {
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL).set_header(vec![
+            Cell::new("Signer").add_attribute(Attribute::Bold),
+            Cell::new("Signature").add_attribute(Attribute::Bold),
+        ]);
+        for signer in &self.signers {
+            table.add_row(vec![
+                Cell::new(&signer.pubkey),
+                Cell::new(match &signer.signature {
+                    Some(signature) => style(signature).green().to_string(),
+                    None => style("missing").red().to_string(),
+                }),
+            ]);
+        }
+
+        println!("\n{}", style("SIGNED TRANSACTION").green().bold());
+        println!("{} {}", style("Blockhash:").cyan(), self.blockhash);
+        println!("{table}");
+
+        let collected: Vec<_> = self
+            .signers
+            .iter()
+            .filter_map(|signer| {
+                let signature = signer.signature.as_ref()?;
+                Some(format!("  --signer {}={signature}", signer.pubkey))
+            })
+            .collect();
+        if !collected.is_empty() {
+            println!("{}", style("Signatures:").cyan());
+            println!("{}", collected.join("\n"));
+        }
+
+        if self.complete {
+            println!("{}", style("All required signatures are present").green());
+        } else {
+            println!(
+                "{}",
+                style("Transaction still needs signatures from the missing signers").yellow()
+            );
+        }
+
+        println!("{}", style("Transaction (base64):").cyan());
+        println!("{}", self.transaction);
+        if let Some(path) = &self.exported_to {
+            println!("{} {path}", style("Saved to:").cyan());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, solana_keypair::Keypair, solana_message::Message,
+        solana_system_interface::instruction::transfer, solana_transaction::Transaction,
+    };
+
+    /// Unsigned transfer from `from` paid for by `fee_payer`
+    fn unsigned_transfer(
+        fee_payer: &Keypair,
+        from: &Keypair,
+        lamports: u64,
+    ) -> VersionedTransaction {
+        let instruction = transfer(&from.pubkey(), &Pubkey::new_unique(), lamports);
+        let message = Message::new(&[instruction], Some(&fee_payer.pubkey()));
+        VersionedTransaction::from(Transaction::new_unsigned(message))
+    }
+
+    fn sign(tx: &VersionedTransaction, signer: &Keypair) -> Signature {
+        signer.sign_message(&tx.message.serialize())
+    }
+
+    #[test]
+    fn test_add_signature() {
+        let fee_payer = Keypair::new();
+        let from = Keypair::new();
+        let mut tx = unsigned_transfer(&fee_payer, &from, 1);
+
+        let outsider = Keypair::new();
+        let signature = sign(&tx, &outsider);
+        let err = add_signature(&mut tx, &outsider.pubkey(), signature).unwrap_err();
+        assert!(err.to_string().contains("is not a required signer"));
+
+        // A signature over another message doesn't verify
+        let other = unsigned_transfer(&fee_payer, &from, 2);
+        let err = add_signature(&mut tx, &from.pubkey(), sign(&other, &from)).unwrap_err();
+        assert!(err.to_string().contains("does not match this transaction"));
+        assert_eq!(
+            missing_signers(&tx),
+            vec![fee_payer.pubkey(), from.pubkey()]
+        );
+
+        // A transaction decoded with too few signature slots is padded
+        tx.signatures.clear();
+        let signature = sign(&tx, &from);
+        add_signature(&mut tx, &from.pubkey(), signature).unwrap();
+        assert_eq!(tx.signatures, vec![Signature::default(), signature]);
+    }
+
+    #[test]
+    fn test_merge_signatures() {
+        let fee_payer = Keypair::new();
+        let from = Keypair::new();
+        let unsigned = unsigned_transfer(&fee_payer, &from, 1);
+        let mut tx = unsigned.clone();
+
+        let mut partial = tx.clone();
+        sign_transaction(&mut partial, &from).unwrap();
+        merge_signatures(&mut tx, &partial).unwrap();
+        assert_eq!(missing_signers(&tx), vec![fee_payer.pubkey()]);
+
+        sign_transaction(&mut tx, &fee_payer).unwrap();
+        assert!(missing_signers(&tx).is_empty());
+
+        let other = unsigned_transfer(&fee_payer, &from, 2);
+        let err = merge_signatures(&mut tx, &other).unwrap_err();
+        assert!(err.to_string().contains("different messages"));
+
+        // Same message, but the signature was made over another one
+        let mut forged = unsigned.clone();
+        forged.signatures[1] = sign(&other, &from);
+        let mut target = unsigned;
+        let err = merge_signatures(&mut target, &forged).unwrap_err();
+        assert!(err.to_string().contains("does not match this transaction"));
+        assert_eq!(
+            missing_signers(&target),
+            vec![fee_payer.pubkey(), from.pubkey()]
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use {
+    crate::{
+        ScillaContext,
+        constants::LAMPORTS_PER_SOL,
+        misc::helpers::lamports_to_sol,
+        ui::{print_status, suspend_spinner},
+    },
+    anyhow::bail,
+    comfy_table::{Attribute, Cell, Table, presets::UTF8_FULL},
+    console::style,
+    inquire::Confirm,
+    serde::Serialize,
+    solana_account_decoder_client_types::UiAccountEncoding,
+    solana_message::VersionedMessage,
+    solana_rpc_client_api::config::{
+        RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig,
+    },
+    solana_transaction::versioned::VersionedTransaction,
+};
+
+/// Outcome of simulating a signed transaction against the current bank.
+#[derive(Debug, Serialize)]
+pub struct TransactionPreview {
+    pub err: Option<String>,
+    pub units_consumed: Option<u64>,
+    pub fee: u64,
+    pub balance_changes: Vec<BalanceChange>,
+    pub logs: Vec<String>,
+}
+
+/// Projected SOL balance change of a writable account.
+#[derive(Debug, Serialize)]
+pub struct BalanceChange {
+    pub address: String,
+    pub pre_lamports: u64,
+    pub post_lamports: u64,
+}
+
+impl BalanceChange {
+    pub fn delta(&self) -> i128 {
+        self.post_lamports as i128 - self.pre_lamports as i128
+    }
+}
+
+/// Fee the cluster charges for `message`, including its priority fee
+pub async fn fee_for_message(
+    ctx: &ScillaContext,
+    message: &VersionedMessage,
+) -> anyhow::Result<u64> {
+    let fee = match message {
+        VersionedMessage::Legacy(message) => ctx.rpc().get_fee_for_message(message).await?,
+        VersionedMessage::V0(message) => ctx.rpc().get_fee_for_message(message).await?,
+    };
+    Ok(fee)
+}
+
+/// Simulates `tx` and collects its logs, compute units, fee and the projected
+/// balances of every writable account.
+pub async fn simulate_transaction(
+    ctx: &ScillaContext,
+    tx: &VersionedTransaction,
+) -> anyhow::Result<TransactionPreview> {
+    let message = &tx.message;
+    let writable: Vec<_> = message
+        .static_account_keys()
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| message.is_maybe_writable(*i, None))
+        .map(|(_, key)| *key)
+        .collect();
+
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: true,
+        commitment: Some(ctx.rpc().commitment()),
+        accounts: Some(RpcSimulateTransactionAccountsConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            addresses: writable.iter().map(ToString::to_string).collect(),
+        }),
+        ..Default::default()
+    };
+
+    let (pre_accounts, simulation, fee) = tokio::try_join!(
+        async { Ok(ctx.rpc().get_multiple_accounts(&writable).await?) },
+        async {
+            Ok(ctx
+                .rpc()
+                .simulate_transaction_with_config(tx, config)
+                .await?)
+        },
+        fee_for_message(ctx, message),
+    )?;
+    let result = simulation.value;
+
+    let post_accounts = result.accounts.unwrap_or_default();
+    let balance_changes = writable
+        .iter()
+        .enumerate()
+        .map(|(i, address)| {
+            let pre_lamports = pre_accounts
+                .get(i)
+                .and_then(Option::as_ref)
+                .map_or(0, |account| account.lamports);
+            let post_lamports = post_accounts
+                .get(i)
+                .and_then(Option::as_ref)
+                .map_or(pre_lamports, |account| account.lamports);
+            BalanceChange {
+                address: address.to_string(),
+                pre_lamports,
+                post_lamports,
+            }
+        })
+        .collect();
+
+    Ok(TransactionPreview {
+        err: result.err.map(|err| err.to_string()),
+        units_consumed: result.units_consumed,
+        fee,
+        balance_changes,
+        logs: result.logs.unwrap_or_default(),
+    })
+}
+
+impl TransactionPreview {
+    fn render(&self) -> String {
+        let mut summary = Table::new();
+        summary
+            .load_preset(UTF8_FULL)
+            .set_header(vec![
+                Cell::new("Simulation").add_attribute(Attribute::Bold),
+                Cell::new("Value").add_attribute(Attribute::Bold),
+            ])
+            .add_row(vec![
+                Cell::new("Result"),
+                Cell::new(match &self.err {
+                    Some(err) => style(format!("Failed: {err}")).red().to_string(),
+                    None => style("Success").green().to_string(),
+                }),
+            ])
+            .add_row(vec![
+                Cell::new("Compute Units Consumed"),
+                Cell::new(
+                    self.units_consumed
+                        .map_or_else(|| "unknown".to_string(), |units| units.to_string()),
+                ),
+            ])
+            .add_row(vec![
+                Cell::new("Fee (SOL)"),
+                Cell::new(format!("{}", lamports_to_sol(self.fee))),
+            ]);
+
+        let mut balances = Table::new();
+        balances.load_preset(UTF8_FULL).set_header(vec![
+            Cell::new("Writable Account").add_attribute(Attribute::Bold),
+            Cell::new("Before (SOL)").add_attribute(Attribute::Bold),
+            Cell::new("After (SOL)").add_attribute(Attribute::Bold),
+            Cell::new("Change (SOL)").add_attribute(Attribute::Bold),
+        ]);
+        for change in &self.balance_changes {
+            let delta = change.delta();
+            let delta_sol = delta.unsigned_abs() as f64 / LAMPORTS_PER_SOL as f64;
+            let delta = match delta.signum() {
+                1 => style(format!("+{delta_sol}")).green().to_string(),
+                -1 => style(format!("-{delta_sol}")).red().to_string(),
+                _ => "0".to_string(),
+            };
+            balances.add_row(vec![
+                Cell::new(&change.address),
+                Cell::new(format!("{}", lamports_to_sol(change.pre_lamports))),
+                Cell::new(format!("{}", lamports_to_sol(change.post_lamports))),
+                Cell::new(delta),
+            ]);
+        }
+
+        let mut rendered = format!(
+            "\n{}\n{summary}\n{balances}",
+            style("TRANSACTION PREVIEW").green().bold()
+        );
+        if !self.logs.is_empty() {
+            rendered.push_str(&format!("\n{}", style("Program logs:").bold()));
+            for log in &self.logs {
+                rendered.push_str(&format!("\n  {}", style(log).dim()));
+            }
+        }
+        rendered
+    }
+
+    /// Prints the preview ahead of the command result.
+    pub fn print(&self, ctx: &ScillaContext) {
+        let rendered = self.render();
+        print_status(ctx, rendered);
+    }
+}
+
+/// Simulates `tx`, shows the preview and asks whether to go ahead with it.
+/// Bails if the simulation fails or the user declines.
+pub async fn preview_and_confirm(
+    ctx: &ScillaContext,
+    tx: &VersionedTransaction,
+) -> anyhow::Result<()> {
+    let preview = simulate_transaction(ctx, tx).await?;
+    preview.print(ctx);
+
+    if let Some(err) = &preview.err {
+        bail!("Transaction simulation failed: {err}");
+    }
+
+    if ctx.skip_confirmation() {
+        return Ok(());
+    }
+
+    let confirmed = suspend_spinner(|| {
+        Confirm::new("Send this transaction?")
+            .with_default(false)
+            .prompt()
+    });
+    match confirmed {
+        Ok(true) => Ok(()),
+        Ok(false) => bail!("Transaction aborted"),
+        Err(err) => bail!("Unable to confirm transaction: {err}"),
+    }
+}
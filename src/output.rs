@@ -0,0 +1,44 @@
+use {clap::ValueEnum, serde::Serialize, std::fmt};
+
+/// How command results are written to stdout
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable tables
+    #[default]
+    Table,
+    /// Pretty-printed JSON
+    Json,
+    /// Single-line JSON
+    JsonCompact,
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let format = match self {
+            OutputFormat::Table => "table",
+            OutputFormat::Json => "json",
+            OutputFormat::JsonCompact => "json-compact",
+        };
+        write!(f, "{format}")
+    }
+}
+
+impl OutputFormat {
+    pub fn is_json(&self) -> bool {
+        !matches!(self, OutputFormat::Table)
+    }
+
+    pub fn render<T: CommandOutput>(&self, output: &T) -> anyhow::Result<()> {
+        match self {
+            OutputFormat::Table => output.print_table(),
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(output)?),
+            OutputFormat::JsonCompact => println!("{}", serde_json::to_string(output)?),
+        }
+        Ok(())
+    }
+}
+
+/// Typed result of a command, rendered either as tables or as JSON
+pub trait CommandOutput: Serialize {
+    fn print_table(&self);
+}
@@ -64,14 +64,29 @@ fn prompt_stake() -> anyhow::Result<StakeCommand> {
     let choice = Select::new(
         "Stake Command:",
         vec![
-            StakeCommand::Create,
-            StakeCommand::Delegate,
-            StakeCommand::Deactivate,
-            StakeCommand::Withdraw,
-            StakeCommand::Merge,
-            StakeCommand::Split,
-            StakeCommand::Show,
-            StakeCommand::History,
+            StakeCommand::Create(Default::default()),
+            StakeCommand::Delegate(Default::default()),
+            StakeCommand::Deactivate(Default::default()),
+            StakeCommand::BulkDeactivate(Default::default()),
+            StakeCommand::Withdraw(Default::default()),
+            StakeCommand::BulkWithdraw(Default::default()),
+            StakeCommand::DeactivateDelinquent(Default::default()),
+            StakeCommand::Merge(Default::default()),
+            StakeCommand::MergeAll(Default::default()),
+            StakeCommand::Split(Default::default()),
+            StakeCommand::SplitParts(Default::default()),
+            StakeCommand::MoveStake(Default::default()),
+            StakeCommand::MoveLamports(Default::default()),
+            StakeCommand::Show(Default::default()),
+            StakeCommand::DerivedAddresses(Default::default()),
+            StakeCommand::Rewards(Default::default()),
+            StakeCommand::IncomeReport(Default::default()),
+            StakeCommand::List(Default::default()),
+            StakeCommand::Authorize(Default::default()),
+            StakeCommand::SetLockup(Default::default()),
+            StakeCommand::ShowLockup(Default::default()),
+            StakeCommand::Eta(Default::default()),
+            StakeCommand::History(Default::default()),
             StakeCommand::GoBack,
         ],
     )
@@ -84,12 +99,17 @@ fn prompt_account() -> anyhow::Result<AccountCommand> {
     let choice = Select::new(
         "Account Command:",
         vec![
-            AccountCommand::FetchAccount,
-            AccountCommand::Balance,
-            AccountCommand::Transfer,
+            AccountCommand::FetchAccount(Default::default()),
+            AccountCommand::Balance(Default::default()),
+            AccountCommand::Transfer(Default::default()),
+            AccountCommand::BatchTransfer(Default::default()),
             AccountCommand::Airdrop,
-            AccountCommand::LargestAccounts,
-            AccountCommand::NonceAccount,
+            AccountCommand::LargestAccounts(Default::default()),
+            AccountCommand::NonceAccount(Default::default()),
+            AccountCommand::CreateNonceAccount(Default::default()),
+            AccountCommand::AdvanceNonceAccount(Default::default()),
+            AccountCommand::AuthorizeNonceAccount(Default::default()),
+            AccountCommand::WithdrawFromNonceAccount(Default::default()),
             AccountCommand::GoBack,
         ],
     )
@@ -102,11 +122,11 @@ fn prompt_vote() -> anyhow::Result<VoteCommand> {
     let choice = Select::new(
         "Vote Command:",
         vec![
-            VoteCommand::CreateVoteAccount,
-            VoteCommand::AuthorizeVoter,
-            VoteCommand::WithdrawFromVoteAccount,
-            VoteCommand::ShowVoteAccount,
-            VoteCommand::CloseVoteAccount,
+            VoteCommand::CreateVoteAccount(Default::default()),
+            VoteCommand::AuthorizeVoter(Default::default()),
+            VoteCommand::WithdrawFromVoteAccount(Default::default()),
+            VoteCommand::ShowVoteAccount(Default::default()),
+            VoteCommand::CloseVoteAccount(Default::default()),
             VoteCommand::GoBack,
         ],
     )
@@ -119,10 +139,11 @@ fn prompt_transaction() -> anyhow::Result<TransactionCommand> {
     let choice = Select::new(
         "Transaction Command:",
         vec![
-            TransactionCommand::CheckConfirmation,
-            TransactionCommand::FetchStatus,
-            TransactionCommand::FetchTransaction,
-            TransactionCommand::SendTransaction,
+            TransactionCommand::CheckConfirmation(Default::default()),
+            TransactionCommand::FetchStatus(Default::default()),
+            TransactionCommand::FetchTransaction(Default::default()),
+            TransactionCommand::SendTransaction(Default::default()),
+            TransactionCommand::SignTransaction(Default::default()),
             TransactionCommand::GoBack,
         ],
     )
@@ -136,7 +157,7 @@ fn prompt_config() -> anyhow::Result<ConfigCommand> {
         "ScillaConfig Command:",
         vec![
             ConfigCommand::Show,
-            ConfigCommand::Edit,
+            ConfigCommand::Edit(Default::default()),
             ConfigCommand::GoBack,
         ],
     )
@@ -172,6 +193,41 @@ where
     }
 }
 
+/// Like [`prompt_input_data`], but an empty answer yields `None`.
+pub fn prompt_optional_input_data<T>(msg: &str) -> Option<T>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    loop {
+        let input = match Text::new(msg)
+            .with_help_message("Leave empty to skip")
+            .prompt()
+        {
+            Ok(v) => v,
+            Err(e) => match e {
+                InquireError::OperationInterrupted | InquireError::OperationCanceled => {
+                    println!("{}", style("Operation cancelled. Exiting.").yellow().bold());
+                    exit(0);
+                }
+                _ => {
+                    print_error(format!("Invalid input: {e}. Please try again."));
+                    continue;
+                }
+            },
+        };
+
+        let input = input.trim();
+        if input.is_empty() {
+            return None;
+        }
+        match input.parse::<T>() {
+            Ok(value) => return Some(value),
+            Err(e) => print_error(format!("Parse error : {e}. Please try again.")),
+        }
+    }
+}
+
 pub fn prompt_select_data<T>(msg: &str, options: Vec<T>) -> T
 where
     T: Display + Clone,
@@ -1,9 +1,15 @@
 use {
+    crate::{context::ScillaContext, error::ReportedError},
     console::style,
     indicatif::{ProgressBar, ProgressStyle},
+    std::sync::Mutex,
 };
 
-pub async fn show_spinner<F, T>(message: &str, fut: F)
+/// Spinner of the command currently running, so prompts raised deep inside a
+/// command can temporarily hide it.
+static ACTIVE_SPINNER: Mutex<Option<ProgressBar>> = Mutex::new(None);
+
+pub async fn show_spinner<F, T>(message: &str, fut: F) -> anyhow::Result<T>
 where
     F: std::future::Future<Output = anyhow::Result<T>>,
 {
@@ -15,17 +21,63 @@ where
     );
     spinner.enable_steady_tick(std::time::Duration::from_millis(100));
     spinner.set_message(message.to_string());
+    *ACTIVE_SPINNER.lock().expect("spinner lock poisoned") = Some(spinner.clone());
 
     let result = fut.await;
 
+    ACTIVE_SPINNER.lock().expect("spinner lock poisoned").take();
+
     match &result {
         Ok(_) => spinner.finish_with_message("✅ Done"),
+        Err(e) if spinner.is_hidden() => {
+            // Not attached to a terminal, so the spinner never renders
+            eprintln!("{}", style(format!("Error : {}", e)).red().bold());
+        }
         Err(e) => {
             spinner.finish_with_message(format!("{}", style(format!("Error : {}", e)).red().bold()))
         }
     }
+
+    result.map_err(|err| ReportedError(err).into())
 }
 
+/// Errors go to stderr so stdout only carries command results, which
+/// matters with JSON output.
 pub fn print_error(message: impl std::fmt::Display) {
-    println!("{}", style(message).red().bold());
+    eprintln!("{}", style(message).red().bold());
+}
+
+/// Prints `err` and marks it as reported, for a command that fails after
+/// its spinner has finished.
+pub fn report_error(err: anyhow::Error) -> anyhow::Error {
+    print_error(&err);
+    ReportedError(err).into()
+}
+
+/// Prints progress, a preview or a plan for the user. With JSON output it
+/// goes to stderr instead, keeping stdout clean for the command result.
+pub fn print_status(ctx: &ScillaContext, message: impl std::fmt::Display) {
+    suspend_spinner(|| {
+        if ctx.output_format().is_json() {
+            eprintln!("{message}");
+        } else {
+            println!("{message}");
+        }
+    });
+}
+
+/// Runs `f` with the active spinner (if any) hidden, so that interactive
+/// prompts and multi-line output don't get overdrawn.
+pub fn suspend_spinner<F, R>(f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let spinner = ACTIVE_SPINNER
+        .lock()
+        .expect("spinner lock poisoned")
+        .clone();
+    match spinner {
+        Some(spinner) => spinner.suspend(f),
+        None => f(),
+    }
 }